@@ -1,7 +1,5 @@
 use std::fs;
 use std::io::{self, Write};
-use std::thread;
-use std::time;
 
 use musiqcore::datastore::jsonbacked::tracker;
 
@@ -27,10 +25,15 @@ pub fn entry_point() -> Result<(), Error> {
     println!("got library");
 
     let musicbrainz_artist_cache = cache::MusicBrainzCacheInterface::new();
+    let musicbrainz_client = musicbrainz::Client::new();
 
     println!("iterating over artists");
     for artist in library.artists.values() {
-        grab_raw_musicbrainz_data(&musicbrainz_artist_cache, &artist.artist_info);
+        grab_raw_musicbrainz_data(
+            &musicbrainz_client,
+            &musicbrainz_artist_cache,
+            &artist.artist_info,
+        );
 
         select_singular_artist_match(&musicbrainz_artist_cache, &artist.artist_info);
     }
@@ -115,6 +118,32 @@ pub fn entry_point() -> Result<(), Error> {
         println!("{} had no match", no_match.artist_info.artist_name);
     }
 
+    println!("iterating over albums");
+    let musicbrainz_album_cache = cache::MusicBrainzAlbumCacheInterface::new();
+    for artist in library.artists.values() {
+        for album in artist.albums.values() {
+            grab_raw_musicbrainz_album_data(
+                &musicbrainz_client,
+                &musicbrainz_album_cache,
+                &artist.artist_info,
+                &album.album_info,
+            );
+
+            select_singular_album_match(
+                &musicbrainz_album_cache,
+                &artist.artist_info,
+                &album.album_info,
+            );
+
+            approve_exact_album_match(
+                &musicbrainz_client,
+                &musicbrainz_album_cache,
+                &artist.artist_info,
+                &album.album_info,
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -183,6 +212,7 @@ fn prompt_for_close_enough_match(
 }
 
 pub fn grab_raw_musicbrainz_data(
+    musicbrainz_client: &musicbrainz::Client,
     musicbrainz_artist_cache: &cache::MusicBrainzCacheInterface,
     artist_info: &musiqlibrary::ArtistInfo,
 ) {
@@ -200,18 +230,22 @@ pub fn grab_raw_musicbrainz_data(
             "querying musicbrainz for artist: {}",
             artist_info.artist_name,
         );
-        let json_str = musicbrainz::query_for_artist_raw(artist_info.artist_name.clone());
-
-        println!("writing json for: {}", artist_info.artist_name);
-        musicbrainz_artist_cache
-            .write_musicbrainz_artist_cache_file(artist_info.artist_name.clone(), json_str);
-
-        println!(
-            "done querying musicbrainz for artist: {}",
-            artist_info.artist_name
-        );
-
-        thread::sleep(time::Duration::new(2, 0))
+        match musicbrainz_client.query_for_artist_raw(artist_info.artist_name.clone()) {
+            Ok(json_str) => {
+                println!("writing json for: {}", artist_info.artist_name);
+                musicbrainz_artist_cache
+                    .write_musicbrainz_artist_cache_file(artist_info.artist_name.clone(), json_str);
+
+                println!(
+                    "done querying musicbrainz for artist: {}",
+                    artist_info.artist_name
+                );
+            }
+            Err(e) => println!(
+                "could not query musicbrainz for artist: {} ({})",
+                artist_info.artist_name, e
+            ),
+        }
     }
 }
 
@@ -228,6 +262,11 @@ pub fn select_singular_artist_match(
             "matched single musicbrainz entry does exist for artist: {}",
             artist_info.artist_name,
         );
+    } else if !musicbrainz_artist_cache.raw_exists(artist_info.artist_name.clone()) {
+        println!(
+            "no musicbrainz results were fetched for artist: {}",
+            artist_info.artist_name,
+        );
     } else {
         println!(
             "finding single musicbrainz match for artist: {}",
@@ -266,6 +305,155 @@ pub fn select_singular_artist_match(
     }
 }
 
+pub fn grab_raw_musicbrainz_album_data(
+    musicbrainz_client: &musicbrainz::Client,
+    musicbrainz_album_cache: &cache::MusicBrainzAlbumCacheInterface,
+    artist_info: &musiqlibrary::ArtistInfo,
+    album_info: &musiqlibrary::AlbumInfo,
+) {
+    if musicbrainz_album_cache.raw_exists(
+        artist_info.artist_name.clone(),
+        album_info.album_name.clone(),
+    ) {
+        println!(
+            "cached musicbrainz data does exist for {} - {}",
+            artist_info.artist_name, album_info.album_name,
+        );
+    } else {
+        println!(
+            "querying musicbrainz for album: {} - {}",
+            artist_info.artist_name, album_info.album_name,
+        );
+        match musicbrainz_client.query_for_release_raw(
+            artist_info.artist_name.clone(),
+            album_info.album_name.clone(),
+        ) {
+            Ok(json_str) => musicbrainz_album_cache.write_musicbrainz_album_cache_file(
+                artist_info.artist_name.clone(),
+                album_info.album_name.clone(),
+                json_str,
+            ),
+            Err(e) => println!(
+                "could not query musicbrainz for album: {} - {} ({})",
+                artist_info.artist_name, album_info.album_name, e
+            ),
+        }
+    }
+}
+
+pub fn select_singular_album_match(
+    musicbrainz_album_cache: &cache::MusicBrainzAlbumCacheInterface,
+    artist_info: &musiqlibrary::ArtistInfo,
+    album_info: &musiqlibrary::AlbumInfo,
+) {
+    if musicbrainz_album_cache.match_exists(
+        artist_info.artist_name.clone(),
+        album_info.album_name.clone(),
+    ) {
+        println!(
+            "matched single musicbrainz entry does exist for album: {} - {}",
+            artist_info.artist_name, album_info.album_name,
+        );
+        return;
+    }
+
+    if !musicbrainz_album_cache.raw_exists(
+        artist_info.artist_name.clone(),
+        album_info.album_name.clone(),
+    ) {
+        return;
+    }
+
+    let json_str = musicbrainz_album_cache.read_musicbrainz_album_cache_file(
+        artist_info.artist_name.clone(),
+        album_info.album_name.clone(),
+    );
+
+    let release_results = musicbrainz::ReleaseListResult::from_json(json_str);
+
+    // many releases of one album score 100, so only keep the first of each release group
+    let mut found_100_matches: Vec<musicbrainz::Release> = Vec::new();
+    for release in release_results.releases.into_iter() {
+        if release.score != Some(100) {
+            continue;
+        }
+        let release_group_id = release.release_group.as_ref().map(|x| x.id.clone());
+        if !found_100_matches
+            .iter()
+            .any(|found| found.release_group.as_ref().map(|x| x.id.clone()) == release_group_id)
+        {
+            found_100_matches.push(release);
+        }
+    }
+
+    match found_100_matches.as_slice() {
+        [single_match] => musicbrainz_album_cache.write_musicbrainz_album_match_file(
+            artist_info.artist_name.clone(),
+            album_info.album_name.clone(),
+            single_match,
+        ),
+        [] => println!(
+            "found no 100 matches for this album: {} - {}",
+            artist_info.artist_name, album_info.album_name
+        ),
+        _ => println!(
+            "found multiple 100 matches for this album: {} - {}",
+            artist_info.artist_name, album_info.album_name
+        ),
+    };
+}
+
+/// Approve a matched release whose title is the album's name, looking up the full release
+pub fn approve_exact_album_match(
+    musicbrainz_client: &musicbrainz::Client,
+    musicbrainz_album_cache: &cache::MusicBrainzAlbumCacheInterface,
+    artist_info: &musiqlibrary::ArtistInfo,
+    album_info: &musiqlibrary::AlbumInfo,
+) {
+    if musicbrainz_album_cache.approved_exists(
+        artist_info.artist_name.clone(),
+        album_info.album_name.clone(),
+    ) {
+        return;
+    }
+
+    let release = match musicbrainz_album_cache.read_musicbrainz_album_match_file(
+        artist_info.artist_name.clone(),
+        album_info.album_name.clone(),
+    ) {
+        Some(release) => release,
+        None => return,
+    };
+
+    let distance = musiqcore::model::functions::levenshtein(
+        &release.title.to_lowercase(),
+        &album_info.album_name.to_lowercase(),
+    );
+    if distance != 0 {
+        println!(
+            "not approving \"{}\" for \"{}\" ({} apart)",
+            release.title, album_info.album_name, distance
+        );
+        return;
+    }
+
+    println!(
+        "looking up musicbrainz release for: {} - {}",
+        artist_info.artist_name, album_info.album_name,
+    );
+    match musicbrainz_client.lookup_release(release.id.clone()) {
+        Ok(full_release) => musicbrainz_album_cache.write_musicbrainz_album_approved_file(
+            artist_info.artist_name.clone(),
+            album_info.album_name.clone(),
+            &full_release,
+        ),
+        Err(e) => println!(
+            "could not look up musicbrainz release {} ({})",
+            release.id, e
+        ),
+    }
+}
+
 #[allow(unused)]
 pub fn sql_mode() -> Result<(), Error> {
     let config_state = musiqcore::model::app::AppConfigState::get_default();
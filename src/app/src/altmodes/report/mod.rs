@@ -1,15 +1,19 @@
-use std::collections::BTreeMap;
-
-use chrono::TimeZone;
-
 use musiqcore::datastore::jsonbacked;
 
+use crate::datastore::jsonbacked::prehistory;
 use crate::model;
 
 #[derive(Debug)]
-pub enum Error {}
+pub enum Error {
+    InvalidRange(String),
+}
+
+pub fn generate_report(maybe_range: Option<String>) -> Result<(), Error> {
+    let range = match maybe_range {
+        Some(range) => model::stats::StatsRange::parse(&range).map_err(Error::InvalidRange)?,
+        None => model::stats::StatsRange::this_year(),
+    };
 
-pub fn generate_year_end_report() -> Result<(), Error> {
     let config_state = musiqcore::model::app::AppConfigState::get_default();
 
     let lib_path = config_state.library_path.clone();
@@ -21,78 +25,79 @@ pub fn generate_year_end_report() -> Result<(), Error> {
         &None,
     );
 
-    let augmented = organized.map_into(&|track| {
-        let uniq_track_id = musiqlibrary::TrackUniqueIdentifier::from_track(&track);
-        let last_year_play_history = {
-            let all_history_play_history = read_only_tracker.get_track_history(&uniq_track_id);
-            match all_history_play_history {
-                Some(v) => v
-                    .iter()
-                    .filter(|x| {
-                        x >= &&chrono::Local
-                            .from_local_datetime(
-                                &chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
-                                    .unwrap()
-                                    .and_hms_opt(0, 0, 0)
-                                    .unwrap(),
-                            )
-                            .unwrap()
-                    })
-                    .collect(),
-                None => Vec::new(),
-            }
-        };
-
-        model::AugmentedTrack {
-            augmented: model::AugmentedData {
-                play_count: last_year_play_history.len(),
-                tagged_genres: Vec::new(),
-            },
-            metadata: track,
-        }
-    });
-
-    let mut most_listened_to_artists = BTreeMap::new();
-    let mut most_listened_to_albums = BTreeMap::new();
-    let mut most_listened_to_tracks = BTreeMap::new();
-
-    for artist in augmented.artists.values() {
-        for album in artist.albums.values() {
-            for disc in album.discs.values() {
-                for track in disc.tracks.values() {
-                    *most_listened_to_artists
-                        .entry(artist.artist_info.artist_name.clone())
-                        .or_insert(0) += track.augmented.play_count;
-
-                    *most_listened_to_albums
-                        .entry((
-                            artist.artist_info.artist_name.clone(),
-                            album.album_info.album_name.clone(),
-                        ))
-                        .or_insert(0) += track.augmented.play_count;
+    let prehistory_reporter = prehistory::Reporter::new(
+        &config_state.app_data_path.to_path_buf(),
+        &config_state.allowed_prehistory_files,
+    );
 
-                    *most_listened_to_tracks
-                        .entry((
-                            artist.artist_info.artist_name.clone(),
-                            album.album_info.album_name.clone(),
-                            track.metadata.title.clone(),
-                        ))
-                        .or_insert(0) += track.augmented.play_count;
-                }
-            }
-        }
-    }
+    let stats = model::stats::ListeningStats::compute(
+        organized.get_all_tracks(),
+        &read_only_tracker,
+        &prehistory_reporter,
+        range,
+    );
 
-    for (name, count) in most_listened_to_artists.iter() {
-        println!("{}\t{}", count, name);
+    println!("Listening report for {}", stats.range.display_text());
+    println!(
+        "{} plays\t{} seconds",
+        stats.play_count, stats.listened_seconds
+    );
+    println!("=========================");
+    for artist in stats.top_artists.iter() {
+        println!("{}\t{}", artist.play_count, artist.artist_name);
     }
     println!("|||||||||||||||||||||||||");
-    for ((artist, album), count) in most_listened_to_albums.iter() {
-        println!("{}\t{}\t{}", count, album, artist);
+    for album in stats.top_albums.iter() {
+        println!(
+            "{}\t{}\t{}",
+            album.play_count, album.album_name, album.artist_name
+        );
     }
     println!("+++++++++++++++++++++++++");
-    for ((artist, album, track), count) in most_listened_to_tracks.iter() {
-        println!("{}\t{}\t{}\t{}", count, track, album, artist);
+    for track in stats.top_tracks.iter() {
+        println!(
+            "{}\t{}\t{}\t{}",
+            track.play_count, track.track.title, track.track.album, track.track.album_artist
+        );
+    }
+    println!("#########################");
+    for genre in stats.top_genres.iter() {
+        println!("{}\t{}", genre.play_count, genre.genre);
+    }
+    println!("-------------------------");
+    for (month, seconds) in stats.listened_per_month.iter() {
+        println!("{}\t{}", month.format("%Y-%m"), seconds);
+    }
+    println!("-------------------------");
+    match stats.longest_streak {
+        Some((start, length)) => println!("longest streak\t{} days from {}", length, start),
+        None => println!("longest streak\tnone"),
+    };
+    println!("current streak\t{} days", stats.current_streak);
+    println!("*************************");
+    for discovery in stats.discoveries.iter() {
+        println!(
+            "{}\t{}\t{}\t{}",
+            discovery.first_played.format("%Y-%m-%d"),
+            discovery.track.title,
+            discovery.track.album,
+            discovery.track.album_artist
+        );
+    }
+    println!("~~~~~~~~~~~~~~~~~~~~~~~~~");
+    for (weekday, hours) in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+        .iter()
+        .zip(stats.time_of_day.iter())
+    {
+        println!(
+            "{}\t{}",
+            weekday,
+            hours
+                .iter()
+                .map(|count| count.to_string())
+                .collect::<Vec<String>>()
+                .join("\t")
+        );
     }
 
     Ok(())
@@ -118,6 +118,19 @@ pub fn run_forever(
                     shared::MprisCallbackMessage::Next => {
                         shared::PlaybackRequest::Next(shared::TrackLoadType::NaturalNext)
                     }
+                    shared::MprisCallbackMessage::SetLoopStatus(repeat_mode) => {
+                        // album repeat goes out to MPRIS as a playlist loop, so keep it when
+                        // that comes back
+                        match (repeat_mode, play_queue.repeat_mode) {
+                            (shared::RepeatMode::All, shared::RepeatMode::Album) => {
+                                shared::PlaybackRequest::SetRepeatMode(shared::RepeatMode::Album)
+                            }
+                            _ => shared::PlaybackRequest::SetRepeatMode(repeat_mode),
+                        }
+                    }
+                    shared::MprisCallbackMessage::SetShuffle(shuffle) => {
+                        shared::PlaybackRequest::SetShuffle(shuffle)
+                    }
                 };
 
                 playback::handle_playback_request(
@@ -1,4 +1,8 @@
+use std::collections::BTreeSet;
+
+use crate::model;
 use crate::shared;
+use crate::util::shuffle;
 
 pub fn handle_playback_request(
     play_queue: &mut shared::PlayQueueInfo,
@@ -9,52 +13,44 @@ pub fn handle_playback_request(
 ) {
     println!("GUI:\thandling internal: {:?}", internal);
     match internal {
-        shared::PlaybackRequest::LoadCurrentSong(track_load_type) => match play_queue
-            .current_playback
-        {
-            Some(ref outer_current_playback) => match outer_current_playback {
-                shared::CurrentPlayback::Track(ref current_playback) => {
-                    let maybe_next_track = match play_queue.play_queue.get(0) {
-                        Some(shared::PlayQueueEntry::Track(track)) => Some(
-                            shared::TrackPathOrPause::TrackPath(track.track.metadata.path.clone()),
-                        ),
-                        Some(shared::PlayQueueEntry::Action(shared::PlayQueueAction::Pause)) => {
-                            Some(shared::TrackPathOrPause::Pause)
-                        }
-                        None => None,
-                    };
-                    match track_load_type {
-                        shared::TrackLoadType::HardLoad => sink_client
-                            .send(shared::SinkMessage::LoadSong(
-                                current_playback.track.metadata.path.clone(),
-                                maybe_next_track,
-                            ))
-                            .unwrap(),
-                        shared::TrackLoadType::NaturalNext => sink_client
-                            .send(shared::SinkMessage::LoadNextSong(maybe_next_track))
-                            .unwrap(),
-                    };
-                    let _ = mpris_client.send(shared::MprisMessage::SetMetadata(
-                        current_playback.track.metadata.album_artist.clone(),
-                        current_playback.track.metadata.title.clone(),
-                    ));
-                    let _ = tracker_client.send(shared::TrackerMessage::SongStarted(
-                        current_playback.track.clone(),
-                    ));
-                }
-                shared::CurrentPlayback::PauseBreak => {
-                    play_queue.playing = false;
-                    handle_playback_request(
-                        play_queue,
-                        sink_client,
-                        mpris_client,
-                        tracker_client,
-                        shared::PlaybackRequest::Pause,
-                    );
-                }
-            },
-            None => println!("Nothing to load for current song"),
-        },
+        shared::PlaybackRequest::LoadCurrentSong(track_load_type) => {
+            match play_queue.current_playback {
+                Some(ref outer_current_playback) => match outer_current_playback {
+                    shared::CurrentPlayback::Track(ref current_playback) => {
+                        let maybe_next_track = compute_next_track(play_queue);
+                        match track_load_type {
+                            shared::TrackLoadType::HardLoad => sink_client
+                                .send(shared::SinkMessage::LoadSong(
+                                    current_playback.track.metadata.path.clone(),
+                                    maybe_next_track,
+                                ))
+                                .unwrap(),
+                            shared::TrackLoadType::NaturalNext => sink_client
+                                .send(shared::SinkMessage::LoadNextSong(maybe_next_track))
+                                .unwrap(),
+                        };
+                        let _ = mpris_client.send(shared::MprisMessage::SetMetadata(
+                            current_playback.track.metadata.album_artist.clone(),
+                            current_playback.track.metadata.title.clone(),
+                        ));
+                        let _ = tracker_client.send(shared::TrackerMessage::SongStarted(
+                            current_playback.track.clone(),
+                        ));
+                    }
+                    shared::CurrentPlayback::PauseBreak => {
+                        play_queue.playing = false;
+                        handle_playback_request(
+                            play_queue,
+                            sink_client,
+                            mpris_client,
+                            tracker_client,
+                            shared::PlaybackRequest::Pause,
+                        );
+                    }
+                },
+                None => println!("Nothing to load for current song"),
+            }
+        }
         shared::PlaybackRequest::PlaySongs(tracks) => handle_playback_request(
             play_queue,
             sink_client,
@@ -72,24 +68,7 @@ pub fn handle_playback_request(
             }
             play_queue.play_queue.append(&mut new_songs_to_queue);
             if should_issue_set_next {
-                match play_queue.play_queue.get(0).unwrap() {
-                    shared::PlayQueueEntry::Track(track) => {
-                        sink_client
-                            .send(shared::SinkMessage::SetNextSong(
-                                shared::TrackPathOrPause::TrackPath(
-                                    track.track.metadata.path.clone(),
-                                ),
-                            ))
-                            .unwrap();
-                    }
-                    shared::PlayQueueEntry::Action(shared::PlayQueueAction::Pause) => {
-                        sink_client
-                            .send(shared::SinkMessage::SetNextSong(
-                                shared::TrackPathOrPause::Pause,
-                            ))
-                            .unwrap();
-                    }
-                }
+                set_next_song_from_play_queue(play_queue, &sink_client);
             }
         }
         shared::PlaybackRequest::InsertSongs(tracks, load_next) => {
@@ -111,24 +90,7 @@ pub fn handle_playback_request(
                     shared::PlaybackRequest::Next(shared::TrackLoadType::HardLoad),
                 );
             } else {
-                match play_queue.play_queue.get(0).unwrap() {
-                    shared::PlayQueueEntry::Track(track) => {
-                        sink_client
-                            .send(shared::SinkMessage::SetNextSong(
-                                shared::TrackPathOrPause::TrackPath(
-                                    track.track.metadata.path.clone(),
-                                ),
-                            ))
-                            .unwrap();
-                    }
-                    shared::PlayQueueEntry::Action(shared::PlayQueueAction::Pause) => {
-                        sink_client
-                            .send(shared::SinkMessage::SetNextSong(
-                                shared::TrackPathOrPause::Pause,
-                            ))
-                            .unwrap();
-                    }
-                }
+                set_next_song_from_play_queue(play_queue, &sink_client);
             }
         }
         shared::PlaybackRequest::Prev => {
@@ -157,6 +119,11 @@ pub fn handle_playback_request(
             }
         }
         shared::PlaybackRequest::Next(track_load_mode) => {
+            // skipping ahead should leave a repeated track behind, but not a repeated group
+            match (&track_load_mode, play_queue.repeat_mode) {
+                (shared::TrackLoadType::HardLoad, shared::RepeatMode::One) => (),
+                _ => refill_for_repeat_mode(play_queue),
+            };
             if play_queue.play_queue.len() > 0 {
                 match play_queue.current_playback {
                     Some(ref current_playback) => play_queue
@@ -227,31 +194,81 @@ pub fn handle_playback_request(
                 shared::HistoryOrQueue::Queue => {
                     play_queue.play_queue.remove(index);
                     if index == 0 {
-                        match play_queue.play_queue.get(0) {
-                            Some(shared::PlayQueueEntry::Track(track)) => {
-                                sink_client
-                                    .send(shared::SinkMessage::SetNextSong(
-                                        shared::TrackPathOrPause::TrackPath(
-                                            track.track.metadata.path.clone(),
-                                        ),
-                                    ))
-                                    .unwrap();
-                            }
-                            Some(shared::PlayQueueEntry::Action(
-                                shared::PlayQueueAction::Pause,
-                            ))
-                            | None => {
-                                sink_client
-                                    .send(shared::SinkMessage::SetNextSong(
-                                        shared::TrackPathOrPause::Pause,
-                                    ))
-                                    .unwrap();
-                            }
-                        }
+                        set_next_song_from_play_queue(play_queue, &sink_client);
                     }
                 }
             };
         }
+        shared::PlaybackRequest::MoveTrackInPlayQueue(index, direction) => {
+            let swap_with = match direction {
+                model::Direction::Up => index.checked_sub(1),
+                model::Direction::Down => match index + 1 < play_queue.play_queue.len() {
+                    true => Some(index + 1),
+                    false => None,
+                },
+            };
+            match swap_with {
+                Some(other_index) if index < play_queue.play_queue.len() => {
+                    play_queue.play_queue.swap(index, other_index);
+                    if index == 0 || other_index == 0 {
+                        set_next_song_from_play_queue(play_queue, &sink_client);
+                    }
+                }
+                _ => println!("cannot move play queue entry {} {:?}", index, direction),
+            }
+        }
+        shared::PlaybackRequest::PlayTrackNext(history_or_queue, index) => {
+            let maybe_entry = match history_or_queue {
+                shared::HistoryOrQueue::History => play_queue.play_history.get(index).cloned(),
+                shared::HistoryOrQueue::Queue => match index < play_queue.play_queue.len() {
+                    true => Some(play_queue.play_queue.remove(index)),
+                    false => None,
+                },
+            };
+            match maybe_entry {
+                Some(entry) => {
+                    play_queue.play_queue.insert(0, entry);
+                    set_next_song_from_play_queue(play_queue, &sink_client);
+                }
+                None => println!("no play queue entry at {} to play next", index),
+            }
+        }
+        shared::PlaybackRequest::ClearPlayQueue => {
+            play_queue.play_queue = Vec::new();
+            set_next_song_from_play_queue(play_queue, &sink_client);
+        }
+        shared::PlaybackRequest::DedupePlayQueue => {
+            let mut seen = BTreeSet::new();
+            play_queue.play_queue.retain(|entry| match entry {
+                shared::PlayQueueEntry::Track(track) => {
+                    seen.insert(track.track.metadata.to_unique_id())
+                }
+                shared::PlayQueueEntry::Action(_) => true,
+            });
+            set_next_song_from_play_queue(play_queue, &sink_client);
+        }
+        shared::PlaybackRequest::ShufflePlayQueue(shuffle_mode) => {
+            play_queue.play_queue =
+                shuffle::shuffle_play_queue(&shuffle_mode, play_queue.play_queue.clone());
+            set_next_song_from_play_queue(play_queue, &sink_client);
+        }
+
+        shared::PlaybackRequest::SetRepeatMode(repeat_mode) => {
+            play_queue.repeat_mode = repeat_mode;
+            set_next_song_from_play_queue(play_queue, &sink_client);
+            let _ = mpris_client.send(shared::MprisMessage::SetLoopStatus(repeat_mode));
+        }
+        shared::PlaybackRequest::CycleRepeatMode => handle_playback_request(
+            play_queue,
+            sink_client,
+            mpris_client,
+            tracker_client,
+            shared::PlaybackRequest::SetRepeatMode(play_queue.repeat_mode.cycle()),
+        ),
+        shared::PlaybackRequest::SetShuffle(shuffle) => {
+            play_queue.shuffle = shuffle;
+            let _ = mpris_client.send(shared::MprisMessage::SetShuffle(shuffle));
+        }
 
         shared::PlaybackRequest::Close => {
             let _ = sink_client.send(shared::SinkMessage::Close);
@@ -259,3 +276,160 @@ pub fn handle_playback_request(
         }
     }
 }
+
+/// Tell the sink what it should load once the current track finishes
+fn set_next_song_from_play_queue(
+    play_queue: &shared::PlayQueueInfo,
+    sink_client: &shared::Client<shared::SinkMessage>,
+) {
+    let next = compute_next_track(play_queue).unwrap_or(shared::TrackPathOrPause::Pause);
+    sink_client
+        .send(shared::SinkMessage::SetNextSong(next))
+        .unwrap();
+}
+
+/// What a natural "next" would play, honoring the repeat mode, which is the front of the
+/// play queue once `refill_for_repeat_mode` has run
+fn compute_next_track(play_queue: &shared::PlayQueueInfo) -> Option<shared::TrackPathOrPause> {
+    let current_track = match play_queue.current_playback {
+        Some(shared::CurrentPlayback::Track(ref current_playback)) => Some(&current_playback.track),
+        Some(shared::CurrentPlayback::PauseBreak) | None => None,
+    };
+    let front = play_queue.play_queue.first().map(entry_to_next_track);
+
+    match (play_queue.repeat_mode, current_track) {
+        (shared::RepeatMode::One, Some(current_track)) => Some(
+            shared::TrackPathOrPause::TrackPath(current_track.metadata.path.clone()),
+        ),
+        (shared::RepeatMode::All, _) if play_queue.play_queue.is_empty() => {
+            match play_queue.play_history.first() {
+                Some(first_played) => Some(entry_to_next_track(first_played)),
+                None => play_queue
+                    .current_playback
+                    .as_ref()
+                    .map(current_playback_to_next_track),
+            }
+        }
+        (shared::RepeatMode::Album, Some(current_track)) => {
+            let album_key = track_album_key(current_track);
+            let next_is_same_album = match play_queue.play_queue.first() {
+                Some(shared::PlayQueueEntry::Track(track)) => {
+                    track_album_key(&track.track) == album_key
+                }
+                Some(shared::PlayQueueEntry::Action(_)) | None => false,
+            };
+            if next_is_same_album {
+                return front;
+            }
+            // the earliest of the album's tracks played just before this one
+            let first_of_album = play_queue
+                .play_history
+                .iter()
+                .rev()
+                .take_while(|entry| match entry {
+                    shared::PlayQueueEntry::Track(track) => {
+                        track_album_key(&track.track) == album_key
+                    }
+                    shared::PlayQueueEntry::Action(_) => false,
+                })
+                .last();
+            match first_of_album {
+                Some(first_of_album) => Some(entry_to_next_track(first_of_album)),
+                None => Some(shared::TrackPathOrPause::TrackPath(
+                    current_track.metadata.path.clone(),
+                )),
+            }
+        }
+        _ => front,
+    }
+}
+
+fn entry_to_next_track(entry: &shared::PlayQueueEntry) -> shared::TrackPathOrPause {
+    match entry {
+        shared::PlayQueueEntry::Track(track) => {
+            shared::TrackPathOrPause::TrackPath(track.track.metadata.path.clone())
+        }
+        shared::PlayQueueEntry::Action(shared::PlayQueueAction::Pause) => {
+            shared::TrackPathOrPause::Pause
+        }
+    }
+}
+
+fn current_playback_to_next_track(
+    current_playback: &shared::CurrentPlayback,
+) -> shared::TrackPathOrPause {
+    match current_playback {
+        shared::CurrentPlayback::Track(current_playback) => {
+            shared::TrackPathOrPause::TrackPath(current_playback.track.metadata.path.clone())
+        }
+        shared::CurrentPlayback::PauseBreak => shared::TrackPathOrPause::Pause,
+    }
+}
+
+/// Reshape the play queue so that the front of it is what the repeat mode
+/// says should play next; the current track is moved back into the queue
+/// (instead of into the history) when it will be repeated as part of a group
+fn refill_for_repeat_mode(play_queue: &mut shared::PlayQueueInfo) {
+    match play_queue.repeat_mode {
+        shared::RepeatMode::Off => (),
+        shared::RepeatMode::One => match play_queue.current_playback {
+            Some(shared::CurrentPlayback::Track(ref current_playback)) => {
+                play_queue.play_queue.insert(
+                    0,
+                    shared::PlayQueueEntry::Track(shared::PlayQueueTrack {
+                        track: current_playback.track.clone(),
+                    }),
+                );
+            }
+            Some(shared::CurrentPlayback::PauseBreak) | None => (),
+        },
+        shared::RepeatMode::All => {
+            if play_queue.play_queue.is_empty() {
+                let mut refilled: Vec<shared::PlayQueueEntry> =
+                    play_queue.play_history.drain(..).collect();
+                if let Some(current_playback) = play_queue.current_playback.take() {
+                    refilled.push(current_playback.to_play_queue_entry());
+                }
+                play_queue.play_queue = refilled;
+            }
+        }
+        shared::RepeatMode::Album => {
+            let current_album = match play_queue.current_playback {
+                Some(shared::CurrentPlayback::Track(ref current_playback)) => {
+                    Some(track_album_key(&current_playback.track))
+                }
+                Some(shared::CurrentPlayback::PauseBreak) | None => None,
+            };
+            if let Some(album_key) = current_album {
+                let next_is_same_album = match play_queue.play_queue.first() {
+                    Some(shared::PlayQueueEntry::Track(track)) => {
+                        track_album_key(&track.track) == album_key
+                    }
+                    Some(shared::PlayQueueEntry::Action(_)) | None => false,
+                };
+                if !next_is_same_album {
+                    let mut refilled = Vec::new();
+                    loop {
+                        match play_queue.play_history.last() {
+                            Some(shared::PlayQueueEntry::Track(track))
+                                if track_album_key(&track.track) == album_key =>
+                            {
+                                refilled.insert(0, play_queue.play_history.pop().unwrap());
+                            }
+                            _ => break,
+                        }
+                    }
+                    if let Some(current_playback) = play_queue.current_playback.take() {
+                        refilled.push(current_playback.to_play_queue_entry());
+                    }
+                    refilled.append(&mut play_queue.play_queue);
+                    play_queue.play_queue = refilled;
+                }
+            }
+        }
+    }
+}
+
+fn track_album_key(track: &model::AugmentedTrack) -> (musiqlibrary::ID, musiqlibrary::ID) {
+    (track.metadata.album_artist_id, track.metadata.album_id)
+}
@@ -109,6 +109,20 @@ impl MusicBrainzCacheInterface {
         .unwrap();
     }
 
+    /// Remove the match.json file for an artist, when its match was rejected
+    pub fn remove_musicbrainz_artist_match_file(&self, artist_name: String) -> io::Result<()> {
+        let this_artist_cache_file = localfs::build_tree_for_file(
+            &self.cache_dir,
+            vec![artist_name, "match.json".to_string()],
+        );
+
+        match fs::remove_file(this_artist_cache_file) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn read_musicbrainz_artist_approved_file(
         &self,
         artist_name: String,
@@ -142,3 +156,134 @@ impl MusicBrainzCacheInterface {
         .unwrap();
     }
 }
+
+/// The same raw/match/approved flow as for artists, kept per album of an artist, where
+/// raw is the release search results, match is the single release picked from them
+/// and approved is the looked up release with its labels and track list
+pub struct MusicBrainzAlbumCacheInterface {
+    cache_dir: path::PathBuf,
+}
+
+impl MusicBrainzAlbumCacheInterface {
+    pub fn new() -> Self {
+        let app_data_path = musiqcore::model::functions::get_default_data_path();
+
+        let cache_dir =
+            localfs::build_tree_for_dirs(&app_data_path, vec!["cache", "musicbrainz", "albums"]);
+
+        MusicBrainzAlbumCacheInterface { cache_dir }
+    }
+
+    fn album_file(
+        &self,
+        artist_name: String,
+        album_name: String,
+        file_name: &str,
+    ) -> path::PathBuf {
+        localfs::build_tree_for_file(
+            &self.cache_dir,
+            vec![artist_name, album_name, file_name.to_string()],
+        )
+    }
+
+    pub fn raw_exists(&self, artist_name: String, album_name: String) -> bool {
+        self.album_file(artist_name, album_name, "raw.json")
+            .exists()
+    }
+
+    pub fn match_exists(&self, artist_name: String, album_name: String) -> bool {
+        self.album_file(artist_name, album_name, "match.json")
+            .exists()
+    }
+
+    pub fn approved_exists(&self, artist_name: String, album_name: String) -> bool {
+        self.album_file(artist_name, album_name, "approved.json")
+            .exists()
+    }
+
+    /// Read the raw.json file for an album
+    pub fn read_musicbrainz_album_cache_file(
+        &self,
+        artist_name: String,
+        album_name: String,
+    ) -> String {
+        let mut file =
+            fs::File::open(self.album_file(artist_name, album_name, "raw.json")).unwrap();
+        let mut ret = String::new();
+        file.read_to_string(&mut ret).unwrap();
+        ret
+    }
+
+    /// Write to the raw.json file for an album with a given (assumed) json payload
+    pub fn write_musicbrainz_album_cache_file(
+        &self,
+        artist_name: String,
+        album_name: String,
+        contents: String,
+    ) {
+        let mut file =
+            fs::File::create(self.album_file(artist_name, album_name, "raw.json")).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+    }
+
+    pub fn read_musicbrainz_album_match_file(
+        &self,
+        artist_name: String,
+        album_name: String,
+    ) -> Option<musicbrainz::Release> {
+        let this_album_cache_file = self.album_file(artist_name, album_name, "match.json");
+
+        match serde_json::from_reader(io::BufReader::new(
+            fs::File::open(&this_album_cache_file).ok()?,
+        )) {
+            Ok(v) => v,
+            Err(e) => {
+                println!("error: {:?}", e);
+                panic!("{:?}", e);
+            }
+        }
+    }
+
+    pub fn write_musicbrainz_album_match_file(
+        &self,
+        artist_name: String,
+        album_name: String,
+        release: &musicbrainz::Release,
+    ) {
+        serde_json::to_writer_pretty(
+            io::BufWriter::new(
+                fs::File::create(self.album_file(artist_name, album_name, "match.json")).unwrap(),
+            ),
+            release,
+        )
+        .unwrap();
+    }
+
+    pub fn read_musicbrainz_album_approved_file(
+        &self,
+        artist_name: String,
+        album_name: String,
+    ) -> Option<musicbrainz::Release> {
+        serde_json::from_reader(io::BufReader::new(
+            fs::File::open(self.album_file(artist_name, album_name, "approved.json")).ok()?,
+        ))
+        .ok()
+    }
+
+    /// Write to the approved.json file for an album with the looked up release
+    pub fn write_musicbrainz_album_approved_file(
+        &self,
+        artist_name: String,
+        album_name: String,
+        release: &musicbrainz::Release,
+    ) {
+        serde_json::to_writer_pretty(
+            io::BufWriter::new(
+                fs::File::create(self.album_file(artist_name, album_name, "approved.json"))
+                    .unwrap(),
+            ),
+            release,
+        )
+        .unwrap();
+    }
+}
@@ -4,4 +4,5 @@ pub mod movieart;
 pub mod movielibrary;
 pub mod playlists;
 pub mod prehistory;
+pub mod showart;
 pub mod tracklibrary;
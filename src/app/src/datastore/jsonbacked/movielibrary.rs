@@ -11,8 +11,14 @@ use musiqlibrary::video;
 use crate::datastore::loader;
 use crate::model;
 
+/// Bumped when the scanner changes what it reads, so caches from before are rescanned
+/// (1: mp4 durations are read with the movie header's timescale, 2: audio and subtitle tracks)
+const CACHE_VERSION: u32 = 2;
+
 #[derive(Serialize, Deserialize, Default)]
 struct CacheMetadataPayload {
+    #[serde(default)]
+    pub version: u32,
     pub info: Vec<video::MovieMetadata>,
 }
 
@@ -46,7 +52,11 @@ pub fn load_library_from_cache_and_scan(
             println!("{:?}", only_cached);
             println!("{:?}", only_filesystem);
 
-            let organized = if only_cached.len() > 0 || only_filesystem.len() > 0 {
+            let organized = if metadata_payload.version != CACHE_VERSION {
+                println!("cache is from an older scanner so just loading entire video library");
+
+                model::VideoLibrary::new(&config_state.movie_path)
+            } else if only_cached.len() > 0 || only_filesystem.len() > 0 {
                 println!("found a diff so just loading entire video library");
 
                 let ret = model::VideoLibrary::new(&config_state.movie_path);
@@ -102,7 +112,10 @@ fn arbitrary_serialize_sort(raw_library: &model::VideoLibrary) -> CacheMetadataP
         ret.push(movie.clone());
     }
 
-    CacheMetadataPayload { info: ret }
+    CacheMetadataPayload {
+        version: CACHE_VERSION,
+        info: ret,
+    }
 }
 
 fn compute_diff(
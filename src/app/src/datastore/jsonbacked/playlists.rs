@@ -1,3 +1,4 @@
+use std::cmp;
 use std::collections::BTreeMap;
 use std::fs;
 use std::io;
@@ -33,15 +34,19 @@ impl PlaylistData {
         }
     }
 
-    pub fn add_playlist(&mut self, name: String) {
+    pub fn add_playlist(&mut self, name: String) -> u32 {
         let new_inner = match &self.inner {
             None => Some(InnerPlaylistData::new(name.clone())),
             Some(ref _v) => None,
         };
 
-        match new_inner {
+        let new_id = match new_inner {
             Some(inner) => match self.inner {
-                None => self.inner = Some(inner),
+                None => {
+                    let new_id = inner.selected_playlist_id;
+                    self.inner = Some(inner);
+                    new_id
+                }
                 Some(ref _v) => panic!("should not be trying to overwrite existing inner"),
             },
             None => match self.inner {
@@ -56,6 +61,8 @@ impl PlaylistData {
             Some(ref inner) => inner.write_json(&self.json_db_path),
             None => panic!("should never have no playlists after calling `add_playlist`"),
         };
+
+        new_id
     }
 
     pub fn get_playlist(&self, playlist_id: u32) -> Option<&model::playlist::PlaylistEntry> {
@@ -163,7 +170,7 @@ pub struct InnerPlaylistData {
 impl InnerPlaylistData {
     fn new(name: String) -> Self {
         InnerPlaylistData {
-            current_id: 0,
+            current_id: 1,
             selected_playlist_id: 1,
             playlists: vec![(
                 1,
@@ -178,7 +185,7 @@ impl InnerPlaylistData {
         }
     }
 
-    fn add(&mut self, name: String) {
+    fn add(&mut self, name: String) -> u32 {
         let new_entry = model::playlist::PlaylistEntry {
             id: self.current_id + 1,
             name,
@@ -187,7 +194,9 @@ impl InnerPlaylistData {
 
         self.current_id += 1;
 
-        self.playlists.insert(new_entry.id, new_entry);
+        let new_id = new_entry.id;
+        self.playlists.insert(new_id, new_entry);
+        new_id
     }
 
     fn delete_playlist(&mut self, playlist_id: u32) -> Result<(), String> {
@@ -329,8 +338,15 @@ impl InnerPlaylistData {
     }
 
     fn from_raw(raw: RawPlaylistData) -> Self {
+        // files saved before the first playlist counted towards the id can lag behind it
+        let highest_id = raw
+            .playlists
+            .iter()
+            .map(|entry| entry.id)
+            .max()
+            .unwrap_or(0);
         InnerPlaylistData {
-            current_id: raw.current_id,
+            current_id: cmp::max(raw.current_id, highest_id),
             selected_playlist_id: raw.selected_playlist_id,
             playlists: raw
                 .playlists
@@ -0,0 +1,394 @@
+use std::fs;
+use std::path;
+use std::sync::mpsc;
+use std::thread;
+use std::time;
+
+use image::GenericImageView;
+use image::ImageReader;
+use serde::{Deserialize, Serialize};
+
+use musiqcore::datastore::localfs;
+use musiqlibrary::shows;
+
+use crate::model;
+use crate::util::ffmpeg;
+
+const IMAGE_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+
+/// Names (without extension) of a show's poster in its directory
+const SHOW_POSTER_NAMES: [&str; 3] = ["poster", "folder", "cover"];
+
+/// Names (without extension) of a season's poster in its own directory
+const SEASON_POSTER_NAMES: [&str; 4] = ["season", "poster", "folder", "cover"];
+
+/// The frame grabbed from an episode with no thumbnail, in its cache dir
+const FRAME_GRAB_FILE: &str = "frame.png";
+
+/// Left in an episode's cache dir when ffmpeg could not grab a frame, so it is not retried
+const FRAME_GRAB_FAILED_MARKER: &str = "frame.failed";
+
+/// How far into an episode (as a fraction of its duration) the fallback frame is grabbed
+const FRAME_GRAB_POSITION: f64 = 0.1;
+
+/// Records which image a cache dir's sizes were made from, so a new or changed one replaces them
+const CACHED_SOURCE_FILE: &str = "source.json";
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct CachedSource {
+    path: path::PathBuf,
+    /// When the image was last modified, in seconds since the epoch
+    modified: u64,
+}
+
+/// An image to find (or grab) and cache at every size
+struct ArtJob {
+    key: model::ShowArtKey,
+    cache_dir: path::PathBuf,
+    /// Sidecar images, in order of preference
+    sources: Vec<path::PathBuf>,
+    /// The video and the time to grab a frame at, when no sidecar image is found
+    frame_grab: Option<(path::PathBuf, time::Duration)>,
+}
+
+/// Where every piece of show art is cached. The caching itself (grabbing frames with ffmpeg and
+/// resizing) runs in the background, so startup does not wait on it.
+pub fn get_show_art_and_start_caching(
+    show_library_state: &musiqcore::model::shows::ShowLibraryState,
+    app_data_path: path::PathBuf,
+) -> model::ShowArt {
+    let mut show_art = model::ShowArt::default();
+
+    let show_library = match show_library_state.get_shows_if_exists() {
+        Some(show_library) => show_library,
+        None => return show_art,
+    };
+
+    let show_cache_path =
+        localfs::build_tree_for_dirs(&app_data_path, vec!["cache", "images", "shows"]);
+
+    let jobs = find_art_jobs(show_library.get_structured_shows(), &show_cache_path);
+
+    for job in jobs.iter() {
+        show_art.insert(job.key.clone(), job.cache_dir.clone());
+    }
+
+    thread::spawn(move || cache_show_art(jobs));
+
+    show_art
+}
+
+fn cache_show_art(jobs: Vec<ArtJob>) {
+    let can_grab_frames = ffmpeg::is_available();
+    if !can_grab_frames {
+        println!("no ffmpeg found, episodes without a thumbnail won't get a frame grab");
+    }
+
+    let num_threads = thread::available_parallelism()
+        .map(|x| x.into())
+        .unwrap_or(1);
+
+    println!("creating thread pool with {} threads", num_threads);
+
+    let tpool = threadpool::ThreadPool::new(num_threads);
+    let (tx, rx) = mpsc::channel();
+
+    for job in jobs.into_iter() {
+        let tx = tx.clone();
+
+        tpool.execute(move || {
+            let was_cached = process_art_job(&job, can_grab_frames);
+            tx.send(was_cached)
+                .expect("I hope the show cache art recv is listening");
+        });
+    }
+
+    drop(tx);
+
+    let found_cache_entries = rx.into_iter().filter(|was_cached| *was_cached).count();
+
+    println!(
+        "saw this many pre-cached show images: {}",
+        found_cache_entries
+    );
+}
+
+fn find_art_jobs(structured: &shows::Shows, show_cache_path: &path::Path) -> Vec<ArtJob> {
+    let mut jobs = Vec::new();
+
+    for (show_key, show) in structured.get_shows().iter() {
+        let maybe_show_dir = show
+            .get_seasons()
+            .values()
+            .flat_map(|season| season.get_episodes().values())
+            .next()
+            .and_then(show_dir_of);
+
+        if let Some((ref show_dir, ref rel_show_dir)) = maybe_show_dir {
+            jobs.push(ArtJob {
+                key: model::ShowArtKey::Show(show_key.clone()),
+                cache_dir: show_cache_path.join(rel_show_dir).join("show"),
+                sources: image_candidates(show_dir, &SHOW_POSTER_NAMES),
+                frame_grab: None,
+            });
+        }
+
+        for (season_number, season) in show.get_seasons().iter() {
+            let first_episode = season.get_first_episode();
+            let season_dir = first_episode.full_path.parent().unwrap().to_path_buf();
+            let rel_season_dir = first_episode.rel_path.parent().unwrap().to_path_buf();
+            if rel_season_dir.as_os_str().is_empty() {
+                // episodes right in the show root have no directory of their own to hold art
+                continue;
+            }
+
+            let mut sources = Vec::new();
+            match maybe_show_dir {
+                Some((ref show_dir, _)) if *show_dir == season_dir => (),
+                _ => sources.append(&mut image_candidates(&season_dir, &SEASON_POSTER_NAMES)),
+            };
+            // the show directory can hold every season's poster, e.g. season01-poster.jpg
+            if let Some((ref show_dir, _)) = maybe_show_dir {
+                let season_poster_name = match season.is_specials() {
+                    true => "season-specials-poster".to_string(),
+                    false => format!("season{:02}-poster", season_number),
+                };
+                sources.append(&mut image_candidates(
+                    show_dir,
+                    &[season_poster_name.as_str()],
+                ));
+            }
+
+            jobs.push(ArtJob {
+                key: model::ShowArtKey::Season(show_key.clone(), *season_number),
+                cache_dir: show_cache_path.join(rel_season_dir).join("season"),
+                sources,
+                frame_grab: None,
+            });
+
+            for episode in season.get_episodes().values() {
+                let episode_dir = episode.full_path.parent().unwrap();
+                let stem = match episode.full_path.file_stem() {
+                    Some(stem) => stem.to_string_lossy().to_string(),
+                    None => continue,
+                };
+                let thumb_name = format!("{}-thumb", stem);
+
+                jobs.push(ArtJob {
+                    key: model::ShowArtKey::Episode(episode.get_key()),
+                    cache_dir: show_cache_path.join(&episode.rel_path),
+                    sources: image_candidates(episode_dir, &[thumb_name.as_str(), stem.as_str()]),
+                    frame_grab: Some((
+                        episode.full_path.clone(),
+                        episode.duration.mul_f64(FRAME_GRAB_POSITION),
+                    )),
+                });
+            }
+        }
+    }
+
+    jobs
+}
+
+/// The show's own directory (and that relative to the show root), when it is not the root itself
+fn show_dir_of(episode: &shows::ShowMetadata) -> Option<(path::PathBuf, path::PathBuf)> {
+    let rel_components = episode.rel_path.components().count();
+    if rel_components < 2 {
+        return None;
+    }
+    let show_root = episode.full_path.ancestors().nth(rel_components)?;
+    let rel_show_dir = path::PathBuf::from(episode.rel_path.components().next()?.as_os_str());
+    Some((show_root.join(&rel_show_dir), rel_show_dir))
+}
+
+fn image_candidates(dir: &path::Path, names: &[&str]) -> Vec<path::PathBuf> {
+    let mut candidates = Vec::new();
+    for name in names.iter() {
+        for extension in IMAGE_EXTENSIONS.iter() {
+            candidates.push(dir.join(format!("{}.{}", name, extension)));
+        }
+    }
+    candidates
+}
+
+fn sized_cache_paths(cache_dir: &path::Path) -> Vec<(model::MovieSize, path::PathBuf)> {
+    vec![
+        model::MovieSize::Large,
+        model::MovieSize::SemiLarge,
+        model::MovieSize::Regular,
+        model::MovieSize::Small,
+        model::MovieSize::Micro,
+    ]
+    .into_iter()
+    .map(|size| {
+        let sized_path = cache_dir.join(model::show_art_file_name(&size));
+        (size, sized_path)
+    })
+    .collect()
+}
+
+/// Where a file is written before it is renamed into place, so it is never read half written
+fn partial_path(final_path: &path::Path) -> path::PathBuf {
+    final_path.with_extension("partial.png")
+}
+
+impl CachedSource {
+    fn of(source: &path::Path) -> Option<Self> {
+        let modified = fs::metadata(source)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        Some(CachedSource {
+            path: source.to_path_buf(),
+            modified: modified
+                .duration_since(time::UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_secs())
+                .unwrap_or(0),
+        })
+    }
+
+    fn read(cache_dir: &path::Path) -> Option<Self> {
+        let bytes = fs::read(cache_dir.join(CACHED_SOURCE_FILE)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn write(&self, cache_dir: &path::Path) {
+        let source_path = cache_dir.join(CACHED_SOURCE_FILE);
+        match serde_json::to_vec(self) {
+            Ok(bytes) => match fs::write(&source_path, bytes) {
+                Ok(()) => (),
+                Err(e) => println!("could not save show art source {:?}: {}", source_path, e),
+            },
+            Err(e) => println!(
+                "could not serialize show art source {:?}: {}",
+                source_path, e
+            ),
+        }
+    }
+}
+
+/// Cache every size of the job's image, unless they were already made from that same image.
+/// Returns whether they were.
+fn process_art_job(job: &ArtJob, can_grab_frames: bool) -> bool {
+    let sized_paths = sized_cache_paths(&job.cache_dir);
+
+    let source = match find_source_image(job, can_grab_frames) {
+        Some(source) => source,
+        None => return false,
+    };
+    let cached_source = match CachedSource::of(&source) {
+        Some(cached_source) => cached_source,
+        None => return false,
+    };
+
+    let was_cached = CachedSource::read(&job.cache_dir).as_ref() == Some(&cached_source)
+        && sized_paths
+            .iter()
+            .all(|(_, sized_path)| localfs::check_exists(sized_path));
+    if was_cached {
+        return true;
+    }
+
+    localfs::confirm_dir(&job.cache_dir).unwrap();
+    match cache_sizes(&source, &sized_paths) {
+        true => cached_source.write(&job.cache_dir),
+        false => {
+            if source == job.cache_dir.join(FRAME_GRAB_FILE) {
+                // a frame that does not decode would otherwise be tried again on every start
+                let _ = fs::remove_file(&source);
+                let _ = fs::write(job.cache_dir.join(FRAME_GRAB_FAILED_MARKER), "");
+            }
+        }
+    };
+
+    false
+}
+
+/// The first sidecar image there is, or else a frame grabbed from the episode
+fn find_source_image(job: &ArtJob, can_grab_frames: bool) -> Option<path::PathBuf> {
+    if let Some(source) = job.sources.iter().find(|source| source.is_file()) {
+        return Some(source.clone());
+    }
+
+    let (video, at) = job.frame_grab.as_ref()?;
+
+    let frame_path = job.cache_dir.join(FRAME_GRAB_FILE);
+    if localfs::check_exists(&frame_path) {
+        return Some(frame_path);
+    }
+
+    let failed_marker = job.cache_dir.join(FRAME_GRAB_FAILED_MARKER);
+    if !can_grab_frames || localfs::check_exists(&failed_marker) {
+        return None;
+    }
+
+    localfs::confirm_dir(&job.cache_dir).unwrap();
+    println!("grabbing a frame for {:?}", video);
+    let partial_frame_path = partial_path(&frame_path);
+    match ffmpeg::grab_frame(video, *at, &partial_frame_path) {
+        Ok(()) => match fs::rename(&partial_frame_path, &frame_path) {
+            Ok(()) => Some(frame_path),
+            Err(e) => {
+                println!("could not save frame grab {:?}: {}", frame_path, e);
+                let _ = fs::remove_file(&partial_frame_path);
+                None
+            }
+        },
+        Err(e) => {
+            println!("no thumbnail for {:?}: {}", video, e);
+            let _ = fs::remove_file(&partial_frame_path);
+            if let ffmpeg::Error::Failed(_) = e {
+                let _ = fs::write(&failed_marker, "");
+            }
+            None
+        }
+    }
+}
+
+/// Whether every size was saved
+fn cache_sizes(source: &path::Path, sized_paths: &[(model::MovieSize, path::PathBuf)]) -> bool {
+    let orig_art = match ImageReader::open(source).and_then(|reader| reader.with_guessed_format()) {
+        Ok(reader) => match reader.decode() {
+            Ok(v) => v,
+            Err(e) => {
+                println!("could not decode show art {:?}: {}", source, e);
+                return false;
+            }
+        },
+        Err(e) => {
+            println!("could not open show art {:?}: {}", source, e);
+            return false;
+        }
+    };
+
+    let (orig_width, orig_height) = orig_art.dimensions();
+    if orig_width == 0 || orig_height == 0 {
+        println!("skipping empty show art {:?}", source);
+        return false;
+    }
+
+    let mut all_saved = true;
+    for (size, sized_path) in sized_paths.iter() {
+        println!("translating {:?} size show art from {:?}", size, source);
+        let height = size.height() as u32;
+        let sized_art = image::imageops::resize(
+            &orig_art,
+            (height * orig_width) / orig_height,
+            height,
+            image::imageops::FilterType::Lanczos3,
+        );
+        let partial_sized_path = partial_path(sized_path);
+        match sized_art
+            .save(&partial_sized_path)
+            .map_err(|e| e.to_string())
+            .and_then(|()| fs::rename(&partial_sized_path, sized_path).map_err(|e| e.to_string()))
+        {
+            Ok(()) => (),
+            Err(e) => {
+                println!("could not save show art {:?}: {}", sized_path, e);
+                let _ = fs::remove_file(&partial_sized_path);
+                all_saved = false;
+            }
+        };
+    }
+    all_saved
+}
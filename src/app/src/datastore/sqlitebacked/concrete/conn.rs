@@ -302,6 +302,20 @@ impl Connections {
         query::tracking::get_all_live_track_counts(&self.shared_db)
     }
 
+    pub fn get_live_track_history(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+        bridge: &bridge::SixtyFourLibrary,
+    ) -> Vec<DateTime<Local>> {
+        let track = bridge.track_from_unique_key(id);
+
+        query::tracking::get_live_track_history(&self.shared_db, track.id)
+    }
+
+    pub fn get_all_live_track_history(&self) -> BTreeMap<u32, Vec<DateTime<Local>>> {
+        query::tracking::get_all_live_track_history(&self.shared_db)
+    }
+
     pub fn increment_track_with_date(
         &mut self,
         bridge: &bridge::SixtyFourLibrary,
@@ -20,6 +20,50 @@ pub fn get_all_live_track_counts(main_db: &rusqlite::Connection) -> BTreeMap<u32
     results.into_iter().collect()
 }
 
+pub fn get_all_live_track_history(
+    main_db: &rusqlite::Connection,
+) -> BTreeMap<u32, Vec<DateTime<Local>>> {
+    let mut statement = main_db
+        .prepare("select track_id, listened_date from live_track_history")
+        .unwrap();
+
+    let results = statement
+        .query_map([], map_track_id_and_date)
+        .unwrap()
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .unwrap();
+
+    let mut history: BTreeMap<u32, Vec<DateTime<Local>>> = BTreeMap::new();
+    for (track_id, listened_date) in results.into_iter() {
+        history.entry(track_id).or_default().push(listened_date);
+    }
+    history
+}
+
+pub fn get_live_track_history(
+    main_db: &rusqlite::Connection,
+    track_id: u32,
+) -> Vec<DateTime<Local>> {
+    let mut statement = main_db
+        .prepare("SELECT track_id, listened_date FROM live_track_history WHERE track_id = ?")
+        .unwrap();
+
+    let results = statement
+        .query_map(rusqlite::params![track_id], map_track_id_and_date)
+        .unwrap()
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .unwrap();
+
+    results
+        .into_iter()
+        .map(|(_track_id, listened_date)| listened_date)
+        .collect()
+}
+
+fn map_track_id_and_date(row: &rusqlite::Row) -> rusqlite::Result<(u32, DateTime<Local>)> {
+    Ok((row.get(0)?, row.get(1)?))
+}
+
 fn map_track_id_and_count(row: &rusqlite::Row) -> rusqlite::Result<(u32, usize)> {
     Ok((row.get(0)?, {
         let x: u32 = row.get(1)?;
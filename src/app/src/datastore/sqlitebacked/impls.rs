@@ -35,6 +35,20 @@ impl datastore::traits::LiveReadOnlyTrackCountReporter for CachingConn {
     fn get_live_track_count(&self, id: &musiqlibrary::TrackUniqueIdentifier) -> usize {
         self.conn.get_live_track_count(id, &self.bridge)
     }
+
+    fn get_live_track_last_played(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Option<DateTime<Local>> {
+        self.get_live_track_history(id).into_iter().max()
+    }
+
+    fn get_live_track_history(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Vec<DateTime<Local>> {
+        self.conn.get_live_track_history(id, &self.bridge)
+    }
 }
 
 impl datastore::traits::HistoricalTrackCountReporter for CachingConn {
@@ -74,16 +88,19 @@ impl datastore::traits::HistoricalTrackCountReporter for SqlitePreHistoryReporte
 pub struct SqliteLiveHistoryReporter {
     bridge: bridge::SixtyFourLibrary,
     livehistory_track_id_to_count: BTreeMap<u32, usize>,
+    livehistory_track_id_to_history: BTreeMap<u32, Vec<DateTime<Local>>>,
 }
 
 impl SqliteLiveHistoryReporter {
     pub fn new(conn: conn::Connections) -> Self {
         let bridge = bridge::SixtyFourLibrary::new(&conn);
         let livehistory_track_id_to_count = conn.get_all_live_track_counts();
+        let livehistory_track_id_to_history = conn.get_all_live_track_history();
 
         SqliteLiveHistoryReporter {
             bridge,
             livehistory_track_id_to_count,
+            livehistory_track_id_to_history,
         }
     }
 }
@@ -97,6 +114,27 @@ impl datastore::traits::LiveReadOnlyTrackCountReporter for SqliteLiveHistoryRepo
             .unwrap_or(&0);
         *count
     }
+
+    fn get_live_track_last_played(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Option<DateTime<Local>> {
+        let db_track = self.bridge.track_from_unique_key(id);
+        self.livehistory_track_id_to_history
+            .get(&db_track.id)
+            .and_then(|history| history.iter().max().cloned())
+    }
+
+    fn get_live_track_history(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Vec<DateTime<Local>> {
+        let db_track = self.bridge.track_from_unique_key(id);
+        self.livehistory_track_id_to_history
+            .get(&db_track.id)
+            .cloned()
+            .unwrap_or(Vec::new())
+    }
 }
 
 pub struct SqliteLiveHistoryRecorder {
@@ -154,6 +154,14 @@ fn music_breadcrumbs(
             )
             .into_message(),
         )),
+        message::MusicNavMessage::Stats(range) => ret.push((
+            "Stats".to_string(),
+            message::MusicNavMessage::Stats(*range).into_message(),
+        )),
+        message::MusicNavMessage::MusicBrainzMatching => ret.push((
+            "MusicBrainz Matching".to_string(),
+            message::MusicNavMessage::MusicBrainzMatching.into_message(),
+        )),
         message::MusicNavMessage::AlbumList(_, _, _) => ret.push((
             "Albums".to_string(),
             message::MusicNavMessage::AlbumList(
@@ -11,6 +11,7 @@ use crate::datastore::{
 };
 
 use crate::model;
+use crate::shared;
 use crate::util::logging;
 
 use crate::datastore::loader;
@@ -59,7 +60,14 @@ pub fn initialize_everything() -> state::App {
         &config_state.app_data_path.to_path_buf(),
     );
 
-    let video_library_state = model::VideoLibraryState::new(video_library, loaded_movie_images);
+    let movie_tracker =
+        datastore::jsonbacked::movietracker::MovieTracker::new(&config_state.app_data_path);
+
+    let resume_tracker =
+        datastore::jsonbacked::resumetracker::ResumeTracker::new(&config_state.app_data_path);
+
+    let video_library_state =
+        model::VideoLibraryState::new(video_library, loaded_movie_images, movie_tracker);
 
     let game_library_state = musiqcore::model::gl::GameLibraryState::new(game_library);
 
@@ -68,6 +76,12 @@ pub fn initialize_everything() -> state::App {
         show_tracker,
     );
 
+    let show_art = jsonbacked::showart::get_show_art_and_start_caching(
+        &show_library_state,
+        config_state.app_data_path.to_path_buf(),
+    );
+    logger.print_elapsed("finding show art (caching in the background)");
+
     let read_only_tracker: Box<dyn datastore::traits::LiveReadOnlyTrackCountReporter> = match loader
     {
         loader::Loader::NoCache | loader::Loader::Json => {
@@ -147,6 +161,16 @@ pub fn initialize_everything() -> state::App {
 
     let musicbrainz_library = model::musicbrainzlib::Library::new(&loaded_library);
 
+    let play_history = model::history::PlayHistory::new(
+        &loaded_library,
+        read_only_tracker.as_ref(),
+        historical_reporter.as_ref(),
+    );
+    logger.print_elapsed("loading play history");
+
+    let listening_sessions = model::radio::ListeningSessions::new(&loaded_library, &play_history);
+    logger.print_elapsed("mining listening sessions");
+
     let augmented_library =
         model::augmented_from_raw(loaded_library, read_only_tracker, historical_reporter);
     logger.print_elapsed("augmenting raw library");
@@ -192,16 +216,20 @@ pub fn initialize_everything() -> state::App {
         },
         video_library: video_library_state,
         show_library: show_library_state,
+        show_art,
         game_library: game_library_state,
+        resume_tracker,
         config: config_state,
         player_info: state::PlayerInfo {
             playing: false,
             current_volume: 1.0,
             play_queue_info: state::PlayQueueInfo {
                 play_queue_visible: true,
+                repeat_mode: shared::RepeatMode::Off,
                 play_history: Vec::new(),
                 play_queue: Vec::new(),
                 current_playback: None,
+                radio: None,
             },
             backend_message_sender: backend_client,
             backend_callback_recv: RefCell::new(Some(backend_callback)),
@@ -210,6 +238,8 @@ pub fn initialize_everything() -> state::App {
             augmented_library,
             extra_library,
             musicbrainz_library,
+            play_history,
+            listening_sessions,
             playlists,
             artist_sorts,
             album_sorts,
@@ -1,3 +1,5 @@
+use musiqcore::model::gl::consoles;
+
 use super::{nav, top};
 
 impl nav::NavMessage {
@@ -72,6 +74,20 @@ impl nav::GameNavMessage {
     pub fn into_message(self) -> top::Message {
         top::Message::Nav(nav::NavMessage::Game(self))
     }
+
+    /// The list of games for a console
+    pub fn from_console(console: &consoles::GameConsole) -> Self {
+        match console {
+            consoles::GameConsole::GameBoy => nav::GameNavMessage::GBList,
+            consoles::GameConsole::GameBoyColor => nav::GameNavMessage::GBCList,
+            consoles::GameConsole::GameBoyAdvance => nav::GameNavMessage::GBAList,
+            consoles::GameConsole::NintendoDS => nav::GameNavMessage::NDSList,
+            consoles::GameConsole::SNES => nav::GameNavMessage::SNESList,
+            consoles::GameConsole::Nintendo64 => nav::GameNavMessage::N64List,
+            consoles::GameConsole::GameCube => nav::GameNavMessage::GameCubeList,
+            consoles::GameConsole::Wii => nav::GameNavMessage::WiiList,
+        }
+    }
 }
 
 impl top::ExternalSpawn {
@@ -22,6 +22,8 @@ pub enum MusicNavMessage {
     AlbumList(usize, model::AlbumSortKey, model::SortOrder),
     Artist(ArtistNavMessage),
     Genres(MusicGenreNavMessage),
+    Stats(model::stats::StatsRange),
+    MusicBrainzMatching,
 }
 
 #[derive(Debug, Clone)]
@@ -6,6 +6,7 @@ use iced::futures::Future;
 use crate::shared;
 
 use crate::model;
+use crate::util::{mpv, shuffle};
 
 use super::nav::*;
 
@@ -44,18 +45,45 @@ pub enum Action {
     DeletePlaylist(u32),
     RemoveTrackFromPlaylist(u32, musiqlibrary::TrackUniqueIdentifier),
     MoveTrackInPlaylist(u32, model::Direction, musiqlibrary::TrackUniqueIdentifier),
+    SavePlayQueueAsPlaylist(String),
 
     ToggleShuffleOnAdd,
+    SetShuffleMode(shuffle::ShuffleMode),
+
+    StartRadio(model::radio::RadioSeed),
+    StopRadio,
 
     SetVolume(VolumeRequest),
 
     UpdateText(String),
     PerformSearch(String, model::SearchDomain),
+    MusicBrainz(MusicBrainzAction),
+    ShowWatch(ShowWatchAction),
     TogglePlayQueueVisible,
     Notify(NotificationMessage),
     Close,
 }
 
+#[derive(Debug, Clone)]
+pub enum MusicBrainzAction {
+    ApproveArtist(musiqlibrary::ID, musicbrainz::Artist),
+    RejectArtistMatch(musiqlibrary::ID),
+    SearchArtist(musiqlibrary::ID, String),
+    ArtistSearched(musiqlibrary::ID, Result<String, String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum ShowWatchAction {
+    MarkSeasonWatched(musiqlibrary::shows::ShowKey, u32),
+    MarkSeasonUnwatched(musiqlibrary::shows::ShowKey, u32),
+    MarkShowWatched(musiqlibrary::shows::ShowKey),
+    MarkShowUnwatched(musiqlibrary::shows::ShowKey),
+    RemoveEpisodeView(
+        musiqlibrary::shows::ShowEpisodeKey,
+        chrono::DateTime<chrono::Local>,
+    ),
+}
+
 #[derive(Debug, Clone)]
 pub enum NotificationMessage {
     OnScreen(NotificationAction),
@@ -78,12 +106,37 @@ pub enum VolumeRequest {
 
 #[derive(Debug, Clone)]
 pub enum ExternalRequest {
+    PlayMovie(musiqlibrary::video::MovieMetadata),
     PlayShow(musiqlibrary::shows::ShowMetadata),
+    FinishedWatching(WatchTarget, mpv::PlaybackProgress),
+}
+
+/// What is being watched in mpv, so that its progress can be recorded against it
+#[derive(Debug, Clone)]
+pub enum WatchTarget {
+    Movie(musiqlibrary::video::MovieMetadata),
+    Show(musiqlibrary::shows::ShowMetadata),
+}
+
+impl WatchTarget {
+    pub fn path(&self) -> &path::Path {
+        match self {
+            WatchTarget::Movie(movie) => &movie.path,
+            WatchTarget::Show(show_episode) => &show_episode.full_path,
+        }
+    }
+
+    pub fn tracks(&self) -> &musiqlibrary::mediatracks::MediaTracks {
+        match self {
+            WatchTarget::Movie(movie) => &movie.tracks,
+            WatchTarget::Show(show_episode) => &show_episode.tracks,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum ExternalSpawn {
-    Mpv(path::PathBuf),
+    Mpv(Box<WatchTarget>),
     LaunchEmulator(musiqcore::model::gl::consoles::GameConsole, path::PathBuf),
 }
 
@@ -19,6 +19,8 @@ pub enum Page {
     PlaylistList(PlaylistListState),
     PlaylistView(PlaylistViewState),
     GenreHome,
+    Stats(StatsState),
+    MusicBrainzMatching(MusicBrainzMatchingState),
     TrackList(TrackListState),
     AlbumList(AlbumListState),
     ArtistList(ArtistListState),
@@ -62,6 +64,8 @@ impl Page {
             Page::PlaylistList(_) => "PlaylistList",
             Page::PlaylistView(_) => "PlaylistView",
             Page::GenreHome => "GenreHome",
+            Page::Stats(_) => "Stats",
+            Page::MusicBrainzMatching(_) => "MusicBrainzMatching",
             Page::TrackList(_) => "TrackList",
             Page::AlbumList(_) => "AlbumList",
             Page::ArtistList(_) => "ArtistList",
@@ -114,10 +118,15 @@ pub struct SearchPageState {
 pub enum SearchDomainResults {
     Music(Option<model::SimpleSearchResults>),
     Movies(Option<model::MovieSearchResults>),
+    Shows(Option<musiqcore::model::shows::ShowSearchResults>),
+    Games(Option<Vec<musiqcore::model::gl::GameSearchResult>>),
+    Everything(Option<model::CombinedSearchResults>),
 }
 
 #[derive(Debug)]
-pub struct PlayQueueState {}
+pub struct PlayQueueState {
+    pub new_playlist_name: String,
+}
 
 #[derive(Debug)]
 pub struct PlaylistListState {
@@ -129,6 +138,17 @@ pub struct PlaylistViewState {
     pub playlist_id: u32,
 }
 
+#[derive(Debug)]
+pub struct StatsState {
+    pub stats: Box<model::stats::ListeningStats>,
+}
+
+#[derive(Debug)]
+pub struct MusicBrainzMatchingState {
+    pub search_query: String,
+    pub candidates: Vec<model::musicbrainzlib::ArtistMatchCandidate>,
+}
+
 #[derive(Debug)]
 pub struct TrackListState {
     pub sort_key: model::TrackSortKey,
@@ -210,7 +230,7 @@ pub struct MovieListState {
 
 #[derive(Debug)]
 pub struct MovieViewState {
-    pub movie: video::MovieMetadata,
+    pub movie: Box<video::MovieMetadata>,
     pub movie_size: Option<model::MovieSize>,
     pub maybe_current_sort_order: Option<model::MovieSortPlacement>,
 }
@@ -27,8 +27,11 @@ pub struct AppState {
     pub library: model::LibraryState,
     pub video_library: model::VideoLibraryState,
     pub show_library: musiqcore::model::shows::ShowLibraryState,
+    pub show_art: model::ShowArt,
     pub game_library: musiqcore::model::gl::GameLibraryState,
 
+    pub resume_tracker: musiqcore::datastore::jsonbacked::resumetracker::ResumeTracker,
+
     pub player_info: PlayerInfo,
 
     pub app_images: embedded::AppImages,
@@ -60,9 +63,11 @@ pub struct MessageInfo {
 /// State for the Play Queue (and Current Track and Play History)
 pub struct PlayQueueInfo {
     pub play_queue_visible: bool,
+    pub repeat_mode: shared::RepeatMode,
     pub play_history: Vec<shared::PlayQueueEntry>,
     pub current_playback: Option<shared::CurrentPlayback>,
     pub play_queue: Vec<shared::PlayQueueEntry>,
+    pub radio: Option<model::radio::RadioStation>,
 }
 
 pub struct ActionState {
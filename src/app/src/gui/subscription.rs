@@ -126,6 +126,9 @@ pub fn keybinding_subscription_fn(
         (keyboard::Key::Named(key::Named::Space), Mods::None) => Some(
             message::Message::PlaybackRequest(shared::PlaybackRequest::PlayPauseToggle),
         ),
+        (keyboard::Key::Character("r"), Mods::None) => Some(message::Message::PlaybackRequest(
+            shared::PlaybackRequest::CycleRepeatMode,
+        )),
 
         (keyboard::Key::Named(key::Named::Backspace), Mods::None) => Some(
             message::Message::HistoryNav(message::HistoryDirection::Backwards),
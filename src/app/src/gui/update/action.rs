@@ -1,5 +1,6 @@
 use iced::Command;
 
+use crate::model;
 use crate::shared;
 
 use super::super::message::{self, Message};
@@ -7,6 +8,8 @@ use super::super::state::{self, AppState};
 
 use super::common;
 use super::loaded;
+use super::nav;
+use super::playback;
 use super::volume;
 
 pub fn handle_action(app: &mut AppState, action: message::Action) -> Command<message::Message> {
@@ -102,8 +105,82 @@ pub fn handle_action(app: &mut AppState, action: message::Action) -> Command<mes
             };
             Command::none()
         }
+        message::Action::SavePlayQueueAsPlaylist(playlist_name) => {
+            let playlist_name = playlist_name.trim().to_string();
+            if playlist_name.is_empty() {
+                println!("not saving play queue: the playlist needs a name");
+                return Command::none();
+            }
+            if !app
+                .player_info
+                .play_queue_info
+                .play_queue
+                .iter()
+                .any(|entry| matches!(entry, shared::PlayQueueEntry::Track(_)))
+            {
+                println!("not saving play queue: it has no tracks");
+                return Command::none();
+            }
+            let playlist_id = app.library.user_playlists.add_playlist(playlist_name);
+            for play_queue_entry in app.player_info.play_queue_info.play_queue.iter() {
+                match play_queue_entry {
+                    shared::PlayQueueEntry::Track(play_queue_track) => {
+                        match app.library.user_playlists.add_track_to_playlist(
+                            playlist_id,
+                            play_queue_track.track.metadata.to_unique_id(),
+                        ) {
+                            Ok(_) => (),
+                            Err(err_string) => {
+                                println!("error saving play queue to playlist: {}", err_string)
+                            }
+                        }
+                    }
+                    shared::PlayQueueEntry::Action(shared::PlayQueueAction::Pause) => (),
+                }
+            }
+            loaded::update_state(
+                app,
+                message::PlaylistNavMessage::PlaylistView(playlist_id).into_message(),
+            )
+        }
         message::Action::ToggleShuffleOnAdd => {
             app.action_state.group_buttons_shuffle = !app.action_state.group_buttons_shuffle;
+            Command::perform(
+                common::backend_sender(
+                    app.player_info.backend_message_sender.clone(),
+                    shared::GUIToBackendMessage::BackendPlayback(
+                        shared::PlaybackRequest::SetShuffle(app.action_state.group_buttons_shuffle),
+                    ),
+                )
+                .send_message(),
+                Message::ErrorResponse,
+            )
+        }
+        message::Action::SetShuffleMode(shuffle_mode) => {
+            if let Err(e) = app.config.save_shuffle_mode(shuffle_mode) {
+                println!("could not save the shuffle mode: {}", e);
+            }
+            Command::none()
+        }
+        message::Action::StartRadio(seed) => {
+            let mut station = model::radio::RadioStation::new(&app.library, seed.clone());
+            let exclude = playback::play_queue_track_ids(&app.player_info.play_queue_info);
+            let mut tracks = match seed {
+                model::radio::RadioSeed::Track(ref track_id) => {
+                    vec![app.library.get_track(track_id).clone()]
+                }
+                _ => Vec::new(),
+            };
+            tracks.append(&mut station.next_tracks(
+                &app.library,
+                &exclude,
+                model::radio::RADIO_BATCH_SIZE,
+            ));
+            app.player_info.play_queue_info.radio = Some(station);
+            playback::handle_playback_request(app, shared::PlaybackRequest::PlaySongs(tracks))
+        }
+        message::Action::StopRadio => {
+            app.player_info.play_queue_info.radio = None;
             Command::none()
         }
         message::Action::TogglePlayQueueVisible => {
@@ -117,6 +194,12 @@ pub fn handle_action(app: &mut AppState, action: message::Action) -> Command<mes
                 state::Page::PlaylistList(playlist_page_state) => {
                     playlist_page_state.new_playlist_name = new_text
                 }
+                state::Page::PlayQueue(play_queue_page_state) => {
+                    play_queue_page_state.new_playlist_name = new_text
+                }
+                state::Page::MusicBrainzMatching(matching_page_state) => {
+                    matching_page_state.search_query = new_text
+                }
                 no_text_input_page => {
                     println!("no text to update for page: {:?}", no_text_input_page)
                 }
@@ -130,6 +213,12 @@ pub fn handle_action(app: &mut AppState, action: message::Action) -> Command<mes
             ),
             _ => Command::none(),
         },
+        message::Action::ShowWatch(show_watch_action) => {
+            handle_show_watch_action(app, show_watch_action)
+        }
+        message::Action::MusicBrainz(musicbrainz_action) => {
+            handle_musicbrainz_action(app, musicbrainz_action)
+        }
         message::Action::Notify(notification_message) => match notification_message {
             message::NotificationMessage::OnScreen(notification_type) => {
                 println!("Got this message: {:?}", notification_type);
@@ -158,3 +247,100 @@ pub fn handle_action(app: &mut AppState, action: message::Action) -> Command<mes
         ]),
     }
 }
+
+fn handle_show_watch_action(
+    app: &mut AppState,
+    show_watch_action: message::ShowWatchAction,
+) -> Command<message::Message> {
+    let show_library = match app.show_library.get_shows_if_exists_mut() {
+        Some(show_library) => show_library,
+        None => {
+            println!("no show library to record watching in");
+            return Command::none();
+        }
+    };
+    match show_watch_action {
+        message::ShowWatchAction::MarkSeasonWatched(show_key, season_number) => {
+            show_library.mark_season_watched(&show_key, season_number)
+        }
+        message::ShowWatchAction::MarkSeasonUnwatched(show_key, season_number) => {
+            show_library.mark_season_unwatched(&show_key, season_number)
+        }
+        message::ShowWatchAction::MarkShowWatched(show_key) => {
+            show_library.mark_show_watched(&show_key)
+        }
+        message::ShowWatchAction::MarkShowUnwatched(show_key) => {
+            show_library.mark_show_unwatched(&show_key)
+        }
+        message::ShowWatchAction::RemoveEpisodeView(episode_key, view_time) => show_library
+            .get_tracker_mut()
+            .remove_episode_view(&episode_key, &view_time),
+    };
+    Command::none()
+}
+
+fn handle_musicbrainz_action(
+    app: &mut AppState,
+    musicbrainz_action: message::MusicBrainzAction,
+) -> Command<message::Message> {
+    match musicbrainz_action {
+        message::MusicBrainzAction::ApproveArtist(artist_id, mb_artist) => {
+            let artist_info = app.library.get_artist_info(artist_id);
+            app.library
+                .musicbrainz_library
+                .approve_artist(&artist_info, mb_artist);
+            refresh_musicbrainz_matching(app);
+            Command::none()
+        }
+        message::MusicBrainzAction::RejectArtistMatch(artist_id) => {
+            let artist_info = app.library.get_artist_info(artist_id);
+            match app
+                .library
+                .musicbrainz_library
+                .reject_artist_match(&artist_info)
+            {
+                Ok(()) => (),
+                Err(e) => println!("error rejecting musicbrainz match: {}", e),
+            };
+            refresh_musicbrainz_matching(app);
+            Command::none()
+        }
+        message::MusicBrainzAction::SearchArtist(artist_id, query) => {
+            let client = app.library.musicbrainz_library.client.clone();
+            Command::perform(
+                async move {
+                    client
+                        .query_for_artist_raw(query)
+                        .map_err(|e| e.to_string())
+                },
+                move |result| {
+                    Message::Action(message::Action::MusicBrainz(
+                        message::MusicBrainzAction::ArtistSearched(artist_id, result),
+                    ))
+                },
+            )
+        }
+        message::MusicBrainzAction::ArtistSearched(artist_id, result) => {
+            match result {
+                Ok(raw_results) => {
+                    let artist_info = app.library.get_artist_info(artist_id);
+                    app.library
+                        .musicbrainz_library
+                        .save_artist_search(&artist_info, raw_results);
+                    refresh_musicbrainz_matching(app);
+                }
+                Err(e) => println!("error searching musicbrainz: {}", e),
+            };
+            Command::none()
+        }
+    }
+}
+
+fn refresh_musicbrainz_matching(app: &mut AppState) {
+    let candidates = nav::musicbrainz_match_candidates(&app.library);
+    if let state::Page::MusicBrainzMatching(ref mut matching_page_state) =
+        app.page_state.current_page
+    {
+        matching_page_state.candidates = candidates;
+    }
+}
@@ -11,19 +11,42 @@ pub fn handle_external_request(
     external_request: message::ExternalRequest,
 ) -> Command<message::Message> {
     match external_request {
-        message::ExternalRequest::PlayShow(show_episode) => {
-            match app.show_library.get_shows_if_exists_mut() {
-                Some(show_library) => {
-                    let tracker = show_library.get_tracker_mut();
-                    tracker.mark_episode_viewed_now(show_episode.get_key());
+        message::ExternalRequest::PlayMovie(movie) => loaded::update_state(
+            app,
+            message::ExternalSpawn::Mpv(Box::new(message::WatchTarget::Movie(movie)))
+                .into_message(),
+        ),
+        message::ExternalRequest::PlayShow(show_episode) => loaded::update_state(
+            app,
+            message::ExternalSpawn::Mpv(Box::new(message::WatchTarget::Show(show_episode)))
+                .into_message(),
+        ),
+        message::ExternalRequest::FinishedWatching(target, progress) => {
+            let completed = match (progress.position_seconds, progress.duration_seconds) {
+                (Some(position), Some(duration)) => {
+                    app.resume_tracker
+                        .record_progress(target.path(), position, duration)
+                }
+                _ => false,
+            };
+
+            if completed {
+                match target {
+                    message::WatchTarget::Movie(ref movie) => {
+                        app.video_library.mark_movie_viewed_now(movie)
+                    }
+                    message::WatchTarget::Show(ref show_episode) => {
+                        match app.show_library.get_shows_if_exists_mut() {
+                            Some(show_library) => {
+                                let tracker = show_library.get_tracker_mut();
+                                tracker.mark_episode_viewed_now(show_episode.get_key());
+                            }
+                            None => println!("we can't actually store the recording of this show"),
+                        }
+                    }
                 }
-                None => println!("we can't actually store the recording of this show"),
             }
-            let _follow_up_empty_action = loaded::update_state(
-                app,
-                message::ExternalSpawn::Mpv(show_episode.full_path).into_message(),
-            );
+            Command::none()
         }
     }
-    Command::none()
 }
@@ -71,6 +71,11 @@ pub fn update_state(app: &mut AppState, message: Message) -> Command<Message> {
             };
             Command::none()
         }
-        Message::ExternalSpawn(spawn_cmd) => spawner::exec_cmd(&app.game_library, spawn_cmd),
+        Message::ExternalSpawn(spawn_cmd) => spawner::exec_cmd(
+            &app.config,
+            &app.game_library,
+            &app.resume_tracker,
+            spawn_cmd,
+        ),
     }
 }
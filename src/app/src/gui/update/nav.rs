@@ -22,7 +22,9 @@ pub fn handle_nav(
             Command::none()
         }
         NavMessage::PlayQueueFocus => {
-            app.page_state.current_page = Page::PlayQueue(state::PlayQueueState {});
+            app.page_state.current_page = Page::PlayQueue(state::PlayQueueState {
+                new_playlist_name: "".to_string(),
+            });
             Command::none()
         }
         NavMessage::Playlist(message::PlaylistNavMessage::PlaylistView(playlist_id)) => {
@@ -36,11 +38,12 @@ pub fn handle_nav(
             Command::none()
         }
         NavMessage::SearchPage(query, domain, perform_search) => {
+            let search_query = musiqcore::model::search::SearchQuery::new(&query);
             let computed_results = match domain {
                 model::SearchDomain::Music => {
                     state::SearchDomainResults::Music(match perform_search {
                         true => {
-                            let search_results = app.library.search(query.clone());
+                            let search_results = app.library.search(&search_query);
                             let mapped_search_results = model::SimpleSearchResults {
                                 artists: search_results.artists.into_iter().collect(),
                                 albums: search_results.albums.into_iter().collect(),
@@ -55,12 +58,43 @@ pub fn handle_nav(
                 model::SearchDomain::Movies => {
                     state::SearchDomainResults::Movies(match perform_search {
                         true => {
-                            let search_results = app.video_library.search_movies(query.clone());
+                            let search_results = app.video_library.search_movies(&search_query);
                             Some(search_results)
                         }
                         false => None,
                     })
                 }
+                model::SearchDomain::Shows => {
+                    state::SearchDomainResults::Shows(match perform_search {
+                        true => app
+                            .show_library
+                            .get_shows_if_exists()
+                            .as_ref()
+                            .map(|show_library| show_library.search(&search_query)),
+                        false => None,
+                    })
+                }
+                model::SearchDomain::Games => {
+                    state::SearchDomainResults::Games(match perform_search {
+                        true => Some(app.game_library.search(&search_query)),
+                        false => None,
+                    })
+                }
+                model::SearchDomain::Everything => {
+                    state::SearchDomainResults::Everything(match perform_search {
+                        true => Some(model::CombinedSearchResults::combine(
+                            &search_query,
+                            app.library.search(&search_query),
+                            app.video_library.search_movies(&search_query),
+                            app.show_library
+                                .get_shows_if_exists()
+                                .as_ref()
+                                .map(|show_library| show_library.search(&search_query)),
+                            app.game_library.search(&search_query),
+                        )),
+                        false => None,
+                    })
+                }
             };
 
             app.page_state.current_page = Page::Search(state::SearchPageState {
@@ -78,6 +112,31 @@ pub fn handle_nav(
                 app.page_state.current_page = Page::GenreHome;
                 Command::none()
             }
+            message::MusicNavMessage::Stats(range) => {
+                let stats = model::stats::ListeningStats::compute(
+                    app.library
+                        .raw_library
+                        .get_all_tracks()
+                        .into_iter()
+                        .map(|track| &track.metadata)
+                        .collect(),
+                    &app.library.play_history,
+                    &app.library.play_history,
+                    range,
+                );
+                app.page_state.current_page = Page::Stats(state::StatsState {
+                    stats: Box::new(stats),
+                });
+                Command::none()
+            }
+            message::MusicNavMessage::MusicBrainzMatching => {
+                app.page_state.current_page =
+                    Page::MusicBrainzMatching(state::MusicBrainzMatchingState {
+                        search_query: "".to_string(),
+                        candidates: musicbrainz_match_candidates(&app.library),
+                    });
+                Command::none()
+            }
             message::MusicNavMessage::TrackList(page, sort, sort_order) => {
                 app.page_state.current_page = Page::TrackList(state::TrackListState {
                     page,
@@ -443,7 +502,7 @@ pub fn handle_nav(
             }
             message::MovieNavMessage::MovieView(movie, movie_size, maybe_current_sort_order) => {
                 app.page_state.current_page = Page::MovieView(state::MovieViewState {
-                    movie,
+                    movie: Box::new(movie),
                     movie_size,
                     maybe_current_sort_order,
                 });
@@ -516,3 +575,15 @@ pub fn handle_nav(
         },
     }
 }
+
+pub fn musicbrainz_match_candidates(
+    library: &model::LibraryState,
+) -> Vec<model::musicbrainzlib::ArtistMatchCandidate> {
+    library.musicbrainz_library.artist_match_candidates(
+        library
+            .get_artist_map()
+            .values()
+            .filter(|artist| !artist.albums.is_empty())
+            .map(|artist| &artist.artist_info),
+    )
+}
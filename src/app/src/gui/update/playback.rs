@@ -1,9 +1,12 @@
+use std::collections::BTreeSet;
+
 use iced::Command;
 
+use crate::model;
 use crate::shared;
 
 use super::super::message::{self, Message};
-use super::super::state::AppState;
+use super::super::state::{self, AppState};
 
 use super::action;
 use super::common;
@@ -15,9 +18,81 @@ pub fn handle_set_play_queue(
     app.player_info.play_queue_info.play_history = new_play_queue.play_history;
     app.player_info.play_queue_info.current_playback = new_play_queue.current_playback;
     app.player_info.play_queue_info.play_queue = new_play_queue.play_queue;
+    app.player_info.play_queue_info.repeat_mode = new_play_queue.repeat_mode;
+    app.action_state.group_buttons_shuffle = new_play_queue.shuffle;
     app.player_info.playing = new_play_queue.playing;
 
-    Command::none()
+    top_up_radio(app)
+}
+
+/// Keep the play queue topped up with radio picks while a radio is running
+fn top_up_radio(app: &mut AppState) -> Command<message::Message> {
+    let exclude = play_queue_track_ids(&app.player_info.play_queue_info);
+    let queued_track_count = app
+        .player_info
+        .play_queue_info
+        .play_queue
+        .iter()
+        .filter(|entry| matches!(entry, shared::PlayQueueEntry::Track(_)))
+        .count();
+
+    let picks = match app.player_info.play_queue_info.radio {
+        Some(ref mut station) => {
+            if let Some(ref awaiting) = station.awaiting {
+                if !exclude.contains(awaiting) {
+                    return Command::none();
+                }
+            }
+            station.awaiting = None;
+
+            if queued_track_count >= model::radio::RADIO_MIN_QUEUED {
+                return Command::none();
+            }
+
+            station.next_tracks(&app.library, &exclude, model::radio::RADIO_BATCH_SIZE)
+        }
+        None => return Command::none(),
+    };
+
+    if picks.is_empty() {
+        println!("radio ran out of tracks to pick");
+        app.player_info.play_queue_info.radio = None;
+        return Command::none();
+    }
+
+    Command::perform(
+        common::backend_sender(
+            app.player_info.backend_message_sender.clone(),
+            shared::GUIToBackendMessage::BackendPlayback(shared::PlaybackRequest::AppendSongs(
+                picks,
+            )),
+        )
+        .send_message(),
+        Message::ErrorResponse,
+    )
+}
+
+/// Every track in the play history, current playback and play queue
+pub fn play_queue_track_ids(
+    play_queue_info: &state::PlayQueueInfo,
+) -> BTreeSet<musiqlibrary::TrackUniqueIdentifier> {
+    let mut track_ids = BTreeSet::new();
+    for entry in play_queue_info
+        .play_history
+        .iter()
+        .chain(play_queue_info.play_queue.iter())
+    {
+        match entry {
+            shared::PlayQueueEntry::Track(play_queue_track) => {
+                track_ids.insert(play_queue_track.track.metadata.to_unique_id());
+            }
+            shared::PlayQueueEntry::Action(shared::PlayQueueAction::Pause) => (),
+        }
+    }
+    if let Some(shared::CurrentPlayback::Track(ref current)) = play_queue_info.current_playback {
+        track_ids.insert(current.track.metadata.to_unique_id());
+    }
+    track_ids
 }
 
 pub fn handle_playback_request(
@@ -31,6 +106,10 @@ pub fn handle_playback_request(
         _ => None,
     };
 
+    if let shared::PlaybackRequest::ClearPlayQueue = playback_request {
+        app.player_info.play_queue_info.radio = None;
+    }
+
     match maybe_tracks {
         Some(tracks) => {
             for track in tracks.iter() {
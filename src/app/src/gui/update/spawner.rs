@@ -1,21 +1,62 @@
 use std::ffi;
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
+use iced::futures::channel::oneshot;
 use iced::Command;
 
-use crate::gui::message;
+use musiqcore::datastore::jsonbacked::resumetracker;
+
+use crate::gui::message::{self, Message};
+use crate::util::mpv;
+
+/// Keeps the IPC socket of each mpv launched by this process distinct
+static MPV_SESSION_COUNT: AtomicUsize = AtomicUsize::new(0);
 
 pub fn exec_cmd(
+    config: &musiqcore::model::app::AppConfigState,
     game_library: &musiqcore::model::gl::GameLibraryState,
+    resume_tracker: &resumetracker::ResumeTracker,
     cmd: message::ExternalSpawn,
 ) -> Command<message::Message> {
     match cmd {
-        message::ExternalSpawn::Mpv(movie_path) => {
-            let _wanted_to_be_detached = process::Command::new("mpv")
-                .arg(movie_path.into_os_string())
-                .spawn()
+        message::ExternalSpawn::Mpv(target) => {
+            let socket_path = std::env::temp_dir().join(format!(
+                "musiqapp-mpv-{}-{}.sock",
+                process::id(),
+                MPV_SESSION_COUNT.fetch_add(1, Ordering::SeqCst)
+            ));
+            let launch_options = mpv::LaunchOptions {
+                start_seconds: resume_tracker
+                    .get_resume_position(target.path())
+                    .map(|resume_position| resume_position.position_seconds),
+                audio_language: config.preferred_audio_language.clone(),
+                subtitle_language: config.preferred_subtitle_language.clone(),
+                subtitle_files: target
+                    .tracks()
+                    .sidecar_subtitles
+                    .iter()
+                    .map(|subtitle| subtitle.path.clone())
+                    .collect(),
+            };
+
+            let mut child = mpv::launch(target.path(), &socket_path, &launch_options)
                 .expect("Failed to execute command");
-            Command::none()
+
+            let (sender, receiver) = oneshot::channel();
+            let _detached = thread::spawn(move || {
+                let progress = mpv::follow_until_exit(&mut child, &socket_path);
+                let _ = std::fs::remove_file(&socket_path);
+                let _ = sender.send(progress);
+            });
+
+            Command::perform(receiver, move |maybe_progress| match maybe_progress {
+                Ok(progress) => Message::ExternalRequest(
+                    message::ExternalRequest::FinishedWatching(*target, progress),
+                ),
+                Err(_) => Message::ErrorResponse(Err("lost track of mpv".to_string())),
+            })
         }
         message::ExternalSpawn::LaunchEmulator(console, game_rom_path) => {
             let cwd = game_library
@@ -1,7 +1,11 @@
 mod links;
 mod pagified;
+mod scanerrors;
 mod thumbnail;
+mod watchprogress;
 
 pub use links::*;
 pub use pagified::*;
+pub use scanerrors::*;
 pub use thumbnail::*;
+pub use watchprogress::*;
@@ -0,0 +1,24 @@
+use iced::widget::Column;
+
+use crate::gui::message::Message;
+
+use super::super::elements::*;
+
+/// The files a library scan found but could not load, with why, or nothing when all loaded
+pub fn scan_error_list<'a>(errors: &[musiqlibrary::ScanError]) -> Column<'a, Message> {
+    let mut column = Column::new().spacing(2);
+    if errors.is_empty() {
+        return column;
+    }
+
+    column = column.push(h2(format!("{} files could not be loaded", errors.len())));
+    for error in errors.iter() {
+        column = column.push(
+            line_row()
+                .spacing(10)
+                .push(bright_paragraph(error.path.to_string_lossy().to_string()))
+                .push(paragraph(error.reason.clone())),
+        );
+    }
+    column
+}
@@ -0,0 +1,34 @@
+use iced::widget::{ProgressBar, Row};
+use iced::Length;
+
+use musiqcore::datastore::jsonbacked::showtracker;
+
+use crate::gui::message::Message;
+
+use super::super::common;
+use super::super::elements::*;
+
+/// A bar of how many episodes have been watched, with the count and the runtime left
+pub fn watch_progress<'a>(progress: &showtracker::WatchProgress) -> Row<'a, Message> {
+    line_row()
+        .spacing(10)
+        .push(
+            ProgressBar::new(
+                0.0..=(progress.total.max(1) as f32),
+                progress.watched as f32,
+            )
+            .width(Length::Fixed(200.0))
+            .height(Length::Fixed(10.0)),
+        )
+        .push(bright_paragraph(format!(
+            "{}/{} watched",
+            progress.watched, progress.total
+        )))
+        .push(paragraph(match progress.is_complete() {
+            true => "done".to_string(),
+            false => format!(
+                "{} left",
+                common::format_duration(progress.remaining.as_secs())
+            ),
+        }))
+}
@@ -1,3 +1,5 @@
+use std::path;
+
 use iced::widget::image;
 use iced::widget::{Button, Column, Container, Image, Row, Text};
 use iced::{Alignment, Element, Length};
@@ -113,7 +115,32 @@ pub fn movie_image(
     size: model::MovieSize,
     fixed_width: bool,
 ) -> Image<image::Handle> {
-    Image::new(iced::widget::image::Handle::from_memory(movie_cover_bytes))
+    sized_movie_image(
+        iced::widget::image::Handle::from_memory(movie_cover_bytes),
+        size,
+        fixed_width,
+    )
+}
+
+/// A movie sized image read from disk when it is drawn, like the cached show art
+pub fn movie_image_from_path<P: Into<path::PathBuf>>(
+    movie_cover_path: P,
+    size: model::MovieSize,
+    fixed_width: bool,
+) -> Image<image::Handle> {
+    sized_movie_image(
+        iced::widget::image::Handle::from_path(movie_cover_path),
+        size,
+        fixed_width,
+    )
+}
+
+fn sized_movie_image(
+    handle: image::Handle,
+    size: model::MovieSize,
+    fixed_width: bool,
+) -> Image<image::Handle> {
+    Image::new(handle)
         .width(if fixed_width {
             Length::Fixed(size.height() as f32 * 2.4 / 3.0)
         } else {
@@ -8,8 +8,6 @@ use crate::state::{self, ActionState, Page, PlayerInfo};
 
 use crate::datastore::staticassets::embedded;
 
-use super::super::elements::*;
-
 use super::pages;
 
 pub fn render_page<'a>(
@@ -18,9 +16,11 @@ pub fn render_page<'a>(
     library: &'a model::LibraryState,
     movie_library: &'a model::VideoLibraryState,
     show_library: &'a musiqcore::model::shows::ShowLibraryState,
+    show_art: &'a model::ShowArt,
     game_library: &'a musiqcore::model::gl::GameLibraryState,
     app_images: &embedded::AppImages,
     action_state: &'a ActionState,
+    config: &'a musiqcore::model::app::AppConfigState,
     play_queue_visible: bool,
     player_info: &'a PlayerInfo,
 ) -> (Vec<(String, Message)>, Container<'a, Message>) {
@@ -29,19 +29,26 @@ pub fn render_page<'a>(
 
     let ret_page = match current_page {
         Page::Home(ref state) => pages::home::home_page(app_images, state),
-        Page::Config(state::ConfigState {}) => pages::config::config_page(),
-        Page::PlayQueue(state::PlayQueueState {}) => {
-            Container::new(bright_paragraph("The Play Queue"))
-        }
+        Page::Config(state::ConfigState {}) => pages::config::config_page(config),
+        Page::PlayQueue(ref state) => pages::playqueue::play_queue_page(config, player_info, state),
         Page::MusicHome => pages::musichome::music_home(app_images),
         Page::PlaylistView(ref state) => {
-            pages::playlist::playlist_view(library, action_state, player_info, state)
+            pages::playlist::playlist_view(library, action_state, config, player_info, state)
         }
         Page::PlaylistList(ref state) => pages::playlists::playlist_list_view(library, state),
-        Page::Search(ref state) => {
-            pages::search::search_page(library, movie_library, app_images, state)
-        }
+        Page::Search(ref state) => pages::search::search_page(
+            library,
+            movie_library,
+            show_library,
+            show_art,
+            app_images,
+            state,
+        ),
         Page::GenreHome => pages::musicgenrehome::genre_home(),
+        Page::Stats(ref state) => pages::stats::stats_page(state),
+        Page::MusicBrainzMatching(ref state) => {
+            pages::musicbrainzmatching::musicbrainz_matching_page(state)
+        }
         Page::TrackList(ref state) => pages::tracklist::track_list(library, state),
         Page::AlbumList(ref state) => {
             pages::albumlist::album_list(library, play_queue_visible, state)
@@ -52,9 +59,13 @@ pub fn render_page<'a>(
         Page::ArtistAlbumsView(ref state) => {
             pages::artistalbums::artist_album_list(library, play_queue_visible, state)
         }
-        Page::ArtistAlbumView(ref state) => {
-            pages::artistalbum::artist_album_view_state(library, action_state, player_info, state)
-        }
+        Page::ArtistAlbumView(ref state) => pages::artistalbum::artist_album_view_state(
+            library,
+            action_state,
+            config,
+            player_info,
+            state,
+        ),
         Page::ArtistAlbumFeaturedInPlaylist(ref state) => {
             pages::artistalbuminplaylist::artist_album_featured_in_playlist_state(library, state)
         }
@@ -70,7 +81,7 @@ pub fn render_page<'a>(
         Page::ArtistFeaturedInPlaylist(ref state) => {
             pages::artistinplaylists::artist_in_playlist_view_state(library, state)
         }
-        Page::MovieHome => pages::moviehome::movie_home(app_images),
+        Page::MovieHome => pages::moviehome::movie_home(movie_library, app_images),
         Page::MovieList(ref state) => pages::movielist::movie_list(
             movie_library,
             state,
@@ -95,16 +106,16 @@ pub fn render_page<'a>(
         Page::NDSList => pages::gamends::nds_list(game_library),
         Page::GameCubeList => pages::gamengc::ngc_list(game_library),
         Page::WiiList => pages::gamewii::wii_list(game_library),
-        Page::ShowHome => pages::showhome::show_home(app_images),
-        Page::ShowList => pages::showlist::show_list(show_library, app_images),
+        Page::ShowHome => pages::showhome::show_home(show_library, app_images),
+        Page::ShowList => pages::showlist::show_list(show_library, show_art, app_images),
         Page::ShowSeriesView(series_key) => {
-            pages::showseriesview::show_series_view(show_library, app_images, series_key)
+            pages::showseriesview::show_series_view(show_library, show_art, app_images, series_key)
         }
         Page::ShowSeasonView(series_key, season_id) => {
-            pages::showseasonview::show_season_view(show_library, series_key, season_id)
+            pages::showseasonview::show_season_view(show_library, show_art, series_key, season_id)
         }
         Page::ShowContinueWatching => {
-            pages::showcontinue::show_continue_watching(show_library, app_images)
+            pages::showcontinue::show_continue_watching(show_library, show_art, app_images)
         }
     };
 
@@ -15,6 +15,7 @@ use super::super::super::style;
 pub fn artist_album_view_state<'a>(
     library: &'a model::LibraryState,
     action_state: &'a ActionState,
+    config: &'a musiqcore::model::app::AppConfigState,
     player_info: &'a PlayerInfo,
     state: &'a state::ArtistAlbumViewState,
 ) -> Container<'a, Message> {
@@ -36,6 +37,7 @@ pub fn artist_album_view_state<'a>(
                 total
             });
             let should_shuffle = action_state.group_buttons_shuffle;
+            let shuffle_mode = &config.shuffle_mode;
 
             let current_track = match player_info.play_queue_info.current_playback {
                 Some(ref o) => match o {
@@ -117,6 +119,11 @@ pub fn artist_album_view_state<'a>(
                                             album.album_info.total_duration.as_secs(),
                                         ),
                                     )))
+                                    .push(musicbrainz_release_info(
+                                        library
+                                            .musicbrainz_library
+                                            .get_album_info(artist_id, album_id),
+                                    ))
                                     .push(
                                         Row::new()
                                             .spacing(5)
@@ -127,7 +134,10 @@ pub fn artist_album_view_state<'a>(
                                                 .on_press(Message::PlaybackRequest(
                                                     shared::PlaybackRequest::PlaySongs(
                                                         if should_shuffle {
-                                                            shuffle::shuffle(tracks.clone())
+                                                            shuffle::shuffle_tracks(
+                                                                shuffle_mode,
+                                                                tracks.clone(),
+                                                            )
                                                         } else {
                                                             tracks.clone()
                                                         }
@@ -141,7 +151,10 @@ pub fn artist_album_view_state<'a>(
                                                 .on_press(Message::PlaybackRequest(
                                                     shared::PlaybackRequest::InsertSongs(
                                                         if should_shuffle {
-                                                            shuffle::shuffle(tracks.clone())
+                                                            shuffle::shuffle_tracks(
+                                                                shuffle_mode,
+                                                                tracks.clone(),
+                                                            )
                                                         } else {
                                                             tracks.clone()
                                                         },
@@ -156,12 +169,30 @@ pub fn artist_album_view_state<'a>(
                                                 .on_press(Message::PlaybackRequest(
                                                     shared::PlaybackRequest::AppendSongs(
                                                         if should_shuffle {
-                                                            shuffle::shuffle(tracks.clone())
+                                                            shuffle::shuffle_tracks(
+                                                                shuffle_mode,
+                                                                tracks.clone(),
+                                                            )
                                                         } else {
                                                             tracks.clone()
                                                         },
                                                     ),
                                                 )),
+                                            )
+                                            .push(
+                                                dark_button(
+                                                    bright_paragraph("~ Start Radio"),
+                                                )
+                                                .on_press(Message::Action(
+                                                    message::Action::StartRadio(
+                                                        model::radio::RadioSeed::Album(
+                                                            musiqlibrary::AlbumUniqueIdentifier::new(
+                                                                *artist_id,
+                                                                *album_id,
+                                                            ),
+                                                        ),
+                                                    ),
+                                                )),
                                             ),
                                     )
                                     .push(
@@ -369,6 +400,18 @@ pub fn artist_album_view_state<'a>(
                                                         ),
                                                     )),
                                                 )
+                                                .push(
+                                                    dark_button(
+                                                        bright_paragraph("~"),
+                                                    )
+                                                    .on_press(Message::Action(
+                                                        message::Action::StartRadio(
+                                                            model::radio::RadioSeed::Track(
+                                                                musiqlibrary::TrackUniqueIdentifier::from_track(&track.metadata),
+                                                            ),
+                                                        ),
+                                                    )),
+                                                )
                                                 .push(
                                                     match library.user_playlists.get_default_playlist_id() {
                                                         Some(default_playlist_id) => Container::new(dark_button(
@@ -466,3 +509,89 @@ pub fn artist_album_view_state<'a>(
         }
     }
 }
+
+fn musicbrainz_release_info<'a>(
+    maybe_release: Option<&musicbrainz::Release>,
+) -> Column<'a, Message> {
+    match maybe_release {
+        Some(release) => {
+            let mut ret = Column::new().spacing(4).push(
+                line_row()
+                    .spacing(6)
+                    .push(h3("MusicBrainz:"))
+                    .push(paragraph(format!(
+                        "{} ({}, {})",
+                        release.title,
+                        release.date.clone().unwrap_or("no date".to_string()),
+                        release.country.clone().unwrap_or("no country".to_string()),
+                    )))
+                    .push(paragraph(release.id.clone())),
+            );
+
+            let labels = release
+                .label_info
+                .iter()
+                .flatten()
+                .map(|label_info| {
+                    format!(
+                        "{}{}",
+                        match label_info.label {
+                            Some(ref label) => label.name.clone(),
+                            None => "no label".to_string(),
+                        },
+                        match label_info.catalog_number {
+                            Some(ref catalog_number) => format!(" [{}]", catalog_number),
+                            None => "".to_string(),
+                        }
+                    )
+                })
+                .collect::<Vec<_>>();
+            if !labels.is_empty() {
+                ret = ret.push(
+                    line_row()
+                        .spacing(6)
+                        .push(h3("Labels:"))
+                        .push(paragraph(labels.join(", "))),
+                );
+            }
+
+            if let Some(ref media) = release.media {
+                ret = ret.push(
+                    line_row().spacing(6).push(h3("Media:")).push(paragraph(
+                        media
+                            .iter()
+                            .map(|medium| {
+                                format!(
+                                    "{} ({} tracks)",
+                                    medium.format.clone().unwrap_or("unknown".to_string()),
+                                    medium.track_count
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    )),
+                );
+            }
+
+            match release.tags {
+                Some(ref tags) if !tags.is_empty() => {
+                    let mut sorted = tags.to_vec();
+                    sorted.sort_by_key(|tag| std::cmp::Reverse(tag.count));
+                    ret = ret.push(
+                        line_row().spacing(6).push(h3("Tags:")).push(paragraph(
+                            sorted
+                                .into_iter()
+                                .map(|tag| tag.name)
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                        )),
+                    );
+                }
+                _ => (),
+            };
+
+            ret
+        }
+        None => Column::new(),
+    }
+}
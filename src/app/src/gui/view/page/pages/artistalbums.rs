@@ -135,7 +135,17 @@ pub fn artist_album_list<'a>(
 
                 Container::new(
                     Column::new()
-                        .push(h1(artist.artist_name.clone()))
+                        .push(
+                            Row::new()
+                                .spacing(10)
+                                .align_items(iced::Alignment::Center)
+                                .push(h1(artist.artist_name.clone()))
+                                .push(dark_button(bright_paragraph("~ Start Radio")).on_press(
+                                    Message::Action(message::Action::StartRadio(
+                                        model::radio::RadioSeed::Artist(*artist_id),
+                                    )),
+                                )),
+                        )
                         .push(artist_view_button_row)
                         .push(scrollable),
                 )
@@ -108,7 +108,10 @@ pub fn artist_info_view_state<'a>(
 
                     ret
                 }
-                None => Column::new().push(h2("No MusicBrainz Info found")),
+                None => Column::new().push(h2("No MusicBrainz Info found")).push(
+                    dark_button(bright_paragraph("Find a Match"))
+                        .on_press(message::MusicNavMessage::MusicBrainzMatching.into_message()),
+                ),
             };
 
             let body = Container::new(
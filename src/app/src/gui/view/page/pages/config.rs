@@ -1,12 +1,33 @@
-use iced::widget::Container;
+use iced::widget::{Column, Container, Row};
 
-use crate::gui::message::{user_nav_message, Message, NavMessage};
+use crate::gui::message::{self, user_nav_message, Message, NavMessage};
+use crate::util::shuffle;
 
 use super::super::super::elements::*;
 
-pub fn config_page<'a>() -> Container<'a, Message> {
+pub fn config_page<'a>(
+    config: &'a musiqcore::model::app::AppConfigState,
+) -> Container<'a, Message> {
+    let mut shuffle_modes = Row::new().spacing(5);
+    for shuffle_mode in shuffle::ShuffleMode::all().into_iter() {
+        let text = bright_paragraph(shuffle_mode.display_text());
+        shuffle_modes = shuffle_modes.push(if shuffle_mode == config.shuffle_mode {
+            dark_button(bright(text))
+        } else {
+            dark_button(dark(text)).on_press(Message::Action(message::Action::SetShuffleMode(
+                shuffle_mode,
+            )))
+        });
+    }
+
     Container::new(
-        dark_button(bright_paragraph("Reload Library"))
-            .on_press(user_nav_message(NavMessage::Config)),
+        Column::new()
+            .spacing(10)
+            .push(
+                dark_button(bright_paragraph("Reload Library"))
+                    .on_press(user_nav_message(NavMessage::Config)),
+            )
+            .push(h2("Shuffle Mode"))
+            .push(shuffle_modes),
     )
 }
@@ -24,14 +24,17 @@ pub mod moviehome;
 pub mod movielist;
 pub mod moviequery;
 pub mod movieseries;
+pub mod musicbrainzmatching;
 pub mod musicgenrehome;
 pub mod musichome;
 pub mod playlist;
 pub mod playlists;
+pub mod playqueue;
 pub mod search;
 pub mod showcontinue;
 pub mod showhome;
 pub mod showlist;
 pub mod showseasonview;
 pub mod showseriesview;
+pub mod stats;
 pub mod tracklist;
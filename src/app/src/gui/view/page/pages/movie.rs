@@ -4,12 +4,15 @@ use iced::Length;
 use crate::model;
 
 use crate::datastore::staticassets::embedded;
-use crate::gui::message::{self, ExternalSpawn, Message};
+use crate::gui::message::{self, ExternalRequest, Message};
 use crate::state;
 
 use super::super::super::common;
 use super::super::super::elements::*;
 
+/// How many views before the most recent one to list in the watch history
+const MAX_SHOWN_EARLIER_VIEWS: usize = 5;
+
 pub fn movie_page<'a>(
     movie_library: &'a model::VideoLibraryState,
     state: &'a state::MovieViewState,
@@ -48,7 +51,7 @@ pub fn movie_page<'a>(
 
             let movie_button = dark_button(movie_image).on_press(
                 message::MovieNavMessage::MovieView(
-                    state.movie.clone(),
+                    (*state.movie).clone(),
                     Some(toggle_to),
                     maybe_current_sort_order,
                 )
@@ -66,8 +69,8 @@ pub fn movie_page<'a>(
 
     let mut movie_info = Column::new().padding(10).spacing(10);
 
-    let play_button = Container::new(dark_button(h2("Play")).on_press(Message::ExternalSpawn(
-        ExternalSpawn::Mpv(state.movie.path.clone().to_path_buf()),
+    let play_button = Container::new(dark_button(h2("Play")).on_press(Message::ExternalRequest(
+        ExternalRequest::PlayMovie((*state.movie).clone()),
     )));
 
     let mut length = Column::new();
@@ -83,22 +86,57 @@ pub fn movie_page<'a>(
         .spacing(10)
         .push(play_and_length.width(Length::FillPortion(1)));
 
-    match state.movie.extra {
-        Some(ref extra_movie_metadata) => {
-            let mut release = Column::new();
-            release = release.push(h2("Release"));
-            release = release.push(h3(extra_movie_metadata
-                .release
-                .format("%Y/%m/%d")
-                .to_string()));
-            play_release_and_length =
-                play_release_and_length.push(release.width(Length::FillPortion(1)));
-        }
-        None => (),
-    };
+    if let Some(release_date) = state.movie.extra.as_ref().and_then(|extra| extra.release) {
+        let mut release = Column::new();
+        release = release.push(h2("Release"));
+        release = release.push(h3(release_date.format("%Y/%m/%d").to_string()));
+        play_release_and_length =
+            play_release_and_length.push(release.width(Length::FillPortion(1)));
+    }
 
     movie_info = movie_info.push(play_release_and_length);
 
+    let views = movie_library.tracker.get_views(&state.movie.get_id());
+    let mut history = Column::new().push(h2("Watched"));
+    match views.last() {
+        Some(last_viewed) => {
+            history = history.push(h3(format!(
+                "{} time{}, last on {}",
+                views.len(),
+                if views.len() == 1 { "" } else { "s" },
+                last_viewed.format("%Y/%m/%d %H:%M"),
+            )));
+            for view in views.iter().rev().skip(1).take(MAX_SHOWN_EARLIER_VIEWS) {
+                history = history.push(paragraph(view.format("%Y/%m/%d %H:%M").to_string()));
+            }
+        }
+        None => history = history.push(h3("Never")),
+    };
+    movie_info = movie_info.push(history);
+
+    let audio_summary = state.movie.tracks.audio_summary();
+    let subtitle_summary = state.movie.tracks.subtitle_summary();
+    movie_info = movie_info.push(
+        Column::new()
+            .push(h2("Tracks"))
+            .push(paragraph(format!(
+                "Audio: {}",
+                if audio_summary.is_empty() {
+                    "none found".to_string()
+                } else {
+                    audio_summary
+                }
+            )))
+            .push(paragraph(format!(
+                "Subtitles: {}",
+                if subtitle_summary.is_empty() {
+                    "none found".to_string()
+                } else {
+                    subtitle_summary
+                }
+            ))),
+    );
+
     match state.movie.extra {
         Some(ref extra) => {
             let mut genre_list = Column::new().spacing(10);
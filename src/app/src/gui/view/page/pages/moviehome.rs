@@ -5,12 +5,16 @@ use crate::model;
 
 use crate::datastore::staticassets::embedded;
 use crate::gui::message::{self, Message};
+use crate::gui::view::components;
 
 use super::super::super::common;
 use super::super::super::consts;
 use super::super::super::elements::*;
 
-pub fn movie_home<'a>(app_images: &embedded::AppImages) -> Container<'a, Message> {
+pub fn movie_home<'a>(
+    movie_library: &'a model::VideoLibraryState,
+    app_images: &embedded::AppImages,
+) -> Container<'a, Message> {
     let body_column = Column::new()
         .push(
             Row::new()
@@ -69,7 +73,10 @@ pub fn movie_home<'a>(app_images: &embedded::AppImages) -> Container<'a, Message
                 )))
                 .on_press(message::MovieNavMessage::SeriesList.into_message()),
             ),
-        );
+        )
+        .push(components::scan_error_list(
+            &movie_library.movies.scan_errors,
+        ));
 
     let body = Container::new(
         Column::new()
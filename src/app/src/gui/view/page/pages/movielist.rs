@@ -127,6 +127,24 @@ pub fn movie_list<'a>(
                         model::MovieSortKey::ByRelease.default_order(),
                         sort_key,
                     ))
+                    .push(sort_button(
+                        "Watched",
+                        model::MovieSortKey::LastWatched,
+                        model::MovieSortKey::LastWatched.default_order(),
+                        sort_key,
+                    ))
+                    .push(sort_button(
+                        "Watch Count",
+                        model::MovieSortKey::WatchCount,
+                        model::MovieSortKey::WatchCount.default_order(),
+                        sort_key,
+                    ))
+                    .push(sort_button(
+                        "Unwatched",
+                        model::MovieSortKey::NeverWatched,
+                        model::MovieSortKey::NeverWatched.default_order(),
+                        sort_key,
+                    ))
                     .push(sort_button(
                         "Random",
                         model::MovieSortKey::Random,
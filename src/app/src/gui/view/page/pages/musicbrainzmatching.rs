@@ -0,0 +1,117 @@
+use iced::widget::{Column, Container, Scrollable, Space, TextInput};
+use iced::Length;
+
+use crate::gui::message::{self, Message};
+use crate::state;
+
+use super::super::super::elements::*;
+
+/// How many artists to list at once, the rest show up as these get approved
+const SHOWN_CANDIDATE_COUNT: usize = 50;
+
+pub fn musicbrainz_matching_page<'a>(
+    state: &'a state::MusicBrainzMatchingState,
+) -> Container<'a, Message> {
+    let state::MusicBrainzMatchingState {
+        search_query,
+        candidates,
+    } = state;
+    let mut candidate_column = Column::new().spacing(15);
+
+    for candidate in candidates.iter().take(SHOWN_CANDIDATE_COUNT) {
+        let artist_id = candidate.artist_info.artist_id;
+
+        let query = match search_query.is_empty() {
+            true => candidate.artist_info.artist_name.clone(),
+            false => search_query.clone(),
+        };
+
+        let mut header = line_row()
+            .spacing(10)
+            .push(
+                dark_button(h2(candidate.artist_info.artist_name.clone()))
+                    .on_press(message::ArtistViewType::ArtistInfo.into_message(artist_id)),
+            )
+            .push(
+                dark_button(bright_paragraph(format!("Search Again: {}", query))).on_press(
+                    Message::Action(message::Action::MusicBrainz(
+                        message::MusicBrainzAction::SearchArtist(artist_id, query),
+                    )),
+                ),
+            );
+
+        header = match (&candidate.matched, candidate.distance) {
+            (Some(matched), Some(distance)) => header
+                .push(h3(format!(
+                    "matched \"{}\" ({} apart)",
+                    matched.name, distance
+                )))
+                .push(
+                    dark_button(bright_paragraph("Approve")).on_press(Message::Action(
+                        message::Action::MusicBrainz(message::MusicBrainzAction::ApproveArtist(
+                            artist_id,
+                            matched.clone(),
+                        )),
+                    )),
+                )
+                .push(
+                    dark_button(bright_paragraph("Reject")).on_press(Message::Action(
+                        message::Action::MusicBrainz(
+                            message::MusicBrainzAction::RejectArtistMatch(artist_id),
+                        ),
+                    )),
+                ),
+            _ => header.push(h3("unmatched")),
+        };
+
+        let mut result_column = Column::new().spacing(2);
+        if candidate.results.is_empty() {
+            result_column = result_column.push(paragraph("no search results"));
+        }
+        for result in candidate.results.iter() {
+            result_column = result_column.push(
+                line_row()
+                    .spacing(6)
+                    .push(Space::with_width(Length::Fixed(20.0)))
+                    .push(paragraph(format!("{}", result.score)))
+                    .push(bright_paragraph(result.name.clone()))
+                    .push(paragraph(format!(
+                        "({}, {} apart)",
+                        result.sort_name,
+                        musiqcore::model::functions::levenshtein(
+                            &result.name.to_lowercase(),
+                            &candidate.artist_info.artist_name.to_lowercase(),
+                        )
+                    )))
+                    .push(dark_button(paragraph("Approve")).on_press(Message::Action(
+                        message::Action::MusicBrainz(message::MusicBrainzAction::ApproveArtist(
+                            artist_id,
+                            result.clone(),
+                        )),
+                    ))),
+            );
+        }
+
+        candidate_column =
+            candidate_column.push(Column::new().spacing(4).push(header).push(result_column));
+    }
+
+    let body = Column::new()
+        .spacing(10)
+        .push(h1("MusicBrainz Matching"))
+        .push(h3(format!(
+            "{} artists without an approved match, showing the closest {}",
+            candidates.len(),
+            SHOWN_CANDIDATE_COUNT.min(candidates.len()),
+        )))
+        .push(
+            TextInput::new(
+                "Search query (blank searches by artist name)...",
+                search_query,
+            )
+            .on_input(|s| Message::Action(message::Action::UpdateText(s))),
+        )
+        .push(Scrollable::new(candidate_column).height(Length::Fill));
+
+    Container::new(body)
+}
@@ -85,10 +85,23 @@ pub fn music_home<'a>(app_images: &embedded::AppImages) -> Container<'a, message
     )))
     .on_press(message::PlaylistNavMessage::PlaylistList("".to_string()).into_message());
 
+    let stats_link = dark_button(h2("Listening Stats")).on_press(
+        message::MusicNavMessage::Stats(model::stats::StatsRange::this_year()).into_message(),
+    );
+
+    let musicbrainz_link = dark_button(h2("MusicBrainz Matching"))
+        .on_press(message::MusicNavMessage::MusicBrainzMatching.into_message());
+
     let body_column = Column::new()
         .push(h1("Music"))
         .push(line_row().push(album_link).push(artist_link))
-        .push(line_row().push(track_link).push(playlist_link));
+        .push(line_row().push(track_link).push(playlist_link))
+        .push(
+            line_row()
+                .spacing(10)
+                .push(stats_link)
+                .push(musicbrainz_link),
+        );
 
     let body = Container::new(Scrollable::new(body_column).height(Length::Fill));
 
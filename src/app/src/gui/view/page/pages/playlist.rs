@@ -17,6 +17,7 @@ use super::super::super::style;
 pub fn playlist_view<'a>(
     library: &'a model::LibraryState,
     action_state: &'a ActionState,
+    config: &'a musiqcore::model::app::AppConfigState,
     player_info: &'a PlayerInfo,
     state: &'a state::PlaylistViewState,
 ) -> Container<'a, Message> {
@@ -67,7 +68,10 @@ pub fn playlist_view<'a>(
                                             Message::PlaybackRequest(
                                                 shared::PlaybackRequest::PlaySongs(
                                                     if should_shuffle {
-                                                        shuffle::shuffle(augmented_tracks.clone())
+                                                        shuffle::shuffle_tracks(
+                                                            &config.shuffle_mode,
+                                                            augmented_tracks.clone(),
+                                                        )
                                                     } else {
                                                         augmented_tracks.clone()
                                                     },
@@ -79,7 +83,8 @@ pub fn playlist_view<'a>(
                                                 .on_press(Message::PlaybackRequest(
                                                     shared::PlaybackRequest::InsertSongs(
                                                         if should_shuffle {
-                                                            shuffle::shuffle(
+                                                            shuffle::shuffle_tracks(
+                                                                &config.shuffle_mode,
                                                                 augmented_tracks.clone(),
                                                             )
                                                         } else {
@@ -94,7 +99,8 @@ pub fn playlist_view<'a>(
                                                 .on_press(Message::PlaybackRequest(
                                                     shared::PlaybackRequest::AppendSongs(
                                                         if should_shuffle {
-                                                            shuffle::shuffle(
+                                                            shuffle::shuffle_tracks(
+                                                                &config.shuffle_mode,
                                                                 augmented_tracks.clone(),
                                                             )
                                                         } else {
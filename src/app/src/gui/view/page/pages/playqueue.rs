@@ -0,0 +1,71 @@
+use iced::widget::{Column, Container, Row, TextInput};
+
+use crate::gui::message::{self, Message};
+use crate::shared;
+use crate::state;
+
+use super::super::super::common;
+use super::super::super::elements::*;
+
+pub fn play_queue_page<'a>(
+    config: &'a musiqcore::model::app::AppConfigState,
+    player_info: &'a state::PlayerInfo,
+    state: &'a state::PlayQueueState,
+) -> Container<'a, Message> {
+    let state::PlayQueueState { new_playlist_name } = state;
+    let play_queue = &player_info.play_queue_info.play_queue;
+
+    let mut track_count = 0;
+    let mut total_seconds = 0;
+    for play_queue_entry in play_queue.iter() {
+        match play_queue_entry {
+            shared::PlayQueueEntry::Track(play_queue_track) => {
+                track_count += 1;
+                total_seconds += play_queue_track.track.metadata.duration.as_secs();
+            }
+            shared::PlayQueueEntry::Action(shared::PlayQueueAction::Pause) => (),
+        }
+    }
+
+    let page =
+        Column::new()
+            .spacing(10)
+            .push(h1("The Play Queue"))
+            .push(bright_paragraph(format!(
+                "{} tracks ({}) up next",
+                track_count,
+                common::format_duration(total_seconds)
+            )))
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(dark_button(bright_paragraph("Shuffle")).on_press(
+                        Message::PlaybackRequest(shared::PlaybackRequest::ShufflePlayQueue(
+                            config.shuffle_mode,
+                        )),
+                    ))
+                    .push(dark_button(bright_paragraph("Remove Duplicates")).on_press(
+                        Message::PlaybackRequest(shared::PlaybackRequest::DedupePlayQueue),
+                    ))
+                    .push(dark_button(bright_paragraph("Clear")).on_press(
+                        Message::PlaybackRequest(shared::PlaybackRequest::ClearPlayQueue),
+                    )),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        TextInput::new("Save Play Queue as Playlist...", new_playlist_name)
+                            .on_input(|s| Message::Action(message::Action::UpdateText(s)))
+                            .on_submit(Message::Action(message::Action::SavePlayQueueAsPlaylist(
+                                new_playlist_name.clone(),
+                            ))),
+                    )
+                    .push(
+                        dark_button(bright_paragraph("Save")).on_press(Message::Action(
+                            message::Action::SavePlayQueueAsPlaylist(new_playlist_name.clone()),
+                        )),
+                    ),
+            );
+
+    Container::new(page)
+}
@@ -13,6 +13,8 @@ use super::super::super::elements::*;
 pub fn search_page<'a>(
     library: &'a model::LibraryState,
     movie_library: &'a model::VideoLibraryState,
+    show_library: &'a musiqcore::model::shows::ShowLibraryState,
+    show_art: &model::ShowArt,
     app_images: &embedded::AppImages,
     state: &'a state::SearchPageState,
 ) -> Container<'a, Message> {
@@ -24,6 +26,9 @@ pub fn search_page<'a>(
             let domain = match domain_results {
                 state::SearchDomainResults::Music(ref _res) => model::SearchDomain::Music,
                 state::SearchDomainResults::Movies(ref _res) => model::SearchDomain::Movies,
+                state::SearchDomainResults::Shows(ref _res) => model::SearchDomain::Shows,
+                state::SearchDomainResults::Games(ref _res) => model::SearchDomain::Games,
+                state::SearchDomainResults::Everything(ref _res) => model::SearchDomain::Everything,
             };
 
             let domain_specific = match domain_results {
@@ -265,6 +270,97 @@ pub fn search_page<'a>(
                         .height(Length::Fill);
                     domain_specific
                 }
+                state::SearchDomainResults::Shows(results) => {
+                    let (show_results, episode_results) =
+                        match results {
+                            Some(results) => (
+                                Scrollable::new(results.shows.iter().fold(
+                                    Column::new(),
+                                    |column, show_key| {
+                                        column.push(show_result(show_key, show_art, app_images))
+                                    },
+                                )),
+                                Scrollable::new(results.episodes.iter().fold(
+                                    Column::new(),
+                                    |column, episode| {
+                                        column.push(episode_result(episode, show_art))
+                                    },
+                                )),
+                            ),
+                            None => (
+                                Scrollable::new(Space::with_width(Length::Fill)),
+                                Scrollable::new(Space::with_width(Length::Fill)),
+                            ),
+                        };
+                    let no_shows_note = match show_library.get_shows_if_exists() {
+                        Some(_) => Column::new(),
+                        None => Column::new().push(paragraph("no show path")),
+                    };
+                    Row::new()
+                        .spacing(5)
+                        .push(
+                            Column::new()
+                                .push(h2("Shows"))
+                                .push(no_shows_note)
+                                .push(show_results)
+                                .width(Length::FillPortion(1)),
+                        )
+                        .push(
+                            Column::new()
+                                .push(h2("Episodes"))
+                                .push(episode_results)
+                                .width(Length::FillPortion(1)),
+                        )
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                }
+                state::SearchDomainResults::Games(results) => {
+                    let game_results = match results {
+                        Some(results) => Scrollable::new(
+                            results
+                                .iter()
+                                .fold(Column::new(), |column, game| column.push(game_result(game))),
+                        ),
+                        None => Scrollable::new(Space::with_width(Length::Fill)),
+                    };
+                    Row::new()
+                        .spacing(5)
+                        .push(
+                            Column::new()
+                                .push(h2("Games"))
+                                .push(game_results)
+                                .width(Length::FillPortion(1)),
+                        )
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                }
+                state::SearchDomainResults::Everything(results) => {
+                    let combined_results = match results {
+                        Some(results) => Scrollable::new(results.results.iter().fold(
+                            Column::new(),
+                            |column, result| {
+                                column.push(combined_result(
+                                    result,
+                                    library,
+                                    movie_library,
+                                    show_art,
+                                    app_images,
+                                ))
+                            },
+                        )),
+                        None => Scrollable::new(Space::with_width(Length::Fill)),
+                    };
+                    Row::new()
+                        .spacing(5)
+                        .push(
+                            Column::new()
+                                .push(h2("Top Results"))
+                                .push(combined_results)
+                                .width(Length::FillPortion(1)),
+                        )
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                }
             };
 
             let body = Container::new(
@@ -284,6 +380,24 @@ pub fn search_page<'a>(
                                 query.clone(),
                                 model::SearchDomain::Movies,
                                 domain.clone(),
+                            ))
+                            .push(domain_button(
+                                "Shows",
+                                query.clone(),
+                                model::SearchDomain::Shows,
+                                domain.clone(),
+                            ))
+                            .push(domain_button(
+                                "Games",
+                                query.clone(),
+                                model::SearchDomain::Games,
+                                domain.clone(),
+                            ))
+                            .push(domain_button(
+                                "Everything",
+                                query.clone(),
+                                model::SearchDomain::Everything,
+                                domain.clone(),
                             )),
                     )
                     .push(
@@ -322,3 +436,182 @@ fn domain_button<'a>(
         query, domain, true,
     )))
 }
+
+fn show_result<'a>(
+    show_key: &musiqlibrary::shows::ShowKey,
+    show_art: &model::ShowArt,
+    app_images: &embedded::AppImages,
+) -> Button<'a, Message> {
+    dark_button(
+        line_row()
+            .spacing(5)
+            .push(
+                match show_art.get_show_poster(model::MovieSize::Micro, show_key) {
+                    Some(poster_path) => {
+                        movie_image_from_path(poster_path, model::MovieSize::Micro, true)
+                    }
+                    None => movie_image(
+                        app_images.get_dvd_image().clone(),
+                        model::MovieSize::Micro,
+                        true,
+                    ),
+                },
+            )
+            .push(bright_paragraph(show_key.raw_string().clone()).width(Length::Fill)),
+    )
+    .on_press(message::ShowNavMessage::ShowSeries(show_key.clone()).into_message())
+}
+
+fn episode_result<'a>(
+    episode: &musiqlibrary::shows::ShowMetadata,
+    show_art: &model::ShowArt,
+) -> Button<'a, Message> {
+    let episode_key = episode.get_key();
+    let mut row = line_row().spacing(5);
+    if let Some(thumbnail_path) =
+        show_art.get_episode_thumbnail(model::MovieSize::Micro, &episode_key)
+    {
+        row = row.push(movie_image_from_path(
+            thumbnail_path,
+            model::MovieSize::Micro,
+            false,
+        ));
+    }
+    dark_button(
+        row.push(
+            bright_paragraph(format!(
+                "{} - S{}E{} {}",
+                episode.show,
+                episode.season_number,
+                episode.episode_sort,
+                episode.episode_id.as_ref().unwrap_or(&episode.title)
+            ))
+            .width(Length::Fill),
+        ),
+    )
+    .on_press(
+        message::ShowNavMessage::ShowSeason(episode_key.show, episode_key.season_number)
+            .into_message(),
+    )
+}
+
+fn game_result<'a>(game: &musiqcore::model::gl::GameSearchResult) -> Row<'a, Message> {
+    line_row()
+        .spacing(5)
+        .push(dark_button(h3(">")).on_press(Message::ExternalSpawn(
+            message::ExternalSpawn::LaunchEmulator(game.console.clone(), game.path.clone()),
+        )))
+        .push(
+            dark_button(
+                line_row()
+                    .spacing(5)
+                    .push(bright_paragraph(game.name.clone()))
+                    .push(paragraph(game.console.full_name()))
+                    .width(Length::Fill),
+            )
+            .on_press(message::GameNavMessage::from_console(&game.console).into_message()),
+        )
+}
+
+/// A result of any kind, labelled with its kind and linking to its own page
+fn combined_result<'a>(
+    result: &model::CombinedSearchResult,
+    library: &model::LibraryState,
+    movie_library: &model::VideoLibraryState,
+    show_art: &model::ShowArt,
+    app_images: &embedded::AppImages,
+) -> Row<'a, Message> {
+    let kind_label = dark_paragraph(result.kind_name()).width(Length::Fixed(70.0));
+
+    let result_element: iced::Element<'a, Message> = match result {
+        model::CombinedSearchResult::Artist(artist) => dark_button(
+            line_row()
+                .spacing(5)
+                .push(album_image(
+                    library
+                        .get_artists_first_album_cover(model::AlbumSize::Micro, artist.artist_id),
+                    model::AlbumSize::Micro,
+                ))
+                .push(bright_paragraph(artist.artist_name.clone()).width(Length::Fill)),
+        )
+        .on_press(message::ArtistViewType::ArtistAlbumsView.into_message(artist.artist_id))
+        .into(),
+        model::CombinedSearchResult::Album(album) => dark_button(
+            line_row()
+                .spacing(5)
+                .push(album_image(
+                    library.get_album_cover(
+                        model::AlbumSize::Micro,
+                        album.artist.artist_id,
+                        album.album.album_id,
+                    ),
+                    model::AlbumSize::Micro,
+                ))
+                .push(
+                    bright_paragraph(format!(
+                        "{} ({})",
+                        album.album.album_name, album.artist.artist_name
+                    ))
+                    .width(Length::Fill),
+                ),
+        )
+        .on_press(
+            message::ArtistNavMessage::AlbumView(
+                album.artist.artist_id,
+                album.album.album_id,
+                message::ArtistAlbumView::ArtistAlbumTrackView(
+                    model::AlbumSize::Regular,
+                    None,
+                    None,
+                ),
+            )
+            .into_message(),
+        )
+        .into(),
+        model::CombinedSearchResult::Track(track) => dark_button(
+            line_row()
+                .spacing(5)
+                .push(album_image(
+                    library.get_album_cover(
+                        model::AlbumSize::Micro,
+                        track.metadata.album_artist_id,
+                        track.metadata.album_id,
+                    ),
+                    model::AlbumSize::Micro,
+                ))
+                .push(
+                    bright_paragraph(format!(
+                        "{} ({})",
+                        track.metadata.title, track.metadata.track_artist
+                    ))
+                    .width(Length::Fill),
+                ),
+        )
+        .on_press(components::track_link(&track.metadata))
+        .into(),
+        model::CombinedSearchResult::Movie(movie) => dark_button(
+            line_row()
+                .spacing(5)
+                .push(movie_image(
+                    movie_library
+                        .get_movie_cover(
+                            model::MovieSize::Micro,
+                            model::MovieRelPath::from_metadata(movie),
+                        )
+                        .unwrap_or(app_images.get_dvd_image().clone()),
+                    model::MovieSize::Micro,
+                    true,
+                ))
+                .push(bright_paragraph(movie.title.clone()).width(Length::Fill)),
+        )
+        .on_press(message::MovieNavMessage::MovieView(movie.clone(), None, None).into_message())
+        .into(),
+        model::CombinedSearchResult::Show(show_key) => {
+            show_result(show_key, show_art, app_images).into()
+        }
+        model::CombinedSearchResult::Episode(episode) => episode_result(episode, show_art).into(),
+        model::CombinedSearchResult::Game(game) => game_result(game).into(),
+    };
+
+    line_row().spacing(5).push(kind_label).push(result_element)
+}
@@ -12,6 +12,7 @@ use super::super::super::elements::*;
 
 pub fn show_continue_watching<'a>(
     show_library_state: &'a musiqcore::model::shows::ShowLibraryState,
+    show_art: &model::ShowArt,
     app_images: &embedded::AppImages,
 ) -> Container<'a, Message> {
     let mut body_column = Column::new().push(h1("Shows"));
@@ -19,25 +20,43 @@ pub fn show_continue_watching<'a>(
     match show_library_state.get_shows_if_exists() {
         Some(show_library) => {
             for (show_key, show) in show_library.get_structured_shows().get_shows().iter() {
+                if show_library.get_show_progress(show_key).is_complete() {
+                    continue;
+                }
+
                 let episode_key = show_library.get_next_show_to_view(show_key);
 
                 let episode = show_library
                     .get_structured_shows()
-                    .get_show(show_key)
-                    .unwrap()
-                    .get_season(&episode_key.season_number)
-                    .unwrap()
-                    .get_episode(&episode_key.episode_sort)
+                    .get_episode(&episode_key)
                     .unwrap();
 
+                // the next episode's thumbnail, then its season's (or show's) poster
+                let episode_image =
+                    match show_art.get_episode_thumbnail(model::MovieSize::Small, &episode_key) {
+                        Some(thumbnail_path) => {
+                            movie_image_from_path(thumbnail_path, model::MovieSize::Small, false)
+                        }
+                        None => match show_art.get_season_poster(
+                            model::MovieSize::Small,
+                            show_key,
+                            episode_key.season_number,
+                        ) {
+                            Some(poster_path) => {
+                                movie_image_from_path(poster_path, model::MovieSize::Small, true)
+                            }
+                            None => movie_image(
+                                app_images.get_dvd_image().clone(),
+                                model::MovieSize::Small,
+                                true,
+                            ),
+                        },
+                    };
+
                 body_column = body_column.push(
                     Column::new().push(h3(show.get_name())).push(
                         dark_button(Container::new(bottom_label(
-                            album_image(
-                                app_images.get_dvd_image().clone(),
-                                model::AlbumSize::Small,
-                            )
-                            .into(),
+                            episode_image.into(),
                             bright_paragraph(common::abr_str(
                                 episode.local_display_name(),
                                 consts::ICON_STR_LENGTH,
@@ -5,12 +5,16 @@ use crate::model;
 
 use crate::datastore::staticassets::embedded;
 use crate::gui::message::{self, Message};
+use crate::gui::view::components;
 
 use super::super::super::common;
 use super::super::super::consts;
 use super::super::super::elements::*;
 
-pub fn show_home<'a>(app_images: &embedded::AppImages) -> Container<'a, Message> {
+pub fn show_home<'a>(
+    show_library: &'a musiqcore::model::shows::ShowLibraryState,
+    app_images: &embedded::AppImages,
+) -> Container<'a, Message> {
     let show_list_link = dark_button(Container::new(bottom_label(
         album_image(
             app_images.get_tracks_image().clone(),
@@ -37,9 +41,15 @@ pub fn show_home<'a>(app_images: &embedded::AppImages) -> Container<'a, Message>
     )))
     .on_press(message::ShowNavMessage::ContinueWatching.into_message());
 
+    let scan_errors = match show_library.get_shows_if_exists() {
+        Some(shows) => components::scan_error_list(shows.get_scan_errors()),
+        None => Column::new(),
+    };
+
     let body_column = Column::new()
         .push(h1("List"))
-        .push(line_row().push(show_list_link).push(recently_viewed_link));
+        .push(line_row().push(show_list_link).push(recently_viewed_link))
+        .push(scan_errors);
 
     let body = Container::new(
         Column::new()
@@ -12,6 +12,7 @@ use super::super::super::elements::*;
 
 pub fn show_list<'a>(
     show_library_state: &'a musiqcore::model::shows::ShowLibraryState,
+    show_art: &model::ShowArt,
     app_images: &embedded::AppImages,
 ) -> Container<'a, Message> {
     let mut body_column = Column::new().push(h1("All Shows"));
@@ -19,10 +20,20 @@ pub fn show_list<'a>(
     match show_library_state.get_shows_if_exists() {
         Some(show_library) => {
             for (show_key, show) in show_library.get_structured_shows().get_shows().iter() {
+                let show_image = match show_art.get_show_poster(model::MovieSize::Small, show_key) {
+                    Some(poster_path) => {
+                        movie_image_from_path(poster_path, model::MovieSize::Small, true)
+                    }
+                    None => movie_image(
+                        app_images.get_dvd_image().clone(),
+                        model::MovieSize::Small,
+                        true,
+                    ),
+                };
+
                 body_column = body_column.push(
                     dark_button(Container::new(bottom_label(
-                        album_image(app_images.get_dvd_image().clone(), model::AlbumSize::Small)
-                            .into(),
+                        show_image.into(),
                         bright_paragraph(common::abr_str(show.get_name(), consts::ICON_STR_LENGTH)),
                     )))
                     .on_press(message::ShowNavMessage::ShowSeries(show_key.clone()).into_message()),
@@ -1,12 +1,18 @@
 use iced::widget::{Column, Container, Scrollable, Space};
 use iced::Length;
 
-use crate::gui::message::{ExternalRequest, Message};
+use crate::gui::message::{self, ExternalRequest, Message};
+use crate::gui::view::components;
+use crate::model;
 
 use super::super::super::elements::*;
 
+/// Wide enough for a 16:9 episode thumbnail at the micro height
+const EPISODE_THUMBNAIL_WIDTH: f32 = 54.0;
+
 pub fn show_season_view<'a>(
     show_library_state: &'a musiqcore::model::shows::ShowLibraryState,
+    show_art: &model::ShowArt,
     series_key: &musiqlibrary::shows::ShowKey,
     season_id: &u32,
 ) -> Container<'a, Message> {
@@ -25,7 +31,46 @@ pub fn show_season_view<'a>(
 
             let season = show.get_season(season_id).unwrap();
 
-            body_column = body_column.push(h2(season.pretty_display()));
+            let mut season_header = line_row().spacing(10);
+            if let Some(poster_path) =
+                show_art.get_season_poster(model::MovieSize::Regular, series_key, *season_id)
+            {
+                season_header = season_header.push(movie_image_from_path(
+                    poster_path,
+                    model::MovieSize::Regular,
+                    false,
+                ));
+            }
+            body_column = body_column.push(season_header.push(h2(season.pretty_display())));
+
+            if let Some(description) = season.get_description() {
+                body_column = body_column.push(paragraph(description.clone()));
+            }
+
+            body_column =
+                body_column.push(
+                    line_row()
+                        .spacing(10)
+                        .push(components::watch_progress(
+                            &show_library.get_season_progress(series_key, *season_id),
+                        ))
+                        .push(dark_button(paragraph("Mark Season Watched")).on_press(
+                            Message::Action(message::Action::ShowWatch(
+                                message::ShowWatchAction::MarkSeasonWatched(
+                                    series_key.clone(),
+                                    *season_id,
+                                ),
+                            )),
+                        ))
+                        .push(dark_button(paragraph("Mark Season Unwatched")).on_press(
+                            Message::Action(message::Action::ShowWatch(
+                                message::ShowWatchAction::MarkSeasonUnwatched(
+                                    series_key.clone(),
+                                    *season_id,
+                                ),
+                            )),
+                        )),
+                );
 
             let mut episodes = Column::new().padding(3).spacing(1);
 
@@ -34,10 +79,54 @@ pub fn show_season_view<'a>(
                     Message::ExternalRequest(ExternalRequest::PlayShow(episode.clone())),
                 ));
 
-                let seen_element = match tracker.get_view_count_for_episode(episode.get_key()) {
+                let views = tracker.get_views_for_episode(&episode.get_key());
+                let seen_element = match views.len() {
                     0 => h3("[  ]"),
                     _ => h3("[#]"),
                 };
+                let remove_view_element = match views.last() {
+                    Some(last_view) => Container::new(
+                        dark_button(dark_paragraph(format!(
+                            "remove view from {}",
+                            last_view.format("%Y/%m/%d")
+                        )))
+                        .on_press(Message::Action(
+                            message::Action::ShowWatch(
+                                message::ShowWatchAction::RemoveEpisodeView(
+                                    episode.get_key(),
+                                    *last_view,
+                                ),
+                            ),
+                        )),
+                    ),
+                    None => Container::new(Space::with_width(0)),
+                };
+
+                let track_info = match (
+                    episode.tracks.audio_summary(),
+                    episode.tracks.subtitle_summary(),
+                ) {
+                    (audio, subtitles) if subtitles.is_empty() => audio,
+                    (audio, subtitles) if audio.is_empty() => format!("subs: {}", subtitles),
+                    (audio, subtitles) => format!("{} | subs: {}", audio, subtitles),
+                };
+
+                let episode_number = match episode.episode_sort_tiebreak {
+                    Some(part) => format!("{}.{}", episode.episode_sort, part),
+                    None => format!("{}", episode.episode_sort),
+                };
+
+                let thumbnail_element = match show_art
+                    .get_episode_thumbnail(model::MovieSize::Micro, &episode.get_key())
+                {
+                    Some(thumbnail_path) => Container::new(
+                        movie_image_from_path(thumbnail_path, model::MovieSize::Micro, false)
+                            .width(Length::Fixed(EPISODE_THUMBNAIL_WIDTH)),
+                    ),
+                    None => {
+                        Container::new(Space::with_width(Length::Fixed(EPISODE_THUMBNAIL_WIDTH)))
+                    }
+                };
 
                 episodes = episodes.push(
                     line_row()
@@ -46,8 +135,30 @@ pub fn show_season_view<'a>(
                         .push(Space::with_width(5))
                         .push(play_button)
                         .push(Space::with_width(10))
-                        .push(h3(format!("{}", episode.episode_sort)).width(Length::Fixed(40.0)))
-                        .push(h3(episode.local_display_name()).width(Length::Fill)),
+                        .push(thumbnail_element)
+                        .push(Space::with_width(10))
+                        .push(h3(episode_number).width(Length::Fixed(40.0)))
+                        .push(h3(episode.local_display_name()).width(Length::Fill))
+                        .push(paragraph(track_info))
+                        .push(remove_view_element),
+                );
+
+                let mut episode_details = Column::new();
+                if let Some(air_date) = episode.air_date {
+                    episode_details = episode_details.push(dark_paragraph(format!(
+                        "Aired {}",
+                        air_date.format("%Y/%m/%d")
+                    )));
+                }
+                if let Some(ref plot) = episode.plot {
+                    episode_details = episode_details.push(paragraph(plot.clone()));
+                }
+                episodes = episodes.push(
+                    line_row()
+                        .push(Space::with_width(Length::Fixed(
+                            120.0 + EPISODE_THUMBNAIL_WIDTH,
+                        )))
+                        .push(episode_details.width(Length::Fill)),
                 );
             }
 
@@ -5,6 +5,7 @@ use crate::model;
 
 use crate::datastore::staticassets::embedded;
 use crate::gui::message::{self, Message};
+use crate::gui::view::components;
 
 use super::super::super::common;
 use super::super::super::consts;
@@ -12,6 +13,7 @@ use super::super::super::elements::*;
 
 pub fn show_series_view<'a>(
     show_library_state: &'a musiqcore::model::shows::ShowLibraryState,
+    show_art: &model::ShowArt,
     app_images: &embedded::AppImages,
     series_key: &musiqlibrary::shows::ShowKey,
 ) -> Container<'a, Message> {
@@ -24,25 +26,83 @@ pub fn show_series_view<'a>(
                 .get_show(series_key)
                 .unwrap();
 
-            body_column = body_column.push(h1(show.get_name()));
+            let poster = match show_art.get_show_poster(model::MovieSize::Regular, series_key) {
+                Some(poster_path) => {
+                    movie_image_from_path(poster_path, model::MovieSize::Regular, false)
+                }
+                None => movie_image(
+                    app_images.get_dvd_image().clone(),
+                    model::MovieSize::Regular,
+                    true,
+                ),
+            };
+
+            body_column = body_column.push(
+                line_row()
+                    .spacing(10)
+                    .push(poster)
+                    .push(h1(show.get_name())),
+            );
+
+            if let Some(description) = show.get_description() {
+                body_column = body_column.push(paragraph(description.clone()));
+            }
+
+            let mark_watched = Message::Action(message::Action::ShowWatch(
+                message::ShowWatchAction::MarkShowWatched(series_key.clone()),
+            ));
+            let mark_unwatched = Message::Action(message::Action::ShowWatch(
+                message::ShowWatchAction::MarkShowUnwatched(series_key.clone()),
+            ));
+            body_column = body_column.push(
+                line_row()
+                    .spacing(10)
+                    .push(components::watch_progress(
+                        &show_library.get_show_progress(series_key),
+                    ))
+                    .push(dark_button(paragraph("Mark Show Watched")).on_press(mark_watched))
+                    .push(dark_button(paragraph("Mark Show Unwatched")).on_press(mark_unwatched)),
+            );
 
             for season in show.get_seasons().values() {
+                let season_image = match show_art.get_season_poster(
+                    model::MovieSize::Small,
+                    series_key,
+                    season.get_season_number(),
+                ) {
+                    Some(poster_path) => {
+                        movie_image_from_path(poster_path, model::MovieSize::Small, true)
+                    }
+                    None => movie_image(
+                        app_images.get_dvd_image().clone(),
+                        model::MovieSize::Small,
+                        true,
+                    ),
+                };
+
                 body_column = body_column.push(
-                    dark_button(Container::new(bottom_label(
-                        album_image(app_images.get_dvd_image().clone(), model::AlbumSize::Small)
-                            .into(),
-                        bright_paragraph(common::abr_str(
-                            season.pretty_display(),
-                            consts::ICON_STR_LENGTH,
-                        )),
-                    )))
-                    .on_press(
-                        message::ShowNavMessage::ShowSeason(
-                            series_key.clone(),
-                            season.get_season_number(),
+                    line_row()
+                        .spacing(10)
+                        .push(
+                            dark_button(Container::new(bottom_label(
+                                season_image.into(),
+                                bright_paragraph(common::abr_str(
+                                    season.pretty_display(),
+                                    consts::ICON_STR_LENGTH,
+                                )),
+                            )))
+                            .on_press(
+                                message::ShowNavMessage::ShowSeason(
+                                    series_key.clone(),
+                                    season.get_season_number(),
+                                )
+                                .into_message(),
+                            ),
                         )
-                        .into_message(),
-                    ),
+                        .push(components::watch_progress(
+                            &show_library
+                                .get_season_progress(series_key, season.get_season_number()),
+                        )),
                 );
             }
         }
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use iced::widget::{Column, Container, ProgressBar, Row, Scrollable, Space};
+use iced::Length;
+
+use crate::model;
+
+use crate::gui::message::{self, Message};
+use crate::state;
+
+use crate::gui::view::components;
+
+use super::super::super::common;
+use super::super::super::elements::*;
+use super::super::super::style;
+
+/// How many artists/albums/tracks/genres to list in each top list
+const TOP_COUNT: usize = 10;
+
+/// How many of the most recent days/weeks to chart listening time for
+const RECENT_DAY_COUNT: usize = 14;
+const RECENT_WEEK_COUNT: usize = 12;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+pub fn stats_page<'a>(state: &'a state::StatsState) -> Container<'a, Message> {
+    let state::StatsState { stats } = state;
+    let summary = Column::new()
+        .spacing(5)
+        .push(h2(format!(
+            "{} plays - {} listened",
+            stats.play_count,
+            common::format_duration(stats.listened_seconds),
+        )))
+        .push(bright_paragraph(match stats.longest_streak {
+            Some((start, length)) => {
+                format!("Longest streak: {} days, starting {}", length, start)
+            }
+            None => "Longest streak: none".to_string(),
+        }))
+        .push(bright_paragraph(format!(
+            "Current streak: {} days",
+            stats.current_streak
+        )));
+
+    let top_lists = Row::new()
+        .spacing(20)
+        .push(top_list(
+            "Top Artists",
+            stats
+                .top_artists
+                .iter()
+                .take(TOP_COUNT)
+                .map(|artist| {
+                    (
+                        artist.artist_name.clone(),
+                        artist.play_count,
+                        message::ArtistViewType::ArtistAlbumsView.into_message(artist.artist_id),
+                    )
+                })
+                .collect(),
+        ))
+        .push(top_list(
+            "Top Albums",
+            stats
+                .top_albums
+                .iter()
+                .take(TOP_COUNT)
+                .map(|album| {
+                    (
+                        format!("{} ({})", album.album_name, album.artist_name),
+                        album.play_count,
+                        message::ArtistNavMessage::AlbumView(
+                            album.artist_id,
+                            album.album_id,
+                            message::ArtistAlbumView::ArtistAlbumTrackView(
+                                model::AlbumSize::Regular,
+                                None,
+                                None,
+                            ),
+                        )
+                        .into_message(),
+                    )
+                })
+                .collect(),
+        ))
+        .push(top_list(
+            "Top Tracks",
+            stats
+                .top_tracks
+                .iter()
+                .take(TOP_COUNT)
+                .map(|track| {
+                    (
+                        format!("{} ({})", track.track.title, track.track.album_artist),
+                        track.play_count,
+                        components::track_link(&track.track),
+                    )
+                })
+                .collect(),
+        ))
+        .push({
+            let mut column = Column::new().spacing(2).push(h3("Top Genres"));
+            for genre in stats.top_genres.iter().take(TOP_COUNT) {
+                column = column.push(bright_paragraph(format!(
+                    "{}\t{}",
+                    genre.play_count, genre.genre
+                )));
+            }
+            column
+        });
+
+    let listening_time = Row::new()
+        .spacing(20)
+        .push(listened_chart(
+            "Per Day",
+            recent(&stats.listened_per_day, RECENT_DAY_COUNT),
+            "%a %m-%d",
+        ))
+        .push(listened_chart(
+            "Per Week",
+            recent(&stats.listened_per_week, RECENT_WEEK_COUNT),
+            "%Y-%m-%d",
+        ))
+        .push(listened_chart(
+            "Per Month",
+            stats.listened_per_month.iter().collect(),
+            "%Y-%m",
+        ));
+
+    let discoveries = {
+        let mut column = Column::new()
+            .spacing(2)
+            .push(h2(format!("New Discoveries ({})", stats.discoveries.len())));
+        for discovery in stats.discoveries.iter().rev().take(TOP_COUNT) {
+            column = column.push(
+                dark_button(bright_paragraph(format!(
+                    "{}\t{} ({})",
+                    discovery.first_played.format("%Y-%m-%d"),
+                    discovery.track.title,
+                    discovery.track.album_artist,
+                )))
+                .on_press(components::track_link(&discovery.track)),
+            );
+        }
+        column
+    };
+
+    let body = Column::new()
+        .spacing(15)
+        .padding(10)
+        .push(summary)
+        .push(top_lists)
+        .push(h2("Listening Time"))
+        .push(listening_time)
+        .push(discoveries)
+        .push(h2("Time of Day"))
+        .push(time_of_day_heatmap(&stats.time_of_day));
+
+    Container::new(
+        Column::new()
+            .push(h1(format!(
+                "Listening Stats: {}",
+                stats.range.display_text()
+            )))
+            .push(range_selector(&stats.range))
+            .push(Scrollable::new(body).height(Length::Fill)),
+    )
+}
+
+fn range_selector<'a>(range: &model::stats::StatsRange) -> Row<'a, Message> {
+    let range_button = |text: String, range: model::stats::StatsRange| {
+        dark_button(bright_paragraph(text))
+            .on_press(message::MusicNavMessage::Stats(range).into_message())
+    };
+
+    let mut row = line_row()
+        .spacing(5)
+        .push(range_button(
+            "Last 7 Days".to_string(),
+            model::stats::StatsRange::LastDays(7),
+        ))
+        .push(range_button(
+            "Last 30 Days".to_string(),
+            model::stats::StatsRange::LastDays(30),
+        ))
+        .push(range_button(
+            "This Year".to_string(),
+            model::stats::StatsRange::this_year(),
+        ))
+        .push(range_button(
+            "All Time".to_string(),
+            model::stats::StatsRange::AllTime,
+        ));
+
+    if let model::stats::StatsRange::Year(year) = range {
+        row = row
+            .push(range_button(
+                format!("< {}", year - 1),
+                model::stats::StatsRange::Year(year - 1),
+            ))
+            .push(range_button(
+                format!("{} >", year + 1),
+                model::stats::StatsRange::Year(year + 1),
+            ));
+    }
+
+    row
+}
+
+fn top_list<'a>(title: &str, entries: Vec<(String, usize, Message)>) -> Column<'a, Message> {
+    let mut column = Column::new().spacing(2).push(h3(title.to_string()));
+    for (name, play_count, message) in entries.into_iter() {
+        column = column.push(
+            dark_button(bright_paragraph(format!("{}\t{}", play_count, name))).on_press(message),
+        );
+    }
+    column
+}
+
+fn recent(listened: &BTreeMap<NaiveDate, u64>, count: usize) -> Vec<(&NaiveDate, &u64)> {
+    let mut entries: Vec<(&NaiveDate, &u64)> = listened.iter().rev().take(count).collect();
+    entries.reverse();
+    entries
+}
+
+fn listened_chart<'a>(
+    title: &str,
+    entries: Vec<(&NaiveDate, &u64)>,
+    date_format: &str,
+) -> Column<'a, Message> {
+    let most_listened = entries
+        .iter()
+        .map(|(_, seconds)| **seconds)
+        .max()
+        .unwrap_or(0);
+
+    let mut column = Column::new().spacing(2).push(h3(title.to_string()));
+    for (date, seconds) in entries.into_iter() {
+        column = column.push(
+            Row::new()
+                .spacing(5)
+                .align_items(iced::Alignment::Center)
+                .push(paragraph(date.format(date_format).to_string()).width(Length::Fixed(110.0)))
+                .push(
+                    ProgressBar::new(0.0..=(most_listened as f32), *seconds as f32)
+                        .width(Length::Fixed(120.0))
+                        .height(Length::Fixed(10.0)),
+                )
+                .push(bright_paragraph(common::format_duration(*seconds))),
+        );
+    }
+    column
+}
+
+fn time_of_day_heatmap<'a>(time_of_day: &[[usize; 24]; 7]) -> Column<'a, Message> {
+    let most_plays = time_of_day
+        .iter()
+        .flat_map(|hours| hours.iter())
+        .cloned()
+        .max()
+        .unwrap_or(0);
+
+    let mut hour_labels = Row::new().push(Space::with_width(Length::Fixed(40.0)));
+    for hour in 0..24 {
+        hour_labels = hour_labels.push(
+            Container::new(dark_paragraph(match hour % 6 {
+                0 => hour.to_string(),
+                _ => "".to_string(),
+            }))
+            .width(Length::Fixed(20.0)),
+        );
+    }
+
+    let mut column = Column::new().spacing(2).push(hour_labels);
+    for (weekday, hours) in WEEKDAYS.iter().zip(time_of_day.iter()) {
+        let mut row = Row::new()
+            .spacing(2)
+            .push(paragraph(weekday.to_string()).width(Length::Fixed(38.0)));
+        for play_count in hours.iter() {
+            let intensity = match most_plays {
+                0 => 0.0,
+                _ => *play_count as f32 / most_plays as f32,
+            };
+            row = row.push(
+                Container::new(Space::new(Length::Fixed(18.0), Length::Fixed(18.0))).style(
+                    iced::theme::Container::Custom(Box::new(style::ContainerHeatmapCell {
+                        intensity,
+                    })),
+                ),
+            );
+        }
+        column = column.push(row);
+    }
+    column
+}
@@ -30,6 +30,7 @@ pub fn view_app(app: &state::AppState) -> Element<Message> {
     let library = &app.library;
     let movie_library = &app.video_library;
     let show_library = &app.show_library;
+    let show_art = &app.show_art;
     let game_library = &app.game_library;
     let config = &app.config;
     let messages = &app.messages;
@@ -50,9 +51,11 @@ pub fn view_app(app: &state::AppState) -> Element<Message> {
         library,
         movie_library,
         show_library,
+        show_art,
         game_library,
         app_images,
         action_state,
+        config,
         play_queue_info.play_queue_visible && !cross_page_display_info.fullscreen_display,
         player_info,
     );
@@ -329,6 +332,15 @@ pub fn render_play_queue<'a>(
                 iced::widget::Row::new()
                     .align_items(iced::Alignment::Start)
                     .push(h1("Current Playback").width(Length::Fill))
+                    .push(
+                        dark_button(bright_paragraph(format!(
+                            "Repeat: {}",
+                            play_queue_info.repeat_mode.display_text()
+                        )))
+                        .on_press(Message::PlaybackRequest(
+                            shared::PlaybackRequest::CycleRepeatMode,
+                        )),
+                    )
                     .push(
                         dark_button(bright_paragraph("Focus"))
                             .on_press(user_nav_message(NavMessage::PlayQueueFocus)),
@@ -338,6 +350,24 @@ pub fn render_play_queue<'a>(
                             .on_press(Message::Action(message::Action::TogglePlayQueueVisible)),
                     ),
             );
+            if let Some(ref station) = play_queue_info.radio {
+                play_queue_view = play_queue_view.push(
+                    iced::widget::Row::new()
+                        .spacing(5)
+                        .align_items(iced::Alignment::Center)
+                        .push(
+                            bright_paragraph(format!(
+                                "Radio: {}",
+                                station.seed.display_text(library)
+                            ))
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            dark_button(bright_paragraph("Stop Radio"))
+                                .on_press(Message::Action(message::Action::StopRadio)),
+                        ),
+                );
+            }
             let mut play_queue_column = Column::new();
             let mut stripe_marker = false;
 
@@ -362,6 +392,14 @@ pub fn render_play_queue<'a>(
                                 .on_press(components::track_link(&play_queue_track.track.metadata))
                                 .width(Length::Fill),
                             )
+                            .push(dark_button(bright_paragraph("+")).on_press(
+                                message::Message::PlaybackRequest(
+                                    shared::PlaybackRequest::PlayTrackNext(
+                                        shared::HistoryOrQueue::History,
+                                        index,
+                                    ),
+                                ),
+                            ))
                             .push(dark_button(bright_paragraph("-")).on_press(
                                 message::Message::PlaybackRequest(
                                     shared::PlaybackRequest::RemoveTrackFromPlayQueue(
@@ -463,6 +501,7 @@ pub fn render_play_queue<'a>(
                                 .on_press(components::track_link(&play_queue_track.track.metadata))
                                 .width(Length::Fill),
                             )
+                            .push(play_queue_entry_move_buttons(index))
                             .push(dark_button(bright_paragraph("-")).on_press(
                                 message::Message::PlaybackRequest(
                                     shared::PlaybackRequest::RemoveTrackFromPlayQueue(
@@ -478,10 +517,11 @@ pub fn render_play_queue<'a>(
                             line_row()
                                 .spacing(5)
                                 .push(bright_paragraph("Paused").width(Length::Fill))
+                                .push(play_queue_entry_move_buttons(index))
                                 .push(dark_button(bright_paragraph("-")).on_press(
                                     message::Message::PlaybackRequest(
                                         shared::PlaybackRequest::RemoveTrackFromPlayQueue(
-                                            shared::HistoryOrQueue::History,
+                                            shared::HistoryOrQueue::Queue,
                                             index,
                                         ),
                                     ),
@@ -516,6 +556,25 @@ pub fn render_play_queue<'a>(
     }
 }
 
+fn play_queue_entry_move_buttons<'a>(index: usize) -> Row<'a, Message> {
+    Row::new()
+        .push(
+            dark_button(bright_paragraph("^^")).on_press(message::Message::PlaybackRequest(
+                shared::PlaybackRequest::PlayTrackNext(shared::HistoryOrQueue::Queue, index),
+            )),
+        )
+        .push(
+            dark_button(bright_paragraph("^")).on_press(message::Message::PlaybackRequest(
+                shared::PlaybackRequest::MoveTrackInPlayQueue(index, model::Direction::Up),
+            )),
+        )
+        .push(
+            dark_button(bright_paragraph("v")).on_press(message::Message::PlaybackRequest(
+                shared::PlaybackRequest::MoveTrackInPlayQueue(index, model::Direction::Down),
+            )),
+        )
+}
+
 pub fn render_playthrough(
     maybe_current_playback: &Option<shared::CurrentPlayback>,
 ) -> Option<Container<'static, Message>> {
@@ -251,3 +251,26 @@ impl container::StyleSheet for NotificationGreenBackground {
         }
     }
 }
+
+pub struct ContainerHeatmapCell {
+    /// How hot the cell is, from 0.0 (coldest) to 1.0 (hottest)
+    pub intensity: f32,
+}
+
+impl container::StyleSheet for ContainerHeatmapCell {
+    type Style = iced::Theme;
+
+    fn appearance(&self, _theme: &iced::Theme) -> container::Appearance {
+        let cold = Color::from_rgb8(0x13, 0x13, 0x13);
+        let hot = Color::from_rgb8(0x00, 0xd8, 0xd8);
+        let intensity = self.intensity.clamp(0.0, 1.0);
+        container::Appearance {
+            background: Some(Background::Color(Color::from_rgb(
+                cold.r + (hot.r - cold.r) * intensity,
+                cold.g + (hot.g - cold.g) * intensity,
+                cold.b + (hot.b - cold.b) * intensity,
+            ))),
+            ..container::Appearance::default()
+        }
+    }
+}
@@ -17,7 +17,7 @@ pub enum RunMode {
     GUI,
     Background,
     ReportToTracker,
-    GenerateReport,
+    GenerateReport(Option<String>),
     Prototype,
     Explore,
 }
@@ -37,7 +37,8 @@ pub fn main() -> Result<(), AppError> {
 
     let mut run_mode = RunMode::GUI;
 
-    for arg in args.into_iter() {
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
         if arg == "--background" {
             run_mode = RunMode::Background;
         }
@@ -45,7 +46,11 @@ pub fn main() -> Result<(), AppError> {
             run_mode = RunMode::ReportToTracker;
         }
         if arg == "--report" {
-            run_mode = RunMode::GenerateReport;
+            let maybe_range = match args.peek() {
+                Some(next) if !next.starts_with("--") => args.next(),
+                _ => None,
+            };
+            run_mode = RunMode::GenerateReport(maybe_range);
         }
         if arg == "--prototype" {
             run_mode = RunMode::Prototype;
@@ -61,8 +66,8 @@ pub fn main() -> Result<(), AppError> {
             altmodes::background::run_server().map_err(AppError::BackgroundServer)
         }
         RunMode::ReportToTracker => altmodes::repl::report_tracks().map_err(AppError::Tracker),
-        RunMode::GenerateReport => {
-            altmodes::report::generate_year_end_report().map_err(AppError::ReportGeneration)
+        RunMode::GenerateReport(maybe_range) => {
+            altmodes::report::generate_report(maybe_range).map_err(AppError::ReportGeneration)
         }
         RunMode::Prototype => altmodes::proto::entry_point().map_err(AppError::Prototype),
         RunMode::Explore => altmodes::explore::entry_point().map_err(AppError::Explore),
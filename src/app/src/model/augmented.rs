@@ -1,3 +1,5 @@
+use chrono::{DateTime, Local};
+
 use musiqcore::datastore;
 
 pub type AugmentedLibrary = musiqlibrary::Library<AugmentedTrack>;
@@ -10,12 +12,14 @@ pub fn augmented_from_raw(
     raw_library.map_into(&|track| {
         let uniq_track_id = musiqlibrary::TrackUniqueIdentifier::from_track(&track);
         let live_play_count = tracked_data.get_live_track_count(&uniq_track_id);
+        let last_played = tracked_data.get_live_track_last_played(&uniq_track_id);
         let historical_play_count = historical_data.get_historical_track_count(&uniq_track_id);
         let total_play_count = live_play_count + historical_play_count;
 
         AugmentedTrack {
             augmented: AugmentedData {
                 play_count: total_play_count,
+                last_played,
                 tagged_genres: Vec::new(),
             },
             metadata: track,
@@ -102,5 +106,6 @@ pub fn artist_track_duration_total(
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct AugmentedData {
     pub play_count: usize,
+    pub last_played: Option<DateTime<Local>>,
     pub tagged_genres: Vec<String>,
 }
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::fs;
 use std::path;
 
-use musiqcore::model::constants;
+use musiqcore::model::{constants, gl, search};
 use musiqlibrary::video;
 
 use super::augmented;
@@ -32,6 +32,9 @@ pub enum Direction {
 pub enum SearchDomain {
     Music,
     Movies,
+    Shows,
+    Games,
+    Everything,
 }
 
 #[derive(Debug)]
@@ -238,3 +241,95 @@ pub struct SimpleSearchResults {
 pub struct MovieSearchResults {
     pub titles: Vec<video::MovieMetadata>,
 }
+
+/// How many results searching everything keeps, as it is one list of every kind
+const COMBINED_SEARCH_RESULT_LIMIT: usize = 100;
+
+#[derive(Debug, Clone)]
+pub enum CombinedSearchResult {
+    Artist(musiqlibrary::ArtistInfo),
+    Album(musiqlibrary::ArtistAlbumInfo),
+    Track(augmented::AugmentedTrack),
+    Movie(video::MovieMetadata),
+    Show(musiqlibrary::shows::ShowKey),
+    Episode(musiqlibrary::shows::ShowMetadata),
+    Game(gl::GameSearchResult),
+}
+
+impl CombinedSearchResult {
+    /// What the search matched this result by
+    pub fn search_text(&self) -> String {
+        match self {
+            CombinedSearchResult::Artist(artist) => artist.artist_name.clone(),
+            CombinedSearchResult::Album(album) => album.album.album_name.clone(),
+            CombinedSearchResult::Track(track) => track.metadata.title.clone(),
+            CombinedSearchResult::Movie(movie) => movie.title.clone(),
+            CombinedSearchResult::Show(show_key) => show_key.raw_string().clone(),
+            CombinedSearchResult::Episode(episode) => episode
+                .episode_id
+                .as_ref()
+                .unwrap_or(&episode.title)
+                .clone(),
+            CombinedSearchResult::Game(game) => game.name.clone(),
+        }
+    }
+
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            CombinedSearchResult::Artist(_) => "Artist",
+            CombinedSearchResult::Album(_) => "Album",
+            CombinedSearchResult::Track(_) => "Track",
+            CombinedSearchResult::Movie(_) => "Movie",
+            CombinedSearchResult::Show(_) => "Show",
+            CombinedSearchResult::Episode(_) => "Episode",
+            CombinedSearchResult::Game(_) => "Game",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CombinedSearchResults {
+    pub results: Vec<CombinedSearchResult>,
+}
+
+impl CombinedSearchResults {
+    /// Rank the results of every domain against each other, keeping the closest
+    pub fn combine(
+        query: &search::SearchQuery,
+        music: SimpleSearchResults,
+        movies: MovieSearchResults,
+        shows: Option<musiqcore::model::shows::ShowSearchResults>,
+        games: Vec<gl::GameSearchResult>,
+    ) -> Self {
+        let mut results = Vec::new();
+        results.extend(music.artists.into_iter().map(CombinedSearchResult::Artist));
+        results.extend(music.albums.into_iter().map(CombinedSearchResult::Album));
+        results.extend(music.tracks.into_iter().map(CombinedSearchResult::Track));
+        results.extend(movies.titles.into_iter().map(CombinedSearchResult::Movie));
+        if let Some(shows) = shows {
+            results.extend(shows.shows.into_iter().map(CombinedSearchResult::Show));
+            results.extend(
+                shows
+                    .episodes
+                    .into_iter()
+                    .map(CombinedSearchResult::Episode),
+            );
+        }
+        results.extend(games.into_iter().map(CombinedSearchResult::Game));
+
+        let scored = results
+            .into_iter()
+            .filter_map(|result| {
+                let search_text = result.search_text();
+                query
+                    .score(&search_text)
+                    .map(|score| (score, search_text, result))
+            })
+            .collect();
+
+        let mut results = search::ranked(scored);
+        results.truncate(COMBINED_SEARCH_RESULT_LIMIT);
+
+        CombinedSearchResults { results }
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+
+use musiqcore::datastore;
+
+/// Every live play (with when it was played) and prehistory play count, keyed by track
+pub struct PlayHistory {
+    live_history: BTreeMap<musiqlibrary::TrackUniqueIdentifier, Vec<DateTime<Local>>>,
+    prehistory_counts: BTreeMap<musiqlibrary::TrackUniqueIdentifier, usize>,
+}
+
+impl PlayHistory {
+    pub fn new(
+        raw_library: &musiqlibrary::RawLibrary,
+        live_reporter: &dyn datastore::traits::LiveReadOnlyTrackCountReporter,
+        historical_reporter: &dyn datastore::traits::HistoricalTrackCountReporter,
+    ) -> Self {
+        let mut live_history = BTreeMap::new();
+        let mut prehistory_counts = BTreeMap::new();
+
+        for track in raw_library.get_all_tracks().into_iter() {
+            let track_id = musiqlibrary::TrackUniqueIdentifier::from_track(track);
+
+            let history = live_reporter.get_live_track_history(&track_id);
+            if !history.is_empty() {
+                live_history.insert(track_id.clone(), history);
+            }
+
+            let prehistory_count = historical_reporter.get_historical_track_count(&track_id);
+            if prehistory_count > 0 {
+                prehistory_counts.insert(track_id, prehistory_count);
+            }
+        }
+
+        PlayHistory {
+            live_history,
+            prehistory_counts,
+        }
+    }
+}
+
+impl datastore::traits::LiveHistoryReadDS for PlayHistory {
+    fn get_track_history(
+        &self,
+        track_identifier: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Option<&Vec<DateTime<Local>>> {
+        self.live_history.get(track_identifier)
+    }
+
+    fn get_track_count(&self, track_identifier: &musiqlibrary::TrackUniqueIdentifier) -> usize {
+        self.live_history
+            .get(track_identifier)
+            .map(|history| history.len())
+            .unwrap_or(0)
+    }
+}
+
+impl datastore::traits::HistoricalTrackCountReporter for PlayHistory {
+    fn get_historical_track_count(&self, id: &musiqlibrary::TrackUniqueIdentifier) -> usize {
+        *self.prehistory_counts.get(id).unwrap_or(&0)
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
 
+use musiqcore::model::search;
+
 use crate::datastore::jsonbacked::playlists;
 
-use super::{augmented, common, sorts};
+use super::{augmented, common, history, radio, sorts};
 
 use super::musicbrainzlib;
 
@@ -15,6 +17,9 @@ pub struct LibraryState {
 
     pub musicbrainz_library: musicbrainzlib::Library,
 
+    pub play_history: history::PlayHistory,
+    pub listening_sessions: radio::ListeningSessions,
+
     pub artist_sorts: sorts::ArtistSorts,
     pub album_sorts: sorts::AlbumSorts,
     pub track_sorts: sorts::TrackSorts,
@@ -29,6 +34,8 @@ impl LibraryState {
         mut augmented_library: augmented::AugmentedLibrary,
         extra_library: ExtraLibraryKeys,
         musicbrainz_library: musicbrainzlib::Library,
+        play_history: history::PlayHistory,
+        listening_sessions: radio::ListeningSessions,
         playlists: playlists::PlaylistData,
         artist_sorts: sorts::ArtistSorts,
         album_sorts: sorts::AlbumSorts,
@@ -60,6 +67,9 @@ impl LibraryState {
 
             musicbrainz_library,
 
+            play_history,
+            listening_sessions,
+
             user_playlists: playlists,
 
             artist_sorts,
@@ -151,7 +161,6 @@ impl LibraryState {
         }
     }
 
-    #[allow(unused)]
     pub fn get_artist_album_tracks(
         &self,
         artist_id: musiqlibrary::ID,
@@ -238,82 +247,60 @@ impl LibraryState {
             .get_album_cover(album_size, artist_id, album_id)
     }
 
-    pub fn search(&self, query: String) -> common::SimpleSearchResults {
+    /// Artists, albums and tracks by name, closest first
+    pub fn search(&self, query: &search::SearchQuery) -> common::SimpleSearchResults {
         let mut artists = Vec::new();
         let mut artist_albums = Vec::new();
         let mut tracks = Vec::new();
         let mut track_artists = Vec::new();
 
         for artist in self.raw_library.artists.values() {
-            if artist
-                .artist_info
-                .artist_name
-                .to_lowercase()
-                .contains(&query.to_lowercase())
-            {
-                artists.push(musiqlibrary::ArtistInfo {
-                    artist_id: artist.artist_info.artist_id,
-                    artist_name: artist.artist_info.artist_name.clone(),
-                });
+            if let Some(score) = query.score(&artist.artist_info.artist_name) {
+                artists.push((
+                    score,
+                    artist.artist_info.artist_name.clone(),
+                    musiqlibrary::ArtistInfo {
+                        artist_id: artist.artist_info.artist_id,
+                        artist_name: artist.artist_info.artist_name.clone(),
+                    },
+                ));
             }
             for album in artist.albums.values() {
-                if album
-                    .album_info
-                    .album_name
-                    .to_lowercase()
-                    .contains(&query.to_lowercase())
-                {
-                    artist_albums.push(musiqlibrary::ArtistAlbumInfo {
-                        artist: artist.artist_info.clone(),
-                        album: album.album_info.clone(),
-                    });
+                if let Some(score) = query.score(&album.album_info.album_name) {
+                    artist_albums.push((
+                        score,
+                        album.album_info.album_name.clone(),
+                        musiqlibrary::ArtistAlbumInfo {
+                            artist: artist.artist_info.clone(),
+                            album: album.album_info.clone(),
+                        },
+                    ));
                 }
 
                 for disc in album.discs.values() {
                     for track in disc.tracks.values() {
-                        if track
-                            .metadata
-                            .title
-                            .to_lowercase()
-                            .contains(&query.to_lowercase())
-                        {
-                            tracks.push(track.clone());
+                        if let Some(score) = query.score(&track.metadata.title) {
+                            tracks.push((score, track.metadata.title.clone(), track.clone()));
                         }
-                        if track.metadata.track_artist != track.metadata.album_artist
-                            && track
-                                .metadata
-                                .track_artist
-                                .to_lowercase()
-                                .contains(&query.to_lowercase())
-                        {
-                            track_artists.push(track.clone());
+                        if track.metadata.track_artist != track.metadata.album_artist {
+                            if let Some(score) = query.score(&track.metadata.track_artist) {
+                                track_artists.push((
+                                    score,
+                                    track.metadata.track_artist.clone(),
+                                    track.clone(),
+                                ));
+                            }
                         }
                     }
                 }
             }
         }
 
-        artists.sort_unstable_by(|a, b| a.artist_name.cmp(&b.artist_name.to_lowercase()));
-
-        artist_albums.sort_unstable_by(|a, b| {
-            a.album
-                .album_name
-                .to_lowercase()
-                .cmp(&b.album.album_name.to_lowercase())
-        });
-
-        tracks.sort_unstable_by(|a, b| {
-            a.metadata
-                .title
-                .to_lowercase()
-                .cmp(&b.metadata.title.to_lowercase())
-        });
-
         common::SimpleSearchResults {
-            artists,
-            albums: artist_albums,
-            tracks,
-            track_artists,
+            artists: search::ranked(artists),
+            albums: search::ranked(artist_albums),
+            tracks: search::ranked(tracks),
+            track_artists: search::ranked(track_artists),
         }
     }
 }
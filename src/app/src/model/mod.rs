@@ -1,16 +1,21 @@
 mod augmented;
 mod common;
 pub mod functions;
+pub mod history;
 mod library;
 pub mod musicbrainzlib;
 pub mod playlist;
+pub mod radio;
+mod showart;
 mod sortkeys;
 mod sorts;
+pub mod stats;
 mod videolibrary;
 
 pub use augmented::*;
 pub use common::*;
 pub use library::*;
+pub use showart::*;
 pub use sortkeys::*;
 pub use sorts::*;
 pub use videolibrary::*;
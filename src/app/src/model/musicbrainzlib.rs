@@ -1,16 +1,35 @@
 use std::collections::BTreeMap;
+use std::io;
+use std::sync::Arc;
 
 use crate::datastore::cache;
 
+/// A library artist without an approved MusicBrainz artist, with what could be approved
+#[derive(Debug, Clone)]
+pub struct ArtistMatchCandidate {
+    pub artist_info: musiqlibrary::ArtistInfo,
+    /// The single best scoring result, if there was one
+    pub matched: Option<musicbrainz::Artist>,
+    /// Levenshtein distance between the (lowercased) library and matched names
+    pub distance: Option<usize>,
+    /// Every result from the last search, best scoring first
+    pub results: Vec<musicbrainz::Artist>,
+}
+
 pub struct Library {
+    pub client: Arc<musicbrainz::Client>,
     pub artists: BTreeMap<musiqlibrary::ID, musicbrainz::Artist>,
+    pub albums: BTreeMap<(musiqlibrary::ID, musiqlibrary::ID), musicbrainz::Release>,
 }
 
 impl Library {
     pub fn new(library: &musiqlibrary::RawLibrary) -> Self {
         let mut mb_artists = BTreeMap::new();
 
+        let mut mb_albums = BTreeMap::new();
+
         let musicbrainz_cache = cache::MusicBrainzCacheInterface::new();
+        let musicbrainz_album_cache = cache::MusicBrainzAlbumCacheInterface::new();
 
         for ml_artist in library.artists.values() {
             match musicbrainz_cache
@@ -21,14 +40,111 @@ impl Library {
                 }
                 None => (),
             };
+
+            for ml_album in ml_artist.albums.values() {
+                if let Some(mb_release) = musicbrainz_album_cache
+                    .read_musicbrainz_album_approved_file(
+                        ml_artist.artist_info.artist_name.clone(),
+                        ml_album.album_info.album_name.clone(),
+                    )
+                {
+                    let _ = mb_albums.insert(
+                        (
+                            ml_artist.artist_info.artist_id,
+                            ml_album.album_info.album_id,
+                        ),
+                        mb_release,
+                    );
+                }
+            }
         }
 
         Library {
+            client: Arc::new(musicbrainz::Client::new()),
             artists: mb_artists,
+            albums: mb_albums,
         }
     }
 
     pub fn get_artist_info(&self, artist_id: &musiqlibrary::ID) -> Option<&musicbrainz::Artist> {
         self.artists.get(artist_id)
     }
+
+    pub fn get_album_info(
+        &self,
+        artist_id: &musiqlibrary::ID,
+        album_id: &musiqlibrary::ID,
+    ) -> Option<&musicbrainz::Release> {
+        self.albums.get(&(*artist_id, *album_id))
+    }
+
+    /// Artists which are unmatched or only fuzzily matched, closest matches first
+    pub fn artist_match_candidates<'a, I: Iterator<Item = &'a musiqlibrary::ArtistInfo>>(
+        &self,
+        artist_infos: I,
+    ) -> Vec<ArtistMatchCandidate> {
+        let musicbrainz_cache = cache::MusicBrainzCacheInterface::new();
+
+        let mut candidates = Vec::new();
+        for artist_info in artist_infos {
+            if self.artists.contains_key(&artist_info.artist_id) {
+                continue;
+            }
+
+            let matched = musicbrainz_cache
+                .read_musicbrainz_artist_match_file(artist_info.artist_name.clone());
+            let distance = matched.as_ref().map(|mb_artist| {
+                musiqcore::model::functions::levenshtein(
+                    &mb_artist.name.to_lowercase(),
+                    &artist_info.artist_name.to_lowercase(),
+                )
+            });
+            let results = match musicbrainz_cache.raw_exists(artist_info.artist_name.clone()) {
+                true => {
+                    let mut results = musicbrainz::ArtistListResult::from_json(
+                        musicbrainz_cache
+                            .read_musicbrainz_artist_cache_file(artist_info.artist_name.clone()),
+                    )
+                    .artists;
+                    results.reverse();
+                    results
+                }
+                false => Vec::new(),
+            };
+
+            candidates.push(ArtistMatchCandidate {
+                artist_info: artist_info.clone(),
+                matched,
+                distance,
+                results,
+            });
+        }
+
+        candidates.sort_by_key(|candidate| candidate.distance.unwrap_or(usize::MAX));
+
+        candidates
+    }
+
+    /// Approve a MusicBrainz artist for a library artist, taking effect immediately
+    pub fn approve_artist(
+        &mut self,
+        artist_info: &musiqlibrary::ArtistInfo,
+        mb_artist: musicbrainz::Artist,
+    ) {
+        cache::MusicBrainzCacheInterface::new()
+            .write_musicbrainz_artist_approved_file(artist_info.artist_name.clone(), &mb_artist);
+        let _ = self.artists.insert(artist_info.artist_id, mb_artist);
+    }
+
+    /// Drop the proposed match for a library artist, leaving its search results
+    pub fn reject_artist_match(&self, artist_info: &musiqlibrary::ArtistInfo) -> io::Result<()> {
+        cache::MusicBrainzCacheInterface::new()
+            .remove_musicbrainz_artist_match_file(artist_info.artist_name.clone())
+    }
+
+    /// Store fresh search results for a library artist
+    pub fn save_artist_search(&self, artist_info: &musiqlibrary::ArtistInfo, raw_results: String) {
+        cache::MusicBrainzCacheInterface::new()
+            .write_musicbrainz_artist_cache_file(artist_info.artist_name.clone(), raw_results);
+    }
 }
@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Duration, Local};
+use rand::Rng;
+
+use musiqcore::datastore;
+
+use super::augmented;
+use super::library;
+
+/// Plays further apart than this are considered separate listening sessions
+const SESSION_GAP_MINUTES: i64 = 45;
+
+/// Tracks played within this many days are never picked by the radio
+const RECENTLY_PLAYED_DAYS: i64 = 3;
+
+/// How many of the best scoring candidates the radio randomly picks from
+const CANDIDATE_POOL_SIZE: usize = 50;
+
+/// How far apart (in years) two tracks can be and still share an era
+const ERA_SPAN_YEARS: f64 = 10.0;
+
+const GENRE_WEIGHT: f64 = 3.0;
+const ARTIST_WEIGHT: f64 = 4.0;
+const ERA_WEIGHT: f64 = 2.0;
+const CO_LISTEN_WEIGHT: f64 = 5.0;
+
+/// How many tracks the radio adds to the play queue at a time
+pub const RADIO_BATCH_SIZE: usize = 5;
+
+/// The radio tops up the play queue once it has fewer tracks than this
+pub const RADIO_MIN_QUEUED: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadioSeed {
+    Track(musiqlibrary::TrackUniqueIdentifier),
+    Album(musiqlibrary::AlbumUniqueIdentifier),
+    Artist(musiqlibrary::ID),
+}
+
+impl RadioSeed {
+    pub fn display_text(&self, library: &library::LibraryState) -> String {
+        match self {
+            RadioSeed::Track(track_id) => library.get_track(track_id).metadata.title.clone(),
+            RadioSeed::Album(album_id) => {
+                library
+                    .get_artist_album_info(album_id.artist_id, album_id.album_id)
+                    .album
+                    .album_name
+            }
+            RadioSeed::Artist(artist_id) => library.get_artist_info(*artist_id).artist_name,
+        }
+    }
+
+    fn seed_tracks(&self, library: &library::LibraryState) -> Vec<augmented::AugmentedTrack> {
+        match self {
+            RadioSeed::Track(track_id) => vec![library.get_track(track_id).clone()],
+            RadioSeed::Album(album_id) => library
+                .get_artist_album_tracks(album_id.artist_id, album_id.album_id)
+                .discs
+                .values()
+                .flat_map(|disc| disc.tracks.values().cloned())
+                .collect(),
+            RadioSeed::Artist(artist_id) => {
+                let mut tracks: Vec<augmented::AugmentedTrack> = library
+                    .get_artist_tracks(artist_id)
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                tracks.append(&mut library.get_featured_tracks_for_artist(artist_id));
+                tracks
+            }
+        }
+    }
+}
+
+/// Groups of tracks that were listened to together, mined from the live play history
+pub struct ListeningSessions {
+    sessions: Vec<BTreeSet<musiqlibrary::TrackUniqueIdentifier>>,
+}
+
+impl ListeningSessions {
+    pub fn new(
+        raw_library: &musiqlibrary::RawLibrary,
+        live_history: &dyn datastore::traits::LiveHistoryReadDS,
+    ) -> Self {
+        let mut plays: Vec<(DateTime<Local>, musiqlibrary::TrackUniqueIdentifier)> = Vec::new();
+        for track in raw_library.get_all_tracks().into_iter() {
+            let track_id = musiqlibrary::TrackUniqueIdentifier::from_track(track);
+            if let Some(history) = live_history.get_track_history(&track_id) {
+                for listened_date in history.iter() {
+                    plays.push((*listened_date, track_id.clone()));
+                }
+            }
+        }
+        plays.sort_by_key(|(listened_date, _)| *listened_date);
+
+        let mut sessions = Vec::new();
+        let mut current_session = BTreeSet::new();
+        let mut last_listened: Option<DateTime<Local>> = None;
+        for (listened_date, track_id) in plays.into_iter() {
+            if let Some(last) = last_listened {
+                if listened_date - last > Duration::minutes(SESSION_GAP_MINUTES) {
+                    sessions.push(current_session);
+                    current_session = BTreeSet::new();
+                }
+            }
+            current_session.insert(track_id);
+            last_listened = Some(listened_date);
+        }
+        if !current_session.is_empty() {
+            sessions.push(current_session);
+        }
+
+        ListeningSessions { sessions }
+    }
+
+    /// How many sessions each track shared with any of the given tracks
+    fn co_listen_counts(
+        &self,
+        tracks: &BTreeSet<musiqlibrary::TrackUniqueIdentifier>,
+    ) -> BTreeMap<musiqlibrary::TrackUniqueIdentifier, usize> {
+        let mut counts = BTreeMap::new();
+        for session in self.sessions.iter() {
+            if session.is_disjoint(tracks) {
+                continue;
+            }
+            for track_id in session.iter() {
+                if !tracks.contains(track_id) {
+                    *counts.entry(track_id.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+}
+
+/// A running radio, which knows what its seed sounds like and what it has already picked
+#[derive(Debug, Clone)]
+pub struct RadioStation {
+    pub seed: RadioSeed,
+    seed_tracks: BTreeSet<musiqlibrary::TrackUniqueIdentifier>,
+    genres: BTreeSet<String>,
+    artists: BTreeSet<musiqlibrary::ID>,
+    era: Option<f64>,
+    co_listens: BTreeMap<musiqlibrary::TrackUniqueIdentifier, usize>,
+    max_co_listens: usize,
+    picked: BTreeSet<musiqlibrary::TrackUniqueIdentifier>,
+    /// The first track of the last batch sent to the backend, until it shows up in the play queue
+    pub awaiting: Option<musiqlibrary::TrackUniqueIdentifier>,
+}
+
+impl RadioStation {
+    pub fn new(library: &library::LibraryState, seed: RadioSeed) -> Self {
+        let tracks = seed.seed_tracks(library);
+
+        let seed_tracks: BTreeSet<musiqlibrary::TrackUniqueIdentifier> = tracks
+            .iter()
+            .map(|track| musiqlibrary::TrackUniqueIdentifier::from_track(&track.metadata))
+            .collect();
+
+        let genres = tracks
+            .iter()
+            .filter_map(|track| normalized_genre(&track.metadata.genre))
+            .collect();
+
+        let mut artists = BTreeSet::new();
+        for track in tracks.iter() {
+            artists.insert(track.metadata.album_artist_id);
+            artists.insert(track.metadata.track_artist_id);
+        }
+
+        let era = match tracks.len() {
+            0 => None,
+            len => Some(
+                tracks
+                    .iter()
+                    .map(|track| track.metadata.date_number as f64)
+                    .sum::<f64>()
+                    / len as f64,
+            ),
+        };
+
+        let co_listens = library.listening_sessions.co_listen_counts(&seed_tracks);
+        let max_co_listens = co_listens.values().cloned().max().unwrap_or(0);
+
+        RadioStation {
+            seed,
+            seed_tracks,
+            genres,
+            artists,
+            era,
+            co_listens,
+            max_co_listens,
+            picked: BTreeSet::new(),
+            awaiting: None,
+        }
+    }
+
+    /// Pick the next tracks for the radio, never picking anything it already picked,
+    /// anything in `exclude`, or anything that was played recently
+    pub fn next_tracks(
+        &mut self,
+        library: &library::LibraryState,
+        exclude: &BTreeSet<musiqlibrary::TrackUniqueIdentifier>,
+        count: usize,
+    ) -> Vec<augmented::AugmentedTrack> {
+        let recent_cutoff = Local::now() - Duration::days(RECENTLY_PLAYED_DAYS);
+
+        let mut scored: Vec<(f64, &augmented::AugmentedTrack)> = Vec::new();
+        for track in library.raw_library.get_all_tracks().into_iter() {
+            let track_id = musiqlibrary::TrackUniqueIdentifier::from_track(&track.metadata);
+            if self.seed_tracks.contains(&track_id)
+                || self.picked.contains(&track_id)
+                || exclude.contains(&track_id)
+            {
+                continue;
+            }
+            if let Some(last_played) = track.augmented.last_played {
+                if last_played > recent_cutoff {
+                    continue;
+                }
+            }
+
+            let score = self.score(&track_id, track);
+            if score > 0.0 {
+                scored.push((score, track));
+            }
+        }
+
+        scored.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap());
+        scored.truncate(CANDIDATE_POOL_SIZE);
+
+        // weighted random sampling without replacement, so that the best
+        // candidates are the most likely picks without always being the same
+        let mut rng = rand::thread_rng();
+        let mut keyed: Vec<(f64, &augmented::AugmentedTrack)> = scored
+            .into_iter()
+            .map(|(score, track)| (rng.gen::<f64>().powf(1.0 / score), track))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap());
+
+        let picks: Vec<augmented::AugmentedTrack> = keyed
+            .into_iter()
+            .take(count)
+            .map(|(_key, track)| track.clone())
+            .collect();
+
+        for track in picks.iter() {
+            self.picked
+                .insert(musiqlibrary::TrackUniqueIdentifier::from_track(
+                    &track.metadata,
+                ));
+        }
+        self.awaiting = picks
+            .first()
+            .map(|track| musiqlibrary::TrackUniqueIdentifier::from_track(&track.metadata));
+
+        picks
+    }
+
+    fn score(
+        &self,
+        track_id: &musiqlibrary::TrackUniqueIdentifier,
+        track: &augmented::AugmentedTrack,
+    ) -> f64 {
+        let mut score = 0.0;
+
+        if let Some(genre) = normalized_genre(&track.metadata.genre) {
+            if self.genres.contains(&genre) {
+                score += GENRE_WEIGHT;
+            }
+        }
+
+        if self.artists.contains(&track.metadata.album_artist_id)
+            || self.artists.contains(&track.metadata.track_artist_id)
+        {
+            score += ARTIST_WEIGHT;
+        }
+
+        if let Some(era) = self.era {
+            let distance = (track.metadata.date_number as f64 - era).abs();
+            score += ERA_WEIGHT * (1.0 - distance / ERA_SPAN_YEARS).max(0.0);
+        }
+
+        if self.max_co_listens > 0 {
+            let co_listens = self.co_listens.get(track_id).cloned().unwrap_or(0);
+            score += CO_LISTEN_WEIGHT * co_listens as f64 / self.max_co_listens as f64;
+        }
+
+        score
+    }
+}
+
+fn normalized_genre(genre: &str) -> Option<String> {
+    let genre = genre.trim().to_lowercase();
+    match genre.is_empty() {
+        true => None,
+        false => Some(genre),
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::path;
+
+use musiqlibrary::shows;
+
+use super::common;
+
+/// The file one size of a piece of show art is cached as, in its cache directory
+pub fn show_art_file_name(size: &common::MovieSize) -> &'static str {
+    match size {
+        common::MovieSize::Large => "large.png",
+        common::MovieSize::SemiLarge => "semilarge.png",
+        common::MovieSize::Regular => "regular.png",
+        common::MovieSize::Small => "small.png",
+        common::MovieSize::Micro => "micro.png",
+    }
+}
+
+/// One kind of show art, by the directory its sizes are cached in. The images are only read when
+/// they are drawn, and one that is still being cached shows up once it is there.
+pub struct CachedArt<K: Ord> {
+    cache_dirs: BTreeMap<K, path::PathBuf>,
+}
+
+impl<K: Ord> Default for CachedArt<K> {
+    fn default() -> Self {
+        CachedArt {
+            cache_dirs: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K: Ord> CachedArt<K> {
+    pub fn insert(&mut self, key: K, cache_dir: path::PathBuf) {
+        let _ = self.cache_dirs.insert(key, cache_dir);
+    }
+
+    pub fn get(&self, size: common::MovieSize, key: &K) -> Option<path::PathBuf> {
+        let sized_path = self.cache_dirs.get(key)?.join(show_art_file_name(&size));
+        match sized_path.is_file() {
+            true => Some(sized_path),
+            false => None,
+        }
+    }
+}
+
+/// Which show art an image belongs to
+#[derive(Debug, Clone)]
+pub enum ShowArtKey {
+    Show(shows::ShowKey),
+    Season(shows::ShowKey, u32),
+    Episode(shows::ShowEpisodeKey),
+}
+
+#[derive(Default)]
+pub struct ShowArt {
+    pub show_posters: CachedArt<shows::ShowKey>,
+    pub season_posters: CachedArt<(shows::ShowKey, u32)>,
+    pub episode_thumbnails: CachedArt<shows::ShowEpisodeKey>,
+}
+
+impl ShowArt {
+    pub fn insert(&mut self, key: ShowArtKey, cache_dir: path::PathBuf) {
+        match key {
+            ShowArtKey::Show(show_key) => self.show_posters.insert(show_key, cache_dir),
+            ShowArtKey::Season(show_key, season_number) => self
+                .season_posters
+                .insert((show_key, season_number), cache_dir),
+            ShowArtKey::Episode(episode_key) => {
+                self.episode_thumbnails.insert(episode_key, cache_dir)
+            }
+        }
+    }
+
+    pub fn get_show_poster(
+        &self,
+        size: common::MovieSize,
+        show_key: &shows::ShowKey,
+    ) -> Option<path::PathBuf> {
+        self.show_posters.get(size, show_key)
+    }
+
+    /// The season's own poster, or the show's when the season has none
+    pub fn get_season_poster(
+        &self,
+        size: common::MovieSize,
+        show_key: &shows::ShowKey,
+        season_number: u32,
+    ) -> Option<path::PathBuf> {
+        match self
+            .season_posters
+            .get(size.clone(), &(show_key.clone(), season_number))
+        {
+            Some(sized_path) => Some(sized_path),
+            None => self.get_show_poster(size, show_key),
+        }
+    }
+
+    /// The episode's thumbnail (or frame grab), which is landscape unlike the posters
+    pub fn get_episode_thumbnail(
+        &self,
+        size: common::MovieSize,
+        episode_key: &shows::ShowEpisodeKey,
+    ) -> Option<path::PathBuf> {
+        self.episode_thumbnails.get(size, episode_key)
+    }
+}
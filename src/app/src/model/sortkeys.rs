@@ -299,6 +299,9 @@ pub enum MovieSortKey {
     LastModified,
     ByDuration,
     ByRelease,
+    LastWatched,
+    WatchCount,
+    NeverWatched,
     Random,
 }
 
@@ -309,7 +312,10 @@ impl MovieSortKey {
             MovieSortKey::LastModified => MovieSortKey::ByTitle,
             MovieSortKey::ByDuration => MovieSortKey::LastModified,
             MovieSortKey::ByRelease => MovieSortKey::ByDuration,
-            MovieSortKey::Random => MovieSortKey::ByRelease,
+            MovieSortKey::LastWatched => MovieSortKey::ByRelease,
+            MovieSortKey::WatchCount => MovieSortKey::LastWatched,
+            MovieSortKey::NeverWatched => MovieSortKey::WatchCount,
+            MovieSortKey::Random => MovieSortKey::NeverWatched,
         }
     }
 
@@ -318,7 +324,10 @@ impl MovieSortKey {
             MovieSortKey::ByTitle => MovieSortKey::LastModified,
             MovieSortKey::LastModified => MovieSortKey::ByDuration,
             MovieSortKey::ByDuration => MovieSortKey::ByRelease,
-            MovieSortKey::ByRelease => MovieSortKey::Random,
+            MovieSortKey::ByRelease => MovieSortKey::LastWatched,
+            MovieSortKey::LastWatched => MovieSortKey::WatchCount,
+            MovieSortKey::WatchCount => MovieSortKey::NeverWatched,
+            MovieSortKey::NeverWatched => MovieSortKey::Random,
             MovieSortKey::Random => MovieSortKey::Random,
         }
     }
@@ -329,6 +338,9 @@ impl MovieSortKey {
             MovieSortKey::LastModified => SortOrder::Reversed,
             MovieSortKey::ByDuration => SortOrder::Reversed,
             MovieSortKey::ByRelease => SortOrder::Reversed,
+            MovieSortKey::LastWatched => SortOrder::Reversed,
+            MovieSortKey::WatchCount => SortOrder::Reversed,
+            MovieSortKey::NeverWatched => SortOrder::Regular,
             MovieSortKey::Random => SortOrder::Regular,
         }
     }
@@ -339,6 +351,9 @@ impl MovieSortKey {
             MovieSortKey::LastModified => "Modified",
             MovieSortKey::ByDuration => "Length",
             MovieSortKey::ByRelease => "Release",
+            MovieSortKey::LastWatched => "Watched",
+            MovieSortKey::WatchCount => "Watch Count",
+            MovieSortKey::NeverWatched => "Unwatched",
             MovieSortKey::Random => "Random",
         }
         .to_string()
@@ -713,11 +713,17 @@ pub struct MovieSorts {
     pub by_last_modified: common::ListAndReversed<video::MovieMetadata>,
     pub by_duration: common::ListAndReversed<video::MovieMetadata>,
     pub by_release: common::ListAndReversed<video::MovieMetadata>,
+    pub by_last_watched: common::ListAndReversed<video::MovieMetadata>,
+    pub by_watch_count: common::ListAndReversed<video::MovieMetadata>,
+    pub never_watched: common::ListAndReversed<video::MovieMetadata>,
     pub random: common::ListAndReversed<video::MovieMetadata>,
 }
 
 impl MovieSorts {
-    pub fn new(movies: &Vec<video::MovieMetadata>) -> Self {
+    pub fn new(
+        movies: &Vec<video::MovieMetadata>,
+        tracker: &musiqcore::datastore::jsonbacked::movietracker::MovieTracker,
+    ) -> Self {
         MovieSorts {
             by_title: {
                 let mut movies_by_title = movies.clone();
@@ -750,13 +756,44 @@ impl MovieSorts {
                 movies_by_release.sort_unstable_by(|a, b| {
                     a.extra
                         .as_ref()
-                        .map(|x| x.release)
-                        .cmp(&b.extra.as_ref().map(|x| x.release))
+                        .and_then(|x| x.release)
+                        .cmp(&b.extra.as_ref().and_then(|x| x.release))
                 });
 
                 common::ListAndReversed::new(movies_by_release.to_vec())
             },
 
+            by_last_watched: {
+                let mut movies_by_last_watched = movies.clone();
+
+                movies_by_last_watched
+                    .sort_by_cached_key(|movie| tracker.get_last_viewed(&movie.get_id()));
+
+                common::ListAndReversed::new(movies_by_last_watched)
+            },
+
+            by_watch_count: {
+                let mut movies_by_watch_count = movies.clone();
+
+                movies_by_watch_count
+                    .sort_by_cached_key(|movie| tracker.get_view_count(&movie.get_id()));
+
+                common::ListAndReversed::new(movies_by_watch_count)
+            },
+
+            never_watched: {
+                let mut movies_never_watched_first = movies.clone();
+
+                movies_never_watched_first.sort_by_cached_key(|movie| {
+                    (
+                        tracker.get_view_count(&movie.get_id()) > 0,
+                        movie.title.to_lowercase(),
+                    )
+                });
+
+                common::ListAndReversed::new(movies_never_watched_first)
+            },
+
             random: {
                 let mut rng = rand::thread_rng();
 
@@ -778,6 +815,9 @@ impl MovieSorts {
             sortkeys::MovieSortKey::LastModified => &self.by_last_modified,
             sortkeys::MovieSortKey::ByDuration => &self.by_duration,
             sortkeys::MovieSortKey::ByRelease => &self.by_release,
+            sortkeys::MovieSortKey::LastWatched => &self.by_last_watched,
+            sortkeys::MovieSortKey::WatchCount => &self.by_watch_count,
+            sortkeys::MovieSortKey::NeverWatched => &self.never_watched,
             sortkeys::MovieSortKey::Random => &self.random,
         }
         .sort_ordered(sort_order)
@@ -0,0 +1,353 @@
+use std::cmp;
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike};
+
+use musiqcore::datastore;
+
+/// The most trailing days a stats range can cover (about a century)
+const MAX_LAST_DAYS: i64 = 36_500;
+
+/// Which plays the listening stats are computed over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsRange {
+    AllTime,
+    Year(i32),
+    LastDays(i64),
+    /// Inclusive on both ends
+    Between(NaiveDate, NaiveDate),
+}
+
+impl StatsRange {
+    pub fn this_year() -> Self {
+        StatsRange::Year(Local::now().year())
+    }
+
+    /// Parses `all`, a year (`2024`), a number of trailing days (`30d`),
+    /// or an inclusive date range (`2024-01-01..2024-06-30`)
+    pub fn parse(arg: &str) -> Result<Self, String> {
+        let arg = arg.trim();
+        if arg == "all" {
+            return Ok(StatsRange::AllTime);
+        }
+        if let Some((start, end)) = arg.split_once("..") {
+            let start = NaiveDate::parse_from_str(start, "%Y-%m-%d")
+                .map_err(|e| format!("invalid start date {}: {}", start, e))?;
+            let end = NaiveDate::parse_from_str(end, "%Y-%m-%d")
+                .map_err(|e| format!("invalid end date {}: {}", end, e))?;
+            return match start <= end {
+                true => Ok(StatsRange::Between(start, end)),
+                false => Err(format!("range start {} is after its end {}", start, end)),
+            };
+        }
+        if let Some(days) = arg.strip_suffix('d') {
+            let days = days
+                .parse::<i64>()
+                .map_err(|e| format!("invalid day count {}: {}", days, e))?;
+            return match days {
+                1..=MAX_LAST_DAYS => Ok(StatsRange::LastDays(days)),
+                _ => Err(format!(
+                    "day count {} is not between 1 and {}",
+                    days, MAX_LAST_DAYS
+                )),
+            };
+        }
+        let year = arg
+            .parse::<i32>()
+            .map_err(|_| format!("unrecognized stats range: {}", arg))?;
+        match (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            year.checked_add(1)
+                .and_then(|next_year| NaiveDate::from_ymd_opt(next_year, 1, 1)),
+        ) {
+            (Some(_), Some(_)) => Ok(StatsRange::Year(year)),
+            _ => Err(format!("year {} is out of range", year)),
+        }
+    }
+
+    pub fn display_text(&self) -> String {
+        match self {
+            StatsRange::AllTime => "All Time".to_string(),
+            StatsRange::Year(year) => year.to_string(),
+            StatsRange::LastDays(days) => format!("Last {} Days", days),
+            StatsRange::Between(start, end) => format!("{} - {}", start, end),
+        }
+    }
+
+    /// The inclusive start and exclusive end of the range, where `None` is unbounded
+    fn bounds(&self) -> (Option<DateTime<Local>>, Option<DateTime<Local>>) {
+        match self {
+            StatsRange::AllTime => (None, None),
+            StatsRange::Year(year) => (
+                NaiveDate::from_ymd_opt(*year, 1, 1).map(local_midnight),
+                year.checked_add(1)
+                    .and_then(|next_year| NaiveDate::from_ymd_opt(next_year, 1, 1))
+                    .map(local_midnight),
+            ),
+            StatsRange::LastDays(days) => (Some(Local::now() - Duration::days(*days)), None),
+            StatsRange::Between(start, end) => (
+                Some(local_midnight(*start)),
+                end.succ_opt().map(local_midnight),
+            ),
+        }
+    }
+
+    pub fn contains(&self, date_time: &DateTime<Local>) -> bool {
+        let (start, end) = self.bounds();
+        start.map(|start| *date_time >= start).unwrap_or(true)
+            && end.map(|end| *date_time < end).unwrap_or(true)
+    }
+
+    /// Prehistory plays have no dates, so they only count towards all-time stats
+    pub fn includes_prehistory(&self) -> bool {
+        matches!(self, StatsRange::AllTime)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RankedArtist {
+    pub artist_id: musiqlibrary::ID,
+    pub artist_name: String,
+    pub play_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RankedAlbum {
+    pub artist_id: musiqlibrary::ID,
+    pub album_id: musiqlibrary::ID,
+    pub artist_name: String,
+    pub album_name: String,
+    pub play_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RankedTrack {
+    pub track: musiqlibrary::FullTrackMetadata,
+    pub play_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RankedGenre {
+    pub genre: String,
+    pub play_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    pub first_played: DateTime<Local>,
+    pub track: musiqlibrary::FullTrackMetadata,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListeningStats {
+    pub range: StatsRange,
+    pub play_count: usize,
+    pub listened_seconds: u64,
+
+    pub top_artists: Vec<RankedArtist>,
+    pub top_albums: Vec<RankedAlbum>,
+    pub top_tracks: Vec<RankedTrack>,
+    pub top_genres: Vec<RankedGenre>,
+
+    /// Seconds listened, keyed by the day
+    pub listened_per_day: BTreeMap<NaiveDate, u64>,
+    /// Seconds listened, keyed by the Monday starting the week
+    pub listened_per_week: BTreeMap<NaiveDate, u64>,
+    /// Seconds listened, keyed by the first of the month
+    pub listened_per_month: BTreeMap<NaiveDate, u64>,
+
+    /// The longest run of consecutive days with a play, as its first day and length
+    pub longest_streak: Option<(NaiveDate, usize)>,
+    /// How many consecutive days, up to today (or yesterday), have had a play
+    pub current_streak: usize,
+
+    /// Tracks first ever played within the range
+    pub discoveries: Vec<Discovery>,
+
+    /// Play counts by weekday (Monday first) and hour of day
+    pub time_of_day: [[usize; 24]; 7],
+}
+
+impl ListeningStats {
+    pub fn compute(
+        tracks: Vec<&musiqlibrary::FullTrackMetadata>,
+        live_history: &dyn datastore::traits::LiveHistoryReadDS,
+        prehistory: &dyn datastore::traits::HistoricalTrackCountReporter,
+        range: StatsRange,
+    ) -> Self {
+        let mut play_count = 0;
+        let mut listened_seconds = 0;
+
+        let mut artist_counts: BTreeMap<musiqlibrary::ID, RankedArtist> = BTreeMap::new();
+        let mut album_counts: BTreeMap<musiqlibrary::AlbumUniqueIdentifier, RankedAlbum> =
+            BTreeMap::new();
+        let mut genre_counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut top_tracks = Vec::new();
+
+        let mut listened_per_day = BTreeMap::new();
+        let mut listened_per_week = BTreeMap::new();
+        let mut listened_per_month = BTreeMap::new();
+        let mut discoveries = Vec::new();
+        let mut time_of_day = [[0; 24]; 7];
+
+        for track in tracks.into_iter() {
+            let track_id = musiqlibrary::TrackUniqueIdentifier::from_track(track);
+            let track_seconds = track.duration.as_secs();
+
+            let history: Vec<&DateTime<Local>> = live_history
+                .get_track_history(&track_id)
+                .map(|history| history.iter().collect())
+                .unwrap_or_default();
+            let prehistory_count = prehistory.get_historical_track_count(&track_id);
+
+            if prehistory_count == 0 {
+                if let Some(first_played) = history.iter().min() {
+                    if range.contains(first_played) {
+                        discoveries.push(Discovery {
+                            first_played: **first_played,
+                            track: track.clone(),
+                        });
+                    }
+                }
+            }
+
+            let mut track_play_count = match range.includes_prehistory() {
+                true => prehistory_count,
+                false => 0,
+            };
+
+            for listened_date in history.into_iter() {
+                if !range.contains(listened_date) {
+                    continue;
+                }
+                track_play_count += 1;
+
+                let day = listened_date.date_naive();
+                let week = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+                let month = day.with_day(1).unwrap();
+                *listened_per_day.entry(day).or_insert(0) += track_seconds;
+                *listened_per_week.entry(week).or_insert(0) += track_seconds;
+                *listened_per_month.entry(month).or_insert(0) += track_seconds;
+
+                time_of_day[day.weekday().num_days_from_monday() as usize]
+                    [listened_date.hour() as usize] += 1;
+            }
+
+            if track_play_count == 0 {
+                continue;
+            }
+
+            play_count += track_play_count;
+            listened_seconds += track_seconds * track_play_count as u64;
+
+            artist_counts
+                .entry(track.album_artist_id)
+                .or_insert(RankedArtist {
+                    artist_id: track.album_artist_id,
+                    artist_name: track.album_artist.clone(),
+                    play_count: 0,
+                })
+                .play_count += track_play_count;
+
+            album_counts
+                .entry(musiqlibrary::AlbumUniqueIdentifier::new(
+                    track.album_artist_id,
+                    track.album_id,
+                ))
+                .or_insert(RankedAlbum {
+                    artist_id: track.album_artist_id,
+                    album_id: track.album_id,
+                    artist_name: track.album_artist.clone(),
+                    album_name: track.album.clone(),
+                    play_count: 0,
+                })
+                .play_count += track_play_count;
+
+            if !track.genre.is_empty() {
+                *genre_counts.entry(track.genre.clone()).or_insert(0) += track_play_count;
+            }
+
+            top_tracks.push(RankedTrack {
+                track: track.clone(),
+                play_count: track_play_count,
+            });
+        }
+
+        let mut top_artists: Vec<RankedArtist> = artist_counts.into_values().collect();
+        top_artists.sort_by_key(|ranked| cmp::Reverse(ranked.play_count));
+
+        let mut top_albums: Vec<RankedAlbum> = album_counts.into_values().collect();
+        top_albums.sort_by_key(|ranked| cmp::Reverse(ranked.play_count));
+
+        top_tracks.sort_by_key(|ranked| cmp::Reverse(ranked.play_count));
+
+        let mut top_genres: Vec<RankedGenre> = genre_counts
+            .into_iter()
+            .map(|(genre, play_count)| RankedGenre { genre, play_count })
+            .collect();
+        top_genres.sort_by_key(|ranked| cmp::Reverse(ranked.play_count));
+
+        discoveries.sort_by_key(|discovery| discovery.first_played);
+
+        let (longest_streak, current_streak) = compute_streaks(&listened_per_day);
+
+        ListeningStats {
+            range,
+            play_count,
+            listened_seconds,
+            top_artists,
+            top_albums,
+            top_tracks,
+            top_genres,
+            listened_per_day,
+            listened_per_week,
+            listened_per_month,
+            longest_streak,
+            current_streak,
+            discoveries,
+            time_of_day,
+        }
+    }
+}
+
+fn compute_streaks(
+    listened_per_day: &BTreeMap<NaiveDate, u64>,
+) -> (Option<(NaiveDate, usize)>, usize) {
+    let mut longest_streak: Option<(NaiveDate, usize)> = None;
+    let mut streak: Option<(NaiveDate, usize)> = None;
+    let mut previous_day: Option<NaiveDate> = None;
+
+    for day in listened_per_day.keys() {
+        streak = match (streak, previous_day) {
+            (Some((start, length)), Some(previous)) if previous.succ_opt() == Some(*day) => {
+                Some((start, length + 1))
+            }
+            _ => Some((*day, 1)),
+        };
+        previous_day = Some(*day);
+
+        if longest_streak.map(|(_, longest)| longest).unwrap_or(0) < streak.unwrap().1 {
+            longest_streak = streak;
+        }
+    }
+
+    let today = Local::now().date_naive();
+    let current_streak = match (streak, previous_day) {
+        (Some((_, length)), Some(last_day))
+            if last_day == today || last_day.succ_opt() == Some(today) =>
+        {
+            length
+        }
+        _ => 0,
+    };
+
+    (longest_streak, current_streak)
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Local> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .unwrap_or_else(|| Local.from_utc_datetime(&midnight))
+}
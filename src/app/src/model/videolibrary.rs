@@ -3,6 +3,9 @@ use std::path;
 
 use musiqlibrary::video;
 
+use musiqcore::datastore::jsonbacked::movietracker;
+use musiqcore::model::search;
+
 use super::{common, sorts};
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -27,11 +30,13 @@ pub struct VideoLibrary {
     // TODO either use this or drop it
     #[allow(unused)]
     pub movie_id_to_path: BTreeMap<video::MovieID, MovieRelPath>,
+    /// Files in the movie directory which could not be loaded
+    pub scan_errors: Vec<musiqlibrary::ScanError>,
 }
 
 impl VideoLibrary {
     pub fn new<P: AsRef<path::Path>>(movie_path: P) -> Self {
-        let movies = video::find_movies_in_dir(movie_path);
+        let (movies, scan_errors) = video::scan_movies_in_dir(movie_path);
 
         let mut movie_btree = BTreeMap::new();
         let mut movie_id_to_path = BTreeMap::new();
@@ -46,6 +51,7 @@ impl VideoLibrary {
         VideoLibrary {
             movies: movie_btree,
             movie_id_to_path,
+            scan_errors,
         }
     }
 
@@ -63,6 +69,7 @@ impl VideoLibrary {
         VideoLibrary {
             movies: movie_btree,
             movie_id_to_path,
+            scan_errors: Vec::new(),
         }
     }
 }
@@ -74,18 +81,14 @@ pub struct VideoLibraryState {
 
     pub art: MovieArt,
 
+    pub tracker: movietracker::MovieTracker,
+
     pub movie_sorts: sorts::MovieSorts,
 }
 
 impl VideoLibraryState {
-    pub fn new(movies: VideoLibrary, art: MovieArt) -> Self {
-        let movie_sorts = sorts::MovieSorts::new(
-            &movies
-                .movies
-                .values()
-                .cloned()
-                .collect::<Vec<video::MovieMetadata>>(),
-        );
+    pub fn new(movies: VideoLibrary, art: MovieArt, tracker: movietracker::MovieTracker) -> Self {
+        let movie_sorts = VideoLibraryState::compute_sorts(&movies, &tracker);
 
         let mut series_info = BTreeMap::new();
 
@@ -108,10 +111,31 @@ impl VideoLibraryState {
             movies,
             series: series_info,
             art,
+            tracker,
             movie_sorts,
         }
     }
 
+    fn compute_sorts(
+        movies: &VideoLibrary,
+        tracker: &movietracker::MovieTracker,
+    ) -> sorts::MovieSorts {
+        sorts::MovieSorts::new(
+            &movies
+                .movies
+                .values()
+                .cloned()
+                .collect::<Vec<video::MovieMetadata>>(),
+            tracker,
+        )
+    }
+
+    /// Record watching a movie, re-sorting so the watch based sorts pick it up
+    pub fn mark_movie_viewed_now(&mut self, movie: &video::MovieMetadata) {
+        self.tracker.mark_movie_viewed_now(movie.get_id());
+        self.movie_sorts = VideoLibraryState::compute_sorts(&self.movies, &self.tracker);
+    }
+
     pub fn get_movie(&self, title: &MovieRelPath) -> video::MovieMetadata {
         self.movies.movies.get(title).unwrap().clone()
     }
@@ -120,16 +144,18 @@ impl VideoLibraryState {
         self.art.get_movie_cover(size, title)
     }
 
-    pub fn search_movies(&self, query: String) -> common::MovieSearchResults {
+    pub fn search_movies(&self, query: &search::SearchQuery) -> common::MovieSearchResults {
         let mut titles = Vec::new();
 
         for movie in self.movies.movies.values() {
-            if movie.title.to_lowercase().contains(&query.to_lowercase()) {
-                titles.push(movie.clone());
+            if let Some(score) = query.score(&movie.title) {
+                titles.push((score, movie.title.clone(), movie.clone()));
             }
         }
 
-        common::MovieSearchResults { titles }
+        common::MovieSearchResults {
+            titles: search::ranked(titles),
+        }
     }
 
     pub fn get_series(&self) -> &BTreeMap<String, Vec<(u32, video::MovieID)>> {
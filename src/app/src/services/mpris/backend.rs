@@ -77,6 +77,14 @@ fn handle_mpris_from_gui(
     let next_callback = mpris_to_gui_callback.clone();
     player.connect_next(move || handle_next(&next_callback));
 
+    let shuffle_callback = mpris_to_gui_callback.clone();
+    player.connect_shuffle(move |shuffle| handle_shuffle(&shuffle_callback, shuffle));
+
+    let loop_status_callback = mpris_to_gui_callback.clone();
+    player.connect_loop_status(move |loop_status| {
+        handle_loop_status(&loop_status_callback, loop_status)
+    });
+
     println!("MPRIS:\tstarting to listen...");
 
     let forever_loop_context = glib::MainContext::default();
@@ -109,6 +117,20 @@ fn handle_mpris_from_gui(
                     player.set_playback_status(mpris_player::PlaybackStatus::Paused);
                     glib::Continue(true)
                 }
+                shared::MprisMessage::SetLoopStatus(repeat_mode) => {
+                    player.set_loop_status(match repeat_mode {
+                        shared::RepeatMode::Off => mpris_player::LoopStatus::None,
+                        shared::RepeatMode::One => mpris_player::LoopStatus::Track,
+                        shared::RepeatMode::All | shared::RepeatMode::Album => {
+                            mpris_player::LoopStatus::Playlist
+                        }
+                    });
+                    glib::Continue(true)
+                }
+                shared::MprisMessage::SetShuffle(shuffle) => {
+                    player.set_shuffle(shuffle);
+                    glib::Continue(true)
+                }
                 shared::MprisMessage::Close => {
                     player.quit().unwrap();
                     glib::Continue(false)
@@ -160,3 +182,28 @@ fn handle_next(mpris_to_gui_callback: &mpsc::Sender<shared::MprisCallbackMessage
         .send(shared::MprisCallbackMessage::Next)
         .unwrap()
 }
+
+fn handle_loop_status(
+    mpris_to_gui_callback: &mpsc::Sender<shared::MprisCallbackMessage>,
+    loop_status: mpris_player::LoopStatus,
+) {
+    println!("MPRIS:\tsending loop status to gui...");
+    let repeat_mode = match loop_status {
+        mpris_player::LoopStatus::None => shared::RepeatMode::Off,
+        mpris_player::LoopStatus::Track => shared::RepeatMode::One,
+        mpris_player::LoopStatus::Playlist => shared::RepeatMode::All,
+    };
+    mpris_to_gui_callback
+        .send(shared::MprisCallbackMessage::SetLoopStatus(repeat_mode))
+        .unwrap()
+}
+
+fn handle_shuffle(
+    mpris_to_gui_callback: &mpsc::Sender<shared::MprisCallbackMessage>,
+    shuffle: bool,
+) {
+    println!("MPRIS:\tsending shuffle to gui...");
+    mpris_to_gui_callback
+        .send(shared::MprisCallbackMessage::SetShuffle(shuffle))
+        .unwrap()
+}
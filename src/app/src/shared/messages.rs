@@ -1,6 +1,7 @@
 use std::path;
 
 use crate::model;
+use crate::util::shuffle;
 
 use super::state;
 
@@ -25,6 +26,15 @@ pub enum PlaybackRequest {
     AppendSongs(Vec<model::AugmentedTrack>),
 
     RemoveTrackFromPlayQueue(HistoryOrQueue, usize),
+    MoveTrackInPlayQueue(usize, model::Direction),
+    PlayTrackNext(HistoryOrQueue, usize),
+    ClearPlayQueue,
+    DedupePlayQueue,
+    ShufflePlayQueue(shuffle::ShuffleMode),
+
+    SetRepeatMode(state::RepeatMode),
+    CycleRepeatMode,
+    SetShuffle(bool),
 
     SetVolume(f32),
     Close,
@@ -78,6 +88,8 @@ pub enum MprisMessage {
     SetPlaying,
     SetPaused,
     SetStopped,
+    SetLoopStatus(state::RepeatMode),
+    SetShuffle(bool),
     Close,
 }
 
@@ -88,6 +100,8 @@ pub enum MprisCallbackMessage {
     Pause,
     Prev,
     Next,
+    SetLoopStatus(state::RepeatMode),
+    SetShuffle(bool),
 }
 
 #[derive(Debug, Clone)]
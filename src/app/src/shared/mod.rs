@@ -4,4 +4,6 @@ mod state;
 
 pub use client::{Callback, Client};
 pub use messages::*;
-pub use state::{CurrentPlayback, PlayQueueAction, PlayQueueEntry, PlayQueueInfo, PlayQueueTrack};
+pub use state::{
+    CurrentPlayback, PlayQueueAction, PlayQueueEntry, PlayQueueInfo, PlayQueueTrack, RepeatMode,
+};
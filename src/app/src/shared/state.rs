@@ -4,6 +4,8 @@ use crate::model;
 #[derive(Debug, Clone)]
 pub struct PlayQueueInfo {
     pub playing: bool,
+    pub repeat_mode: RepeatMode,
+    pub shuffle: bool,
     pub play_history: Vec<PlayQueueEntry>,
     pub current_playback: Option<CurrentPlayback>,
     pub play_queue: Vec<PlayQueueEntry>,
@@ -13,6 +15,8 @@ impl PlayQueueInfo {
     pub fn new() -> Self {
         PlayQueueInfo {
             playing: false,
+            repeat_mode: RepeatMode::Off,
+            shuffle: false,
             play_history: Vec::new(),
             current_playback: None,
             play_queue: Vec::new(),
@@ -20,6 +24,36 @@ impl PlayQueueInfo {
     }
 }
 
+/// What to do once the current track naturally finishes
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RepeatMode {
+    Off,
+    One,
+    All,
+    Album,
+}
+
+impl RepeatMode {
+    pub fn display_text(&self) -> String {
+        match self {
+            RepeatMode::Off => "Off",
+            RepeatMode::One => "One",
+            RepeatMode::All => "All",
+            RepeatMode::Album => "Album",
+        }
+        .to_string()
+    }
+
+    pub fn cycle(&self) -> Self {
+        match self {
+            RepeatMode::Off => RepeatMode::All,
+            RepeatMode::All => RepeatMode::Album,
+            RepeatMode::Album => RepeatMode::One,
+            RepeatMode::One => RepeatMode::Off,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum PlayQueueEntry {
     Track(PlayQueueTrack),
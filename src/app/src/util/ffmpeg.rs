@@ -0,0 +1,65 @@
+use std::fmt;
+use std::io;
+use std::path;
+use std::process;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum Error {
+    /// ffmpeg could not be run at all (usually because it is not installed)
+    Spawn(io::Error),
+    /// ffmpeg ran but could not grab a frame, with what it printed
+    Failed(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Spawn(e) => write!(f, "could not run ffmpeg: {}", e),
+            Error::Failed(e) => write!(f, "ffmpeg could not grab a frame: {}", e),
+        }
+    }
+}
+
+/// Whether there is an ffmpeg to run
+pub fn is_available() -> bool {
+    process::Command::new("ffmpeg")
+        .arg("-version")
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Save the video frame at `at` as an image, in the format `output`'s extension names
+pub fn grab_frame<P: AsRef<path::Path>, O: AsRef<path::Path>>(
+    video: P,
+    at: Duration,
+    output: O,
+) -> Result<(), Error> {
+    let result = process::Command::new("ffmpeg")
+        .arg("-nostdin")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-y")
+        // seeking before the input is fast, it jumps to the nearest keyframe
+        .arg("-ss")
+        .arg(format!("{:.3}", at.as_secs_f64()))
+        .arg("-i")
+        .arg(video.as_ref().as_os_str())
+        .arg("-frames:v")
+        .arg("1")
+        .arg(output.as_ref().as_os_str())
+        .stdin(process::Stdio::null())
+        .output()
+        .map_err(Error::Spawn)?;
+
+    match result.status.success() && output.as_ref().exists() {
+        true => Ok(()),
+        false => Err(Error::Failed(
+            String::from_utf8_lossy(&result.stderr).trim().to_string(),
+        )),
+    }
+}
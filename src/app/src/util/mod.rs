@@ -1,2 +1,4 @@
+pub mod ffmpeg;
 pub mod logging;
+pub mod mpv;
 pub mod shuffle;
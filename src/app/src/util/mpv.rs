@@ -0,0 +1,368 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+/// How often to ask mpv where playback is
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long mpv gets to open its IPC socket after being launched
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait on a reply before checking whether mpv is still running
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// mpv closed the socket, which it does when it exits
+    Closed,
+    /// mpv did not reply in time
+    Timeout,
+    /// mpv answered a command with something other than "success"
+    Command(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "mpv socket error: {}", e),
+            Error::Json(e) => write!(f, "unreadable mpv reply: {}", e),
+            Error::Closed => write!(f, "mpv closed its socket"),
+            Error::Timeout => write!(f, "mpv did not reply in time"),
+            Error::Command(e) => write!(f, "mpv command failed: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::BrokenPipe | io::ErrorKind::ConnectionReset => Error::Closed,
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Error::Timeout,
+            _ => Error::Io(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+/// How far playback got before mpv exited, when it could be read
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackProgress {
+    pub position_seconds: Option<f64>,
+    pub duration_seconds: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct IpcReply {
+    request_id: Option<u64>,
+    error: Option<String>,
+    data: Option<serde_json::Value>,
+}
+
+/// How mpv should start playing a file
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub start_seconds: Option<f64>,
+    pub audio_language: Option<String>,
+    pub subtitle_language: Option<String>,
+    /// Subtitles found next to the file, which replace the ones mpv would find on its own
+    pub subtitle_files: Vec<path::PathBuf>,
+}
+
+/// Talks to mpv over its JSON IPC socket (`--input-ipc-server`)
+pub struct IpcClient {
+    writer: UnixStream,
+    reader: BufReader<UnixStream>,
+    /// What has been read of a line that a timeout cut short
+    partial_line: Vec<u8>,
+    next_request_id: u64,
+}
+
+impl IpcClient {
+    pub fn connect<P: AsRef<path::Path>>(socket_path: P) -> Result<Self, Error> {
+        IpcClient::connect_with_timeout(socket_path, READ_TIMEOUT)
+    }
+
+    pub fn connect_with_timeout<P: AsRef<path::Path>>(
+        socket_path: P,
+        read_timeout: Duration,
+    ) -> Result<Self, Error> {
+        let writer = UnixStream::connect(socket_path)?;
+        writer.set_read_timeout(Some(read_timeout))?;
+        writer.set_write_timeout(Some(read_timeout))?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(IpcClient {
+            writer,
+            reader,
+            partial_line: Vec::new(),
+            next_request_id: 1,
+        })
+    }
+
+    /// Read a numeric property, which mpv leaves unavailable (e.g. before a file is loaded)
+    pub fn get_property_f64(&mut self, name: &str) -> Result<Option<f64>, Error> {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+
+        let request = serde_json::json!({
+            "command": ["get_property", name],
+            "request_id": request_id,
+        });
+        self.writer.write_all(format!("{}\n", request).as_bytes())?;
+
+        loop {
+            let line = self.read_line()?;
+
+            // events (which have no request_id) are interleaved with replies,
+            // as are late replies to requests that timed out
+            let reply: IpcReply = serde_json::from_slice(&line)?;
+            if reply.request_id != Some(request_id) {
+                continue;
+            }
+
+            return match reply.error.as_deref() {
+                Some("success") => Ok(reply.data.and_then(|data| data.as_f64())),
+                Some("property unavailable") => Ok(None),
+                Some(other) => Err(Error::Command(other.to_string())),
+                None => Err(Error::Command("no error field in reply".to_string())),
+            };
+        }
+    }
+
+    /// The next whole line, keeping what was read so far when the read times out
+    fn read_line(&mut self) -> Result<Vec<u8>, Error> {
+        let read = self.reader.read_until(b'\n', &mut self.partial_line)?;
+        if read == 0 || self.partial_line.last() != Some(&b'\n') {
+            return Err(Error::Closed);
+        }
+        Ok(std::mem::take(&mut self.partial_line))
+    }
+}
+
+/// Launch mpv on a file with an IPC socket to follow it by
+pub fn launch<P: AsRef<path::Path>, S: AsRef<path::Path>>(
+    file: P,
+    socket_path: S,
+    options: &LaunchOptions,
+) -> io::Result<process::Child> {
+    let mut command = process::Command::new("mpv");
+    command.arg(format!(
+        "--input-ipc-server={}",
+        socket_path.as_ref().to_string_lossy()
+    ));
+    if let Some(start) = options.start_seconds {
+        command.arg(format!("--start={:.0}", start));
+    }
+    if let Some(ref audio_language) = options.audio_language {
+        command.arg(format!("--alang={}", audio_language));
+    }
+    if let Some(ref subtitle_language) = options.subtitle_language {
+        command.arg(format!("--slang={}", subtitle_language));
+    }
+    if !options.subtitle_files.is_empty() {
+        command.arg("--sub-auto=no");
+    }
+    for subtitle_file in options.subtitle_files.iter() {
+        let mut sub_file_arg = std::ffi::OsString::from("--sub-file=");
+        sub_file_arg.push(subtitle_file.as_os_str());
+        command.arg(sub_file_arg);
+    }
+    command.arg(file.as_ref().as_os_str());
+    command.spawn()
+}
+
+/// Poll a launched mpv until it exits, returning the last position and duration it reported
+pub fn follow_until_exit<P: AsRef<path::Path>>(
+    child: &mut process::Child,
+    socket_path: P,
+) -> PlaybackProgress {
+    let mut progress = PlaybackProgress::default();
+
+    let connect_started = Instant::now();
+    let mut client = loop {
+        match IpcClient::connect(&socket_path) {
+            Ok(client) => break Some(client),
+            Err(_) => {
+                let exited = !matches!(child.try_wait(), Ok(None));
+                if exited || connect_started.elapsed() > CONNECT_TIMEOUT {
+                    break None;
+                }
+                thread::sleep(Duration::from_millis(200));
+            }
+        }
+    };
+
+    match client {
+        Some(ref mut client) => loop {
+            match poll_progress(client) {
+                Ok((position, duration)) => {
+                    if position.is_some() {
+                        progress.position_seconds = position;
+                    }
+                    if duration.is_some() {
+                        progress.duration_seconds = duration;
+                    }
+                }
+                Err(Error::Closed) => break,
+                // a hung mpv is waited out, as long as it is still running
+                Err(Error::Timeout) => {
+                    if !matches!(child.try_wait(), Ok(None)) {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    println!("error following mpv: {}", e);
+                    break;
+                }
+            };
+            thread::sleep(POLL_INTERVAL);
+        },
+        None => println!("could not connect to mpv, its progress won't be saved"),
+    };
+
+    let _ = child.wait();
+
+    progress
+}
+
+fn poll_progress(client: &mut IpcClient) -> Result<(Option<f64>, Option<f64>), Error> {
+    let position = client.get_property_f64("time-pos")?;
+    let duration = client.get_property_f64("duration")?;
+    Ok((position, duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::net::UnixListener;
+
+    use super::*;
+
+    /// Listen where a test's mpv socket would be, in a directory of its own
+    fn listen(test_name: &str) -> (path::PathBuf, UnixListener) {
+        let dir =
+            std::env::temp_dir().join(format!("musiq-mpv-test-{}-{}", process::id(), test_name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let socket_path = dir.join("mpv.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        (socket_path, listener)
+    }
+
+    /// Accept one connection and answer each request line with what `answer` writes back
+    fn serve<F: Fn(u64) -> String + Send + 'static>(
+        listener: UnixListener,
+        requests: usize,
+        answer: F,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            for _ in 0..requests {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+                let request_id = request["request_id"].as_u64().unwrap();
+                writer.write_all(answer(request_id).as_bytes()).unwrap();
+            }
+        })
+    }
+
+    #[test]
+    fn matches_replies_to_requests_between_events() {
+        let (socket_path, listener) = listen("replies");
+        let server = serve(listener, 2, |request_id| {
+            format!(
+                "{{\"event\":\"playback-restart\"}}\n\
+                 {{\"request_id\":{},\"error\":\"success\",\"data\":1.5}}\n\
+                 {{\"event\":\"seek\"}}\n\
+                 {{\"request_id\":{},\"error\":\"success\",\"data\":{}}}\n",
+                request_id + 100,
+                request_id,
+                request_id as f64 * 10.0
+            )
+        });
+
+        let mut client = IpcClient::connect(&socket_path).unwrap();
+        assert_eq!(client.get_property_f64("time-pos").unwrap(), Some(10.0));
+        assert_eq!(client.get_property_f64("duration").unwrap(), Some(20.0));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn unavailable_properties_are_none() {
+        let (socket_path, listener) = listen("unavailable");
+        let server = serve(listener, 1, |request_id| {
+            format!(
+                "{{\"request_id\":{},\"error\":\"property unavailable\"}}\n",
+                request_id
+            )
+        });
+
+        let mut client = IpcClient::connect(&socket_path).unwrap();
+        assert_eq!(client.get_property_f64("time-pos").unwrap(), None);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn end_of_stream_is_closed() {
+        let (socket_path, listener) = listen("closed");
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(b"{\"event\":\"shutdown\"}\n").unwrap();
+        });
+
+        let mut client = IpcClient::connect(&socket_path).unwrap();
+        server.join().unwrap();
+        assert!(matches!(
+            client.get_property_f64("time-pos"),
+            Err(Error::Closed)
+        ));
+    }
+
+    #[test]
+    fn silence_times_out_and_a_late_reply_is_skipped() {
+        let (socket_path, listener) = listen("timeout");
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            // half of the first reply, then the rest of it after the client gave up on it
+            writer.write_all(b"{\"request_id\":1,").unwrap();
+            thread::sleep(Duration::from_millis(300));
+            writer
+                .write_all(b"\"error\":\"success\",\"data\":1.0}\n")
+                .unwrap();
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            writer
+                .write_all(b"{\"request_id\":2,\"error\":\"success\",\"data\":2.0}\n")
+                .unwrap();
+        });
+
+        let mut client =
+            IpcClient::connect_with_timeout(&socket_path, Duration::from_millis(100)).unwrap();
+        assert!(matches!(
+            client.get_property_f64("time-pos"),
+            Err(Error::Timeout)
+        ));
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(client.get_property_f64("time-pos").unwrap(), Some(2.0));
+        server.join().unwrap();
+    }
+}
@@ -1,7 +1,146 @@
-use rand::{self, seq::SliceRandom};
+use std::collections::BTreeMap;
+
+use chrono::Local;
+use rand::{self, seq::SliceRandom, Rng};
+
+use crate::model;
+use crate::shared;
+
+pub use musiqcore::model::shared::ShuffleMode;
+
+/// How many days since a track was last played before it stops
+/// counting as any more "stale" for the weighted shuffle
+const MAX_STALE_DAYS: i64 = 365;
 
 pub fn shuffle<T>(mut list: Vec<T>) -> Vec<T> {
     let mut rng = rand::thread_rng();
     list.shuffle(&mut rng);
     list
 }
+
+pub fn shuffle_tracks(
+    mode: &ShuffleMode,
+    tracks: Vec<model::AugmentedTrack>,
+) -> Vec<model::AugmentedTrack> {
+    match mode {
+        ShuffleMode::Uniform => shuffle(tracks),
+        ShuffleMode::ArtistSpread => artist_spread_shuffle(tracks),
+        ShuffleMode::Album => album_shuffle(tracks),
+        ShuffleMode::Weighted => weighted_shuffle(tracks),
+    }
+}
+
+/// Shuffle the tracks in a play queue, leaving any queued actions
+/// (like pauses) at the position they were queued at
+pub fn shuffle_play_queue(
+    mode: &ShuffleMode,
+    play_queue: Vec<shared::PlayQueueEntry>,
+) -> Vec<shared::PlayQueueEntry> {
+    let mut tracks = Vec::new();
+    let mut actions = Vec::new();
+    for (index, entry) in play_queue.into_iter().enumerate() {
+        match entry {
+            shared::PlayQueueEntry::Track(play_queue_track) => tracks.push(play_queue_track.track),
+            shared::PlayQueueEntry::Action(action) => actions.push((index, action)),
+        }
+    }
+
+    let mut shuffled: Vec<shared::PlayQueueEntry> = shuffle_tracks(mode, tracks)
+        .into_iter()
+        .map(|track| shared::PlayQueueEntry::Track(shared::PlayQueueTrack { track }))
+        .collect();
+    for (index, action) in actions.into_iter() {
+        shuffled.insert(index, shared::PlayQueueEntry::Action(action));
+    }
+    shuffled
+}
+
+/// Avoid playing the same artist back-to-back when possible, by always
+/// drawing next from the artist with the most tracks left (other than
+/// the artist that was just drawn)
+fn artist_spread_shuffle(tracks: Vec<model::AugmentedTrack>) -> Vec<model::AugmentedTrack> {
+    let mut by_artist: BTreeMap<musiqlibrary::ID, Vec<model::AugmentedTrack>> = BTreeMap::new();
+    for track in shuffle(tracks).into_iter() {
+        by_artist
+            .entry(track.metadata.track_artist_id)
+            .or_default()
+            .push(track);
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut last_artist = None;
+    let mut ret = Vec::new();
+    loop {
+        let mut candidates: Vec<musiqlibrary::ID> = by_artist
+            .iter()
+            .filter(|(artist_id, artist_tracks)| {
+                !artist_tracks.is_empty() && Some(**artist_id) != last_artist
+            })
+            .map(|(artist_id, _)| *artist_id)
+            .collect();
+        if candidates.is_empty() {
+            match last_artist {
+                Some(artist_id) if !by_artist.get(&artist_id).unwrap().is_empty() => {
+                    candidates.push(artist_id)
+                }
+                _ => break,
+            }
+        }
+        candidates.shuffle(&mut rng);
+        let chosen = *candidates
+            .iter()
+            .max_by_key(|artist_id| by_artist.get(artist_id).unwrap().len())
+            .unwrap();
+        ret.push(by_artist.get_mut(&chosen).unwrap().pop().unwrap());
+        last_artist = Some(chosen);
+    }
+    ret
+}
+
+/// Shuffle the order of the albums, but keep each album's tracks
+/// in the order they were given
+fn album_shuffle(tracks: Vec<model::AugmentedTrack>) -> Vec<model::AugmentedTrack> {
+    let mut album_order = Vec::new();
+    let mut by_album: BTreeMap<musiqlibrary::AlbumUniqueIdentifier, Vec<model::AugmentedTrack>> =
+        BTreeMap::new();
+    for track in tracks.into_iter() {
+        let album_key = musiqlibrary::AlbumUniqueIdentifier {
+            artist_id: track.metadata.album_artist_id,
+            album_id: track.metadata.album_id,
+        };
+        if !by_album.contains_key(&album_key) {
+            album_order.push(album_key.clone());
+        }
+        by_album.entry(album_key).or_default().push(track);
+    }
+
+    let mut ret = Vec::new();
+    for album_key in shuffle(album_order).into_iter() {
+        ret.append(&mut by_album.remove(&album_key).unwrap());
+    }
+    ret
+}
+
+/// Weighted random order (Efraimidis-Spirakis) favoring tracks with few plays
+/// and tracks that have not been played in a long time
+fn weighted_shuffle(tracks: Vec<model::AugmentedTrack>) -> Vec<model::AugmentedTrack> {
+    let now = Local::now();
+    let mut rng = rand::thread_rng();
+
+    let mut keyed: Vec<(f64, model::AugmentedTrack)> = tracks
+        .into_iter()
+        .map(|track| {
+            let stale_days = match track.augmented.last_played {
+                Some(last_played) => (now - last_played).num_days().clamp(0, MAX_STALE_DAYS),
+                None => MAX_STALE_DAYS,
+            };
+            let weight =
+                (1.0 + stale_days as f64) / (1.0 + track.augmented.play_count as f64).sqrt();
+            let key = rng.gen::<f64>().powf(1.0 / weight);
+            (key, track)
+        })
+        .collect();
+
+    keyed.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap());
+    keyed.into_iter().map(|(_key, track)| track).collect()
+}
@@ -3,6 +3,7 @@ use std::fs;
 use std::io;
 use std::path::PathBuf;
 
+use chrono::Local;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
@@ -57,6 +58,9 @@ pub fn bootstrap_raw_data<T: Default + DeserializeOwned + Serialize, S: Into<Str
 
     let raw = match maybe_raw {
         None => {
+            if localfs::check_exists(&json_db_path) {
+                set_aside_unreadable_file(&json_db_path);
+            }
             let empty = T::default();
             serde_json::to_writer(
                 io::BufWriter::new(fs::File::create(json_db_path.clone()).unwrap()),
@@ -71,6 +75,27 @@ pub fn bootstrap_raw_data<T: Default + DeserializeOwned + Serialize, S: Into<Str
     (raw, json_db_path)
 }
 
+/// Move a file that could not be read out of the way, instead of writing over what may be the
+/// only copy of its data
+fn set_aside_unreadable_file(json_db_path: &PathBuf) {
+    let set_aside_path = json_db_path.with_extension(format!(
+        "json.unreadable-{}",
+        Local::now().format("%Y%m%d%H%M%S")
+    ));
+    match fs::rename(json_db_path, &set_aside_path) {
+        Ok(_) => println!(
+            "moved unreadable file {:?} to {:?}",
+            json_db_path.display(),
+            set_aside_path.display()
+        ),
+        Err(e) => panic!(
+            "could not move unreadable file {:?} out of the way: {:?}",
+            json_db_path.display(),
+            e
+        ),
+    }
+}
+
 pub struct FileAllower {
     allowed_set: Option<BTreeSet<PathBuf>>,
 }
@@ -1,3 +1,5 @@
+use chrono::{DateTime, Local};
+
 use crate::datastore;
 use crate::datastore::jsonbacked::tracker;
 
@@ -5,4 +7,32 @@ impl datastore::traits::LiveReadOnlyTrackCountReporter for tracker::ReadOnlyTrac
     fn get_live_track_count(&self, id: &musiqlibrary::TrackUniqueIdentifier) -> usize {
         self.get_track_count(id)
     }
+
+    fn get_live_track_last_played(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Option<DateTime<Local>> {
+        self.get_track_history(id)
+            .and_then(|history| history.iter().max().cloned())
+    }
+
+    fn get_live_track_history(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Vec<DateTime<Local>> {
+        self.get_track_history(id).cloned().unwrap_or(Vec::new())
+    }
+}
+
+impl datastore::traits::LiveHistoryReadDS for tracker::ReadOnlyTracker {
+    fn get_track_history(
+        &self,
+        track_identifier: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Option<&Vec<DateTime<Local>>> {
+        tracker::ReadOnlyTracker::get_track_history(self, track_identifier)
+    }
+
+    fn get_track_count(&self, track_identifier: &musiqlibrary::TrackUniqueIdentifier) -> usize {
+        tracker::ReadOnlyTracker::get_track_count(self, track_identifier)
+    }
 }
@@ -1,4 +1,6 @@
 pub mod common;
 pub mod impls;
+pub mod movietracker;
+pub mod resumetracker;
 pub mod showtracker;
 pub mod tracker;
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+use std::{fs, io, path};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use musiqlibrary::video;
+
+use crate::datastore::jsonbacked::common;
+
+pub struct MovieTracker {
+    movie_tracker_json_file_path: path::PathBuf,
+    tracked_movie_views: BTreeMap<video::MovieID, Vec<DateTime<Local>>>,
+}
+
+impl MovieTracker {
+    pub fn new<P: AsRef<path::Path>>(app_data_path: P) -> Self {
+        let app_data_path = app_data_path.as_ref().to_path_buf();
+        let (tracked_movie_views_raw, movie_tracker_json_file_path): (RawMovieTrackedPayload, _) =
+            common::bootstrap_raw_data(
+                &app_data_path,
+                vec!["data", "movietracker", "timestamps.json"],
+            );
+
+        MovieTracker {
+            movie_tracker_json_file_path,
+            tracked_movie_views: tracked_movie_views_raw.to_btree_map(),
+        }
+    }
+
+    /// Every view of a movie, oldest first
+    pub fn get_views(&self, movie_id: &video::MovieID) -> &[DateTime<Local>] {
+        match self.tracked_movie_views.get(movie_id) {
+            Some(views) => views.as_slice(),
+            None => &[],
+        }
+    }
+
+    pub fn get_view_count(&self, movie_id: &video::MovieID) -> usize {
+        self.get_views(movie_id).len()
+    }
+
+    pub fn get_last_viewed(&self, movie_id: &video::MovieID) -> Option<DateTime<Local>> {
+        self.get_views(movie_id).iter().max().cloned()
+    }
+
+    pub fn mark_movie_viewed_now(&mut self, movie_id: video::MovieID) {
+        self.mark_movie_viewed_at(movie_id, Local::now())
+    }
+
+    pub fn mark_movie_viewed_at(&mut self, movie_id: video::MovieID, view_time: DateTime<Local>) {
+        let views = self.tracked_movie_views.entry(movie_id).or_default();
+        views.push(view_time);
+        views.sort();
+
+        let raw_tracker = RawMovieTrackedPayload::from_btree_map(&self.tracked_movie_views);
+
+        serde_json::to_writer(
+            io::BufWriter::new(fs::File::create(&self.movie_tracker_json_file_path).unwrap()),
+            &raw_tracker,
+        )
+        .unwrap();
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct RawMovieTrackedPayload {
+    pub views: Vec<(video::MovieID, Vec<DateTime<Local>>)>,
+}
+
+impl RawMovieTrackedPayload {
+    pub fn to_btree_map(self) -> BTreeMap<video::MovieID, Vec<DateTime<Local>>> {
+        self.views.into_iter().collect()
+    }
+
+    pub fn from_btree_map(views: &BTreeMap<video::MovieID, Vec<DateTime<Local>>>) -> Self {
+        RawMovieTrackedPayload {
+            views: views.iter().map(|(x, y)| (x.clone(), y.clone())).collect(),
+        }
+    }
+}
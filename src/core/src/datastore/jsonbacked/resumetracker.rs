@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::{fs, io, path};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::datastore::jsonbacked::common;
+
+/// How far through a file (as a fraction of its duration) counts as having watched it
+pub const COMPLETION_THRESHOLD: f64 = 0.9;
+
+/// Where playback of a file was left off
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResumePosition {
+    pub position_seconds: f64,
+    pub duration_seconds: f64,
+    pub updated: DateTime<Local>,
+}
+
+impl ResumePosition {
+    pub fn fraction_watched(&self) -> f64 {
+        if self.duration_seconds > 0.0 {
+            self.position_seconds / self.duration_seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+pub struct ResumeTracker {
+    resume_tracker_json_file_path: path::PathBuf,
+    positions: BTreeMap<path::PathBuf, ResumePosition>,
+}
+
+impl ResumeTracker {
+    pub fn new<P: AsRef<path::Path>>(app_data_path: P) -> Self {
+        let app_data_path = app_data_path.as_ref().to_path_buf();
+        let (raw_positions, resume_tracker_json_file_path): (RawResumePayload, _) =
+            common::bootstrap_raw_data(&app_data_path, vec!["data", "resume", "positions.json"]);
+
+        ResumeTracker {
+            resume_tracker_json_file_path,
+            positions: raw_positions.positions.into_iter().collect(),
+        }
+    }
+
+    pub fn get_resume_position<P: AsRef<path::Path>>(&self, file: P) -> Option<&ResumePosition> {
+        self.positions.get(file.as_ref())
+    }
+
+    /// Record how far playback got, returning whether that counts as having watched the file,
+    /// in which case the file will start from the beginning next time
+    pub fn record_progress<P: AsRef<path::Path>>(
+        &mut self,
+        file: P,
+        position_seconds: f64,
+        duration_seconds: f64,
+    ) -> bool {
+        let position = ResumePosition {
+            position_seconds,
+            duration_seconds,
+            updated: Local::now(),
+        };
+
+        let completed = position.fraction_watched() >= COMPLETION_THRESHOLD;
+        if completed {
+            let _ = self.positions.remove(file.as_ref());
+        } else {
+            let _ = self.positions.insert(file.as_ref().to_path_buf(), position);
+        }
+
+        self.save();
+
+        completed
+    }
+
+    fn save(&self) {
+        let raw_positions = RawResumePayload {
+            positions: self
+                .positions
+                .iter()
+                .map(|(x, y)| (x.clone(), y.clone()))
+                .collect(),
+        };
+
+        serde_json::to_writer(
+            io::BufWriter::new(fs::File::create(&self.resume_tracker_json_file_path).unwrap()),
+            &raw_positions,
+        )
+        .unwrap();
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct RawResumePayload {
+    pub positions: Vec<(path::PathBuf, ResumePosition)>,
+}
@@ -1,11 +1,26 @@
 use std::collections::BTreeMap;
-use std::{cmp, fs, io, path};
+use std::{cmp, fs, io, path, time};
 
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
 use crate::datastore::jsonbacked::common;
 
+/// How much of a set of episodes (a season or a show) has been watched
+#[derive(Debug, Clone, Default)]
+pub struct WatchProgress {
+    pub watched: usize,
+    pub total: usize,
+    /// The runtime of the episodes not watched yet
+    pub remaining: time::Duration,
+}
+
+impl WatchProgress {
+    pub fn is_complete(&self) -> bool {
+        self.total > 0 && self.watched == self.total
+    }
+}
+
 pub struct ShowTracker {
     show_tracker_json_file_path: path::PathBuf,
     tracked_show_views: BTreeMap<musiqlibrary::shows::ShowEpisodeKey, Vec<DateTime<Local>>>,
@@ -22,6 +37,30 @@ impl ShowTracker {
                 vec!["data", "showtracker", "timestamps.json"],
             );
 
+        ShowTracker::from_raw(tracked_show_views_raw, show_tracker_json_file_path)
+    }
+
+    /// Load the tracker without creating or rewriting anything on disk, for readers
+    /// outside the app (which may be writing it at the same time); None if it can't be read
+    pub fn load_read_only<P: AsRef<path::Path>>(app_data_path: P) -> Option<Self> {
+        let show_tracker_json_file_path = app_data_path
+            .as_ref()
+            .join("data")
+            .join("showtracker")
+            .join("timestamps.json");
+        let tracked_show_views_raw: RawShowTrackedPayload =
+            common::maybe_get_existing_raw_data(&show_tracker_json_file_path)?;
+
+        Some(ShowTracker::from_raw(
+            tracked_show_views_raw,
+            show_tracker_json_file_path,
+        ))
+    }
+
+    fn from_raw(
+        tracked_show_views_raw: RawShowTrackedPayload,
+        show_tracker_json_file_path: path::PathBuf,
+    ) -> Self {
         let tracked_show_views = tracked_show_views_raw.to_btree_map();
 
         let cached_most_recently_viewed_shows =
@@ -48,6 +87,31 @@ impl ShowTracker {
         }
     }
 
+    pub fn get_views_for_episode(
+        &self,
+        key: &musiqlibrary::shows::ShowEpisodeKey,
+    ) -> Vec<DateTime<Local>> {
+        match self.tracked_show_views.get(key) {
+            Some(views) => views.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_progress<'a, I: Iterator<Item = &'a musiqlibrary::shows::ShowMetadata>>(
+        &self,
+        episodes: I,
+    ) -> WatchProgress {
+        let mut progress = WatchProgress::default();
+        for episode in episodes {
+            progress.total += 1;
+            match self.get_view_count_for_episode(episode.get_key()) {
+                0 => progress.remaining += episode.duration,
+                _ => progress.watched += 1,
+            }
+        }
+        progress
+    }
+
     pub fn mark_episode_viewed_now(&mut self, key: musiqlibrary::shows::ShowEpisodeKey) {
         self.mark_episode_viewed_at(key, Local::now())
     }
@@ -57,11 +121,72 @@ impl ShowTracker {
         key: musiqlibrary::shows::ShowEpisodeKey,
         view_time: DateTime<Local>,
     ) {
-        self.tracked_show_views
-            .entry(key)
-            .or_insert(Vec::new())
-            .push(view_time);
+        self.mark_episodes_viewed_at(vec![key], view_time)
+    }
+
+    /// Record a view of each episode which hasn't been watched yet, leaving watched ones be
+    pub fn mark_unwatched_episodes_viewed_at(
+        &mut self,
+        keys: Vec<musiqlibrary::shows::ShowEpisodeKey>,
+        view_time: DateTime<Local>,
+    ) {
+        let unwatched = keys
+            .into_iter()
+            .filter(|key| self.get_view_count_for_episode(key.clone()) == 0)
+            .collect();
+        self.mark_episodes_viewed_at(unwatched, view_time)
+    }
+
+    fn mark_episodes_viewed_at(
+        &mut self,
+        keys: Vec<musiqlibrary::shows::ShowEpisodeKey>,
+        view_time: DateTime<Local>,
+    ) {
+        for key in keys.into_iter() {
+            self.tracked_show_views
+                .entry(key)
+                .or_default()
+                .push(view_time);
+        }
+
+        self.save();
+    }
+
+    /// Forget every view of these episodes, so they show up as never watched
+    pub fn clear_episode_views(&mut self, keys: Vec<musiqlibrary::shows::ShowEpisodeKey>) {
+        for key in keys.iter() {
+            self.tracked_show_views.remove(key);
+        }
+
+        self.save();
+    }
+
+    /// Forget a single view, like one recorded by mistake
+    pub fn remove_episode_view(
+        &mut self,
+        key: &musiqlibrary::shows::ShowEpisodeKey,
+        view_time: &DateTime<Local>,
+    ) {
+        let now_unwatched = match self.tracked_show_views.get_mut(key) {
+            Some(views) => {
+                match views.iter().position(|view| view == view_time) {
+                    Some(index) => {
+                        let _removed = views.remove(index);
+                    }
+                    None => println!("no view of {:?} at {}", key, view_time),
+                };
+                views.is_empty()
+            }
+            None => false,
+        };
+        if now_unwatched {
+            self.tracked_show_views.remove(key);
+        }
+
+        self.save();
+    }
 
+    fn save(&mut self) {
         self.cached_most_recently_viewed_shows =
             ShowTracker::compute_cached_info(&self.tracked_show_views);
 
@@ -79,7 +204,11 @@ impl ShowTracker {
     ) -> BTreeMap<musiqlibrary::shows::ShowKey, musiqlibrary::shows::ShowEpisodeKey> {
         let mut cached_most_recently_viewed_shows = BTreeMap::new();
 
-        for (episode_key, _timestamp) in tracked_show_views.iter() {
+        // specials are watched whenever, so they don't move where a show is continued from
+        for (episode_key, _timestamp) in tracked_show_views
+            .iter()
+            .filter(|(episode_key, _)| !episode_key.is_special())
+        {
             let found_episode = cached_most_recently_viewed_shows
                 .entry(episode_key.show.clone())
                 .or_insert(episode_key.clone());
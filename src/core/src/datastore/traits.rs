@@ -10,6 +10,14 @@ pub trait HistoricalTrackCountReporter {
 
 pub trait LiveReadOnlyTrackCountReporter {
     fn get_live_track_count(&self, id: &musiqlibrary::TrackUniqueIdentifier) -> usize;
+    fn get_live_track_last_played(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Option<DateTime<Local>>;
+    fn get_live_track_history(
+        &self,
+        id: &musiqlibrary::TrackUniqueIdentifier,
+    ) -> Vec<DateTime<Local>>;
 }
 
 pub trait LiveHistoryReadDS {
@@ -2,9 +2,10 @@ use std::fs;
 use std::io;
 use std::path;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::model::shared::SinkMode;
+use crate::datastore::localfs;
+use crate::model::shared::{ShuffleMode, SinkMode};
 use crate::model::LoadMode;
 
 #[derive(Debug, Clone)]
@@ -33,6 +34,12 @@ pub struct AppConfigState {
 
     pub allowed_tracker_files: Option<Vec<path::PathBuf>>,
     pub allowed_prehistory_files: Option<Vec<path::PathBuf>>,
+
+    /// Language codes (like "eng" or "en") mpv should pick audio and subtitles by
+    pub preferred_audio_language: Option<String>,
+    pub preferred_subtitle_language: Option<String>,
+
+    pub shuffle_mode: ShuffleMode,
 }
 
 impl AppConfigState {
@@ -76,10 +83,16 @@ pub struct RawAppConfigState {
 
     pub allowed_tracker_files: Option<Vec<path::PathBuf>>,
     pub allowed_prehistory_files: Option<Vec<path::PathBuf>>,
+
+    pub preferred_audio_language: Option<String>,
+    pub preferred_subtitle_language: Option<String>,
+
+    pub shuffle_mode: Option<ShuffleMode>,
 }
 
 impl RawAppConfigState {
     pub fn to_real<P: AsRef<path::Path>>(self, app_data_path: P) -> AppConfigState {
+        let saved_settings = load_saved_settings(&app_data_path.as_ref().to_path_buf());
         AppConfigState {
             library_path: self.library_path,
             movie_path: self.movie_path,
@@ -98,6 +111,12 @@ impl RawAppConfigState {
             scale_factor: self.scale_factor.unwrap_or(1.0),
             allowed_tracker_files: self.allowed_tracker_files,
             allowed_prehistory_files: self.allowed_prehistory_files,
+            preferred_audio_language: self.preferred_audio_language,
+            preferred_subtitle_language: self.preferred_subtitle_language,
+            shuffle_mode: saved_settings
+                .shuffle_mode
+                .or(self.shuffle_mode)
+                .unwrap_or(ShuffleMode::Uniform),
         }
     }
 }
@@ -111,6 +130,50 @@ impl AppConfigState {
 
         raw_config_state.to_real(crate::model::functions::get_default_data_path())
     }
+
+    /// Set the shuffle mode and save it with the rest of the app's data, leaving the config
+    /// file alone
+    pub fn save_shuffle_mode(&mut self, shuffle_mode: ShuffleMode) -> io::Result<()> {
+        self.shuffle_mode = shuffle_mode;
+
+        let settings_path = saved_settings_path(&self.app_data_path);
+        let saved_settings = SavedSettings {
+            shuffle_mode: Some(shuffle_mode),
+        };
+
+        // written next to the settings and moved over them, so a crash never leaves half a file
+        let tmp_path = settings_path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(&saved_settings)?)?;
+        fs::rename(tmp_path, settings_path)
+    }
+}
+
+/// Settings changed from inside the app, which take precedence over the config file
+#[derive(Serialize, Deserialize, Default)]
+struct SavedSettings {
+    shuffle_mode: Option<ShuffleMode>,
+}
+
+fn saved_settings_path(app_data_path: &path::PathBuf) -> path::PathBuf {
+    localfs::build_tree_for_file(app_data_path, vec!["data", "settings", "settings.json"])
+}
+
+fn load_saved_settings(app_data_path: &path::PathBuf) -> SavedSettings {
+    let settings_path = saved_settings_path(app_data_path);
+    match fs::File::open(&settings_path) {
+        Ok(reader) => match serde_json::from_reader(io::BufReader::new(reader)) {
+            Ok(saved_settings) => saved_settings,
+            Err(e) => {
+                println!(
+                    "could not deserialize data from path: {:?} {:?}",
+                    settings_path.display(),
+                    e
+                );
+                SavedSettings::default()
+            }
+        },
+        Err(_) => SavedSettings::default(),
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -253,6 +253,40 @@ impl GameLibraryState {
             None => None,
         }
     }
+
+    /// Games on every console whose name matches, closest first
+    pub fn search(&self, query: &crate::model::search::SearchQuery) -> Vec<GameSearchResult> {
+        let mut scored = Vec::new();
+
+        for console in consoles::GameConsole::all().into_iter() {
+            if let Some((games, _prefix)) = self.get_generic_game_and_prefix(&console) {
+                for game in games.into_iter() {
+                    let name = game.get_name();
+                    if let Some(score) = query.score(&name) {
+                        scored.push((
+                            score,
+                            name.clone(),
+                            GameSearchResult {
+                                console: console.clone(),
+                                name,
+                                path: game.get_rom_path().clone(),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+
+        crate::model::search::ranked(scored)
+    }
+}
+
+/// A game matched by a search, with what it takes to launch it
+#[derive(Debug, Clone)]
+pub struct GameSearchResult {
+    pub console: consoles::GameConsole,
+    pub name: String,
+    pub path: path::PathBuf,
 }
 
 pub struct GameLibrary {
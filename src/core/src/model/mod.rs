@@ -3,6 +3,7 @@ mod common;
 pub mod constants;
 pub mod functions;
 pub mod gamelibrary;
+pub mod search;
 pub mod shared;
 pub mod shows;
 
@@ -0,0 +1,141 @@
+use std::cmp;
+
+use super::functions;
+
+/// Each matching word's cost is worth more than any number of extra words in a result
+const WORD_WEIGHT: usize = 100;
+
+/// What a query word costs against a result word, by how it matched
+const EXACT_COST: usize = 0;
+const PREFIX_COST: usize = 1;
+const SUBSTRING_COST: usize = 2;
+const TYPO_COST: usize = 2;
+/// When only the query's letters with the spaces and punctuation taken out match (e.g. "acdc")
+const COMPACT_COST: usize = 3;
+
+/// A search query, matched word by word against what is searched
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    words: Vec<String>,
+    compact: String,
+}
+
+impl SearchQuery {
+    pub fn new(query: &str) -> Self {
+        let words = split_words(query);
+        let compact = words.concat();
+        SearchQuery { words, compact }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// How far `candidate` is from the query (lower is closer), or None when it does not match
+    pub fn score(&self, candidate: &str) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let candidate_words = split_words(candidate);
+        let extra_words = candidate_words.len().saturating_sub(self.words.len());
+
+        let word_cost = self
+            .words
+            .iter()
+            .map(|word| {
+                candidate_words
+                    .iter()
+                    .filter_map(|candidate_word| word_cost(word, candidate_word))
+                    .min()
+            })
+            .sum::<Option<usize>>();
+
+        match word_cost {
+            Some(cost) => Some(cost * WORD_WEIGHT + extra_words),
+            None => match candidate_words.concat().contains(&self.compact) {
+                true => Some(COMPACT_COST * self.words.len() * WORD_WEIGHT + extra_words),
+                false => None,
+            },
+        }
+    }
+}
+
+/// Sort scored results closest first, and alphabetically by the name they were scored on for
+/// equal scores
+pub fn ranked<T>(mut scored: Vec<(usize, String, T)>) -> Vec<T> {
+    scored.sort_by_cached_key(|(score, name, _)| (*score, name.to_lowercase()));
+    scored.into_iter().map(|(_, _, result)| result).collect()
+}
+
+fn split_words(s: &str) -> Vec<String> {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect()
+}
+
+fn word_cost(query_word: &str, candidate_word: &str) -> Option<usize> {
+    if candidate_word == query_word {
+        return Some(EXACT_COST);
+    }
+    if candidate_word.starts_with(query_word) {
+        return Some(PREFIX_COST);
+    }
+    if candidate_word.contains(query_word) {
+        return Some(SUBSTRING_COST);
+    }
+
+    let allowed_typos = allowed_typos(query_word);
+    if allowed_typos == 0 {
+        return None;
+    }
+
+    // against the start of the word too, for a typo in a word that is still being typed
+    let candidate_prefix = candidate_word
+        .chars()
+        .take(query_word.chars().count())
+        .collect::<String>();
+    let typos = cmp::min(
+        functions::levenshtein(query_word, candidate_word),
+        functions::levenshtein(query_word, &candidate_prefix) + 1,
+    );
+    // levenshtein counts swapped letters as two edits, but it is a single slip of the fingers
+    let typos = match is_one_swap(query_word, candidate_word)
+        || is_one_swap(query_word, &candidate_prefix)
+    {
+        true => cmp::min(typos, 1),
+        false => typos,
+    };
+
+    match typos <= allowed_typos {
+        true => Some(TYPO_COST + 2 * typos),
+        false => None,
+    }
+}
+
+/// Whether the words are the same but for two neighbouring letters being swapped
+fn is_one_swap(first: &str, second: &str) -> bool {
+    let first = first.chars().collect::<Vec<char>>();
+    let second = second.chars().collect::<Vec<char>>();
+    if first.len() != second.len() {
+        return false;
+    }
+    let differences = (0..first.len())
+        .filter(|i| first[*i] != second[*i])
+        .collect::<Vec<usize>>();
+    match differences.as_slice() {
+        [i, j] => *j == *i + 1 && first[*i] == second[*j] && first[*j] == second[*i],
+        _ => false,
+    }
+}
+
+/// Short words have to be typed right, longer ones can be a letter or two off
+fn allowed_typos(query_word: &str) -> usize {
+    match query_word.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
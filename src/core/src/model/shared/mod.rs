@@ -1,3 +1,5 @@
 mod runmode;
+mod shufflemode;
 
 pub use runmode::SinkMode;
+pub use shufflemode::ShuffleMode;
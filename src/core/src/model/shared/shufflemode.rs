@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// How a list of tracks gets shuffled, picked on the settings page
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ShuffleMode {
+    Uniform,
+    ArtistSpread,
+    Album,
+    Weighted,
+}
+
+impl ShuffleMode {
+    pub fn all() -> Vec<Self> {
+        vec![
+            ShuffleMode::Uniform,
+            ShuffleMode::ArtistSpread,
+            ShuffleMode::Album,
+            ShuffleMode::Weighted,
+        ]
+    }
+
+    pub fn display_text(&self) -> String {
+        match self {
+            ShuffleMode::Uniform => "Uniform",
+            ShuffleMode::ArtistSpread => "Spread Artists",
+            ShuffleMode::Album => "By Album",
+            ShuffleMode::Weighted => "Favor Rarely Played",
+        }
+        .to_string()
+    }
+}
@@ -3,6 +3,7 @@ use std::path;
 use musiqlibrary::shows;
 
 use crate::datastore::jsonbacked;
+use crate::model::search;
 
 pub struct ShowLibraryState {
     inner: Option<ShowLibrary>,
@@ -32,6 +33,7 @@ impl ShowLibraryState {
 
 pub struct ShowLibrary {
     shows: shows::Shows,
+    scan_errors: Vec<musiqlibrary::ScanError>,
     tracker: jsonbacked::showtracker::ShowTracker,
 }
 
@@ -40,11 +42,12 @@ impl ShowLibrary {
         show_root_path: P,
         tracker: jsonbacked::showtracker::ShowTracker,
     ) -> Self {
-        let show_vec = shows::find_shows_in_dir(show_root_path);
+        let (show_vec, scan_errors) = shows::scan_shows_in_dir(show_root_path);
         let structured = shows::Shows::from_vec(&show_vec);
 
         ShowLibrary {
             shows: structured,
+            scan_errors,
             tracker,
         }
     }
@@ -53,6 +56,11 @@ impl ShowLibrary {
         &self.shows
     }
 
+    /// Files in the show directory which could not be loaded
+    pub fn get_scan_errors(&self) -> &Vec<musiqlibrary::ScanError> {
+        &self.scan_errors
+    }
+
     pub fn get_tracker(&self) -> &jsonbacked::showtracker::ShowTracker {
         &self.tracker
     }
@@ -77,13 +85,133 @@ impl ShowLibrary {
         }
     }
 
+    pub fn get_season_progress(
+        &self,
+        show_key: &musiqlibrary::shows::ShowKey,
+        season_number: u32,
+    ) -> jsonbacked::showtracker::WatchProgress {
+        match self
+            .shows
+            .get_show(show_key)
+            .and_then(|show| show.get_season(&season_number))
+        {
+            Some(season) => self.tracker.get_progress(season.get_episodes().values()),
+            None => jsonbacked::showtracker::WatchProgress::default(),
+        }
+    }
+
+    /// Progress through every season but the specials
+    pub fn get_show_progress(
+        &self,
+        show_key: &musiqlibrary::shows::ShowKey,
+    ) -> jsonbacked::showtracker::WatchProgress {
+        match self.shows.get_show(show_key) {
+            Some(show) => self
+                .tracker
+                .get_progress(show.get_regular_episodes().into_iter()),
+            None => jsonbacked::showtracker::WatchProgress::default(),
+        }
+    }
+
+    pub fn mark_season_watched(
+        &mut self,
+        show_key: &musiqlibrary::shows::ShowKey,
+        season_number: u32,
+    ) {
+        let keys = self.season_episode_keys(show_key, season_number);
+        self.tracker
+            .mark_unwatched_episodes_viewed_at(keys, chrono::Local::now());
+    }
+
+    pub fn mark_season_unwatched(
+        &mut self,
+        show_key: &musiqlibrary::shows::ShowKey,
+        season_number: u32,
+    ) {
+        let keys = self.season_episode_keys(show_key, season_number);
+        self.tracker.clear_episode_views(keys);
+    }
+
+    pub fn mark_show_watched(&mut self, show_key: &musiqlibrary::shows::ShowKey) {
+        let keys = self.show_episode_keys(show_key);
+        self.tracker
+            .mark_unwatched_episodes_viewed_at(keys, chrono::Local::now());
+    }
+
+    pub fn mark_show_unwatched(&mut self, show_key: &musiqlibrary::shows::ShowKey) {
+        let keys = self.show_episode_keys(show_key);
+        self.tracker.clear_episode_views(keys);
+    }
+
+    fn season_episode_keys(
+        &self,
+        show_key: &musiqlibrary::shows::ShowKey,
+        season_number: u32,
+    ) -> Vec<musiqlibrary::shows::ShowEpisodeKey> {
+        match self
+            .shows
+            .get_show(show_key)
+            .and_then(|show| show.get_season(&season_number))
+        {
+            Some(season) => season
+                .get_episodes()
+                .values()
+                .map(|episode| episode.get_key())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn show_episode_keys(
+        &self,
+        show_key: &musiqlibrary::shows::ShowKey,
+    ) -> Vec<musiqlibrary::shows::ShowEpisodeKey> {
+        match self.shows.get_show(show_key) {
+            Some(show) => show
+                .get_regular_episodes()
+                .into_iter()
+                .map(|episode| episode.get_key())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Shows by name and episodes by name (or title when they have no name), closest first
+    pub fn search(&self, query: &search::SearchQuery) -> ShowSearchResults {
+        let mut scored_shows = Vec::new();
+        let mut scored_episodes = Vec::new();
+
+        for (show_key, show) in self.shows.get_shows().iter() {
+            let show_name = show.get_name();
+            if let Some(score) = query.score(&show_name) {
+                scored_shows.push((score, show_name, show_key.clone()));
+            }
+
+            for season in show.get_seasons().values() {
+                for episode in season.get_episodes().values() {
+                    let episode_name = episode.episode_id.as_ref().unwrap_or(&episode.title);
+                    if let Some(score) = query.score(episode_name) {
+                        scored_episodes.push((score, episode_name.clone(), episode.clone()));
+                    }
+                }
+            }
+        }
+
+        ShowSearchResults {
+            shows: search::ranked(scored_shows),
+            episodes: search::ranked(scored_episodes),
+        }
+    }
+
     pub fn get_next_show_to_view(
         &self,
         show_key: &musiqlibrary::shows::ShowKey,
     ) -> musiqlibrary::shows::ShowEpisodeKey {
-        let most_recently_viewed = self.get_show_most_recently_viewed(show_key);
-
-        self.next_episode(&most_recently_viewed)
+        match self.tracker.get_show_most_recently_viewed(show_key) {
+            Some(most_recently_viewed) => self.next_episode(most_recently_viewed),
+            // nothing watched yet, so start from the beginning
+            None => self.get_show_most_recently_viewed(show_key),
+        }
     }
 
     fn next_episode(
@@ -93,3 +221,9 @@ impl ShowLibrary {
         self.shows.next_episode(show_key)
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct ShowSearchResults {
+    pub shows: Vec<shows::ShowKey>,
+    pub episodes: Vec<shows::ShowMetadata>,
+}
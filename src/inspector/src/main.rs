@@ -147,10 +147,16 @@ fn main() {
         (
             "movies",
             Command::new_parent(
-                vec![(
-                    "tree",
-                    Command::Specific(Box::new(movies::MovieTreeViewer {})),
-                )]
+                vec![
+                    (
+                        "tree",
+                        Command::Specific(Box::new(movies::MovieTreeViewer {})),
+                    ),
+                    (
+                        "duration-check",
+                        Command::Specific(Box::new(movies::MovieDurationChecker {})),
+                    ),
+                ]
                 .into_iter()
                 .collect(),
             ),
@@ -158,10 +164,16 @@ fn main() {
         (
             "shows",
             Command::new_parent(
-                vec![(
-                    "tree",
-                    Command::Specific(Box::new(shows::ShowTreeViewer {})),
-                )]
+                vec![
+                    (
+                        "tree",
+                        Command::Specific(Box::new(shows::ShowTreeViewer {})),
+                    ),
+                    (
+                        "duration-check",
+                        Command::Specific(Box::new(shows::ShowDurationChecker {})),
+                    ),
+                ]
                 .into_iter()
                 .collect(),
             ),
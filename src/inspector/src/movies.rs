@@ -1,6 +1,7 @@
 use std::path::PathBuf;
+use std::time;
 
-use musiqlibrary::video;
+use musiqlibrary::{mp4time, video};
 
 use super::commands::AppCmd;
 
@@ -8,10 +9,10 @@ pub struct MovieTreeViewer {}
 
 impl AppCmd for MovieTreeViewer {
     fn operate(&self, path: PathBuf) {
-        let mut movies = video::find_movies_in_dir(path.clone());
+        let (mut movies, scan_errors) = video::scan_movies_in_dir(path.clone());
         movies.sort_by(|a, b| a.title.cmp(&b.title));
         eprintln!("Movie Library:");
-        let movie_count = movies.len() - 1;
+        let movie_count = movies.len().saturating_sub(1);
         for (current_movie_index, movie) in movies.iter().enumerate() {
             println!(
                 "{} Movie: '{}'{}",
@@ -41,5 +42,77 @@ impl AppCmd for MovieTreeViewer {
                 movie.path.clone().into_os_string().to_string_lossy()
             );
         }
+        print_scan_errors(&scan_errors);
+    }
+}
+
+/// How far the movie header and the longest track can be apart before it is flagged
+const DURATION_TOLERANCE: time::Duration = time::Duration::from_secs(2);
+
+pub struct MovieDurationChecker {}
+
+impl AppCmd for MovieDurationChecker {
+    fn operate(&self, path: PathBuf) {
+        check_mp4_durations(video::find_movie_paths(path));
+    }
+}
+
+/// Compare the movie header duration of each mp4 with its longest track, printing the ones
+/// that disagree (will be no output for a file that agrees)
+pub fn check_mp4_durations(paths: Vec<PathBuf>) {
+    eprintln!(
+        "let's see if any mp4 durations are more than {}s from their longest track",
+        DURATION_TOLERANCE.as_secs()
+    );
+    let mut mismatch_count = 0;
+    let mut checked_count = 0;
+    for path in paths.into_iter() {
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("mp4") | Some("m4v") => (),
+            _ => continue,
+        };
+        checked_count += 1;
+        match mp4time::read_mp4_durations(&path) {
+            Ok(durations) => match durations.longest_track {
+                Some(longest_track) => {
+                    let difference = durations.movie.abs_diff(longest_track);
+                    if difference > DURATION_TOLERANCE {
+                        mismatch_count += 1;
+                        println!(
+                            "mismatch: {}\tmovie header: {:.1}s\tlongest track: {:.1}s",
+                            path.to_string_lossy(),
+                            durations.movie.as_secs_f64(),
+                            longest_track.as_secs_f64()
+                        );
+                    }
+                }
+                None => {
+                    mismatch_count += 1;
+                    println!("no tracks: {}", path.to_string_lossy());
+                }
+            },
+            Err(e) => {
+                mismatch_count += 1;
+                println!("unreadable: {}\t{}", path.to_string_lossy(), e);
+            }
+        }
+    }
+    eprintln!(
+        "...{} of {} mp4 files flagged",
+        mismatch_count, checked_count
+    );
+}
+
+pub fn print_scan_errors(scan_errors: &[musiqlibrary::ScanError]) {
+    if scan_errors.is_empty() {
+        return;
+    }
+    println!("Could Not Load ({}):", scan_errors.len());
+    for scan_error in scan_errors.iter() {
+        println!(
+            "    {}: {}",
+            scan_error.path.clone().into_os_string().to_string_lossy(),
+            scan_error.reason
+        );
     }
 }
@@ -3,15 +3,16 @@ use std::path::PathBuf;
 use musiqlibrary::shows;
 
 use super::commands::AppCmd;
+use super::movies;
 
 pub struct ShowTreeViewer {}
 
 impl AppCmd for ShowTreeViewer {
     fn operate(&self, path: PathBuf) {
-        let shows = shows::find_shows_in_dir(path.clone());
+        let (shows, scan_errors) = shows::scan_shows_in_dir(path.clone());
         let structured = shows::Shows::from_vec(&shows);
         println!("All Shows:");
-        let show_count = structured.get_shows().len() - 1;
+        let show_count = structured.get_shows().len().saturating_sub(1);
         for (show_index, show) in structured.get_shows().values().enumerate() {
             println!(
                 "\t{}Show: {}",
@@ -38,6 +39,15 @@ impl AppCmd for ShowTreeViewer {
                 }
             }
         }
+        movies::print_scan_errors(&scan_errors);
+    }
+}
+
+pub struct ShowDurationChecker {}
+
+impl AppCmd for ShowDurationChecker {
+    fn operate(&self, path: PathBuf) {
+        movies::check_mp4_durations(shows::find_show_file_paths(path));
     }
 }
 
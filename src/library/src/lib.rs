@@ -1,8 +1,12 @@
 pub mod games;
 pub mod impls;
+pub mod matroska;
+pub mod mediatracks;
 pub mod model;
+pub mod mp4time;
 mod organizer;
 mod parser;
+mod scanerror;
 mod scanner;
 pub mod shows;
 pub mod video;
@@ -16,5 +20,6 @@ pub use model::*;
 
 pub use organizer::compute_album_paths;
 pub use organizer::organize_tracks;
+pub use scanerror::ScanError;
 pub use scanner::find_files;
 pub use scanner::find_only_files;
@@ -0,0 +1,208 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::{fmt, fs, path, str, time};
+
+/// Possible Errors from reading a Matroska/WebM file
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The file doesn't start with an EBML header, or its doc type isn't matroska/webm
+    NotMatroska,
+    /// An element's id or size couldn't be read
+    MalformedElement,
+    /// The segment has no Info element before its first cluster
+    NoSegmentInfo,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "could not read matroska file: {}", e),
+            Error::NotMatroska => write!(f, "not a matroska or webm file"),
+            Error::MalformedElement => write!(f, "malformed matroska element"),
+            Error::NoSegmentInfo => write!(f, "no segment info before the first cluster"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// What the Segment Info element says about a Matroska/WebM file
+/// https://www.matroska.org/technical/elements.html
+#[derive(Debug, Clone)]
+pub struct MatroskaInfo {
+    pub title: Option<String>,
+    pub duration: Option<time::Duration>,
+}
+
+const EBML_HEADER_ID: u64 = 0x1A45DFA3;
+const DOC_TYPE_ID: u64 = 0x4282;
+const SEGMENT_ID: u64 = 0x18538067;
+const SEGMENT_INFO_ID: u64 = 0x1549A966;
+const CLUSTER_ID: u64 = 0x1F43B675;
+const TIMESTAMP_SCALE_ID: u64 = 0x2AD7B1;
+const DURATION_ID: u64 = 0x4489;
+const TITLE_ID: u64 = 0x7BA9;
+
+/// Timestamps are in nanoseconds times this, unless the file says otherwise
+const DEFAULT_TIMESTAMP_SCALE: u64 = 1_000_000;
+
+/// Segment Info is small, anything bigger is not worth reading
+const MAX_SEGMENT_INFO_SIZE: u64 = 1 << 20;
+
+pub fn read_matroska_info<P: AsRef<path::Path>>(file_path: P) -> Result<MatroskaInfo, Error> {
+    let mut file = io::BufReader::new(fs::File::open(file_path)?);
+
+    let (header_id, header_size) = read_element_header(&mut file)?;
+    if header_id != EBML_HEADER_ID {
+        return Err(Error::NotMatroska);
+    }
+    let header = read_element_body(&mut file, header_size)?;
+    match find_child(&header, DOC_TYPE_ID)? {
+        Some(doc_type) if doc_type == b"matroska" || doc_type == b"webm" => (),
+        _ => return Err(Error::NotMatroska),
+    };
+
+    let (segment_id, _segment_size) = read_element_header(&mut file)?;
+    if segment_id != SEGMENT_ID {
+        return Err(Error::NotMatroska);
+    }
+
+    // the segment's children follow, with its info ahead of the clusters of media
+    loop {
+        let (id, size) = match read_element_header(&mut file) {
+            Ok(header) => header,
+            Err(Error::Io(ref e)) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(Error::NoSegmentInfo)
+            }
+            Err(e) => return Err(e),
+        };
+        match id {
+            SEGMENT_INFO_ID => {
+                let info = read_element_body(&mut file, size)?;
+                return parse_segment_info(&info);
+            }
+            CLUSTER_ID => return Err(Error::NoSegmentInfo),
+            _ => match size {
+                Some(size) => {
+                    let _ = file.seek(SeekFrom::Current(size as i64))?;
+                }
+                None => return Err(Error::MalformedElement),
+            },
+        }
+    }
+}
+
+fn parse_segment_info(info: &[u8]) -> Result<MatroskaInfo, Error> {
+    let timestamp_scale = match find_child(info, TIMESTAMP_SCALE_ID)? {
+        Some(scale) => read_uint(scale),
+        None => DEFAULT_TIMESTAMP_SCALE,
+    };
+
+    let duration = match find_child(info, DURATION_ID)? {
+        Some(raw_duration) => read_float(raw_duration).and_then(|ticks| {
+            let nanos = ticks * timestamp_scale as f64;
+            match nanos.is_finite() && nanos >= 0.0 {
+                true => Some(time::Duration::from_nanos(nanos as u64)),
+                false => None,
+            }
+        }),
+        None => None,
+    };
+
+    let title = match find_child(info, TITLE_ID)? {
+        Some(raw_title) => str::from_utf8(raw_title)
+            .ok()
+            .map(|title| title.trim_end_matches('\0').trim().to_string())
+            .filter(|title| !title.is_empty()),
+        None => None,
+    };
+
+    Ok(MatroskaInfo { title, duration })
+}
+
+/// Find a direct child element in the body of a master element
+fn find_child(body: &[u8], wanted_id: u64) -> Result<Option<&[u8]>, Error> {
+    let mut cursor = io::Cursor::new(body);
+    while (cursor.position() as usize) < body.len() {
+        let (id, size) = read_element_header(&mut cursor)?;
+        let size = size.ok_or(Error::MalformedElement)? as usize;
+        let start = cursor.position() as usize;
+        let end = start.checked_add(size).ok_or(Error::MalformedElement)?;
+        if end > body.len() {
+            return Err(Error::MalformedElement);
+        }
+        if id == wanted_id {
+            return Ok(Some(&body[start..end]));
+        }
+        cursor.set_position(end as u64);
+    }
+    Ok(None)
+}
+
+/// Read an element's id and its size, which is None when the size is unknown
+fn read_element_header<R: Read>(reader: &mut R) -> Result<(u64, Option<u64>), Error> {
+    let (id, _) = read_vint(reader, true)?;
+    let (size, length) = read_vint(reader, false)?;
+    let unknown_size = (1u64 << (7 * length)) - 1;
+    Ok((
+        id,
+        if size == unknown_size {
+            None
+        } else {
+            Some(size)
+        },
+    ))
+}
+
+fn read_element_body<R: Read>(reader: &mut R, size: Option<u64>) -> Result<Vec<u8>, Error> {
+    let size = match size {
+        Some(size) if size <= MAX_SEGMENT_INFO_SIZE => size as usize,
+        _ => return Err(Error::MalformedElement),
+    };
+    let mut body = vec![0; size];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
+
+/// Read a variable length integer, keeping its length marker when it is an element id
+fn read_vint<R: Read>(reader: &mut R, keep_marker: bool) -> Result<(u64, u32), Error> {
+    let mut first = [0u8; 1];
+    reader.read_exact(&mut first)?;
+    let length = first[0].leading_zeros() + 1;
+    if length > 8 {
+        return Err(Error::MalformedElement);
+    }
+
+    let mut value = match keep_marker {
+        true => first[0] as u64,
+        false => (first[0] as u64) & (0xFF >> length),
+    };
+    let mut rest = vec![0u8; (length - 1) as usize];
+    reader.read_exact(&mut rest)?;
+    for byte in rest.into_iter() {
+        value = (value << 8) | byte as u64;
+    }
+
+    Ok((value, length))
+}
+
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .take(8)
+        .fold(0, |total, byte| (total << 8) | *byte as u64)
+}
+
+fn read_float(bytes: &[u8]) -> Option<f64> {
+    match bytes.len() {
+        4 => Some(f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64),
+        8 => Some(f64::from_be_bytes([
+            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+        ])),
+        _ => None,
+    }
+}
@@ -0,0 +1,178 @@
+use std::{fs, path};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackKind {
+    Audio,
+    Subtitle,
+}
+
+/// An audio or subtitle track inside a video file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddedTrack {
+    pub kind: TrackKind,
+    /// ISO 639-2 code (like "eng"), None when the file says it is undetermined
+    pub language: Option<String>,
+    pub codec: String,
+}
+
+/// A subtitle file next to a video, like "Movie.srt" or "Movie.en.vtt"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleFile {
+    pub path: path::PathBuf,
+    /// What follows the video's name in the file name, like "en" in "Movie.en.srt"
+    pub language: Option<String>,
+    pub format: String,
+}
+
+/// The audio and subtitle tracks that can be picked between when playing a video
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaTracks {
+    pub embedded: Vec<EmbeddedTrack>,
+    pub sidecar_subtitles: Vec<SubtitleFile>,
+}
+
+impl EmbeddedTrack {
+    pub fn display_name(&self) -> String {
+        format!(
+            "{} ({})",
+            self.language.clone().unwrap_or("und".to_string()),
+            self.codec
+        )
+    }
+}
+
+impl SubtitleFile {
+    pub fn display_name(&self) -> String {
+        format!(
+            "{} ({} file)",
+            self.language.clone().unwrap_or("und".to_string()),
+            self.format
+        )
+    }
+}
+
+impl MediaTracks {
+    pub fn audio(&self) -> Vec<&EmbeddedTrack> {
+        self.embedded
+            .iter()
+            .filter(|track| track.kind == TrackKind::Audio)
+            .collect()
+    }
+
+    pub fn embedded_subtitles(&self) -> Vec<&EmbeddedTrack> {
+        self.embedded
+            .iter()
+            .filter(|track| track.kind == TrackKind::Subtitle)
+            .collect()
+    }
+
+    /// The audio tracks, like "eng (aac), jpn (aac)"
+    pub fn audio_summary(&self) -> String {
+        self.audio()
+            .iter()
+            .map(|track| track.display_name())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// The embedded and sidecar subtitles, like "eng (tx3g), en (srt file)"
+    pub fn subtitle_summary(&self) -> String {
+        self.embedded_subtitles()
+            .iter()
+            .map(|track| track.display_name())
+            .chain(
+                self.sidecar_subtitles
+                    .iter()
+                    .map(|subtitle| subtitle.display_name()),
+            )
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+/// The audio and subtitle tracks from an mp4's trak boxes, skipping video and others
+pub fn find_mp4_tracks(traks: &[mp4::TrakBox]) -> Vec<EmbeddedTrack> {
+    let mut tracks = Vec::new();
+    for trak in traks.iter() {
+        // https://developer.apple.com/documentation/quicktime-file-format/handler_reference_atom
+        let kind = match trak.mdia.hdlr.handler_type.to_string().as_str() {
+            "soun" => TrackKind::Audio,
+            "sbtl" | "subt" | "text" | "clcp" => TrackKind::Subtitle,
+            _ => continue,
+        };
+
+        let stsd = &trak.mdia.minf.stbl.stsd;
+        let codec = if stsd.mp4a.is_some() {
+            "aac"
+        } else if stsd.tx3g.is_some() {
+            "tx3g"
+        } else {
+            "unknown"
+        };
+
+        let language = match trak.mdia.mdhd.language.trim() {
+            "" | "und" => None,
+            language => Some(language.to_string()),
+        };
+
+        tracks.push(EmbeddedTrack {
+            kind,
+            language,
+            codec: codec.to_string(),
+        });
+    }
+    tracks
+}
+
+/// Find the .srt and .vtt files next to a video which start with the video's file name
+pub fn find_sidecar_subtitles(video_path: &path::Path) -> Vec<SubtitleFile> {
+    let mut subtitles = Vec::new();
+
+    let (parent_dir, video_stem) = match (video_path.parent(), video_path.file_stem()) {
+        (Some(parent_dir), Some(video_stem)) => (parent_dir, video_stem.to_string_lossy()),
+        _ => return subtitles,
+    };
+
+    let entries = match fs::read_dir(parent_dir) {
+        Ok(entries) => entries,
+        Err(_) => return subtitles,
+    };
+
+    for entry in entries.flatten() {
+        let subtitle_path = entry.path();
+        let format = match subtitle_path
+            .extension()
+            .map(|x| x.to_string_lossy().to_lowercase())
+        {
+            Some(format) if format == "srt" || format == "vtt" => format,
+            _ => continue,
+        };
+        let subtitle_stem = match subtitle_path.file_stem() {
+            Some(subtitle_stem) => subtitle_stem.to_string_lossy().to_string(),
+            None => continue,
+        };
+
+        let language = if subtitle_stem == video_stem {
+            None
+        } else {
+            match subtitle_stem
+                .strip_prefix(video_stem.as_ref())
+                .and_then(|rest| rest.strip_prefix('.'))
+            {
+                Some(rest) => rest.split('.').next().map(|x| x.to_string()),
+                None => continue,
+            }
+        };
+
+        subtitles.push(SubtitleFile {
+            path: subtitle_path,
+            language,
+            format,
+        });
+    }
+
+    subtitles.sort_by(|a, b| a.path.cmp(&b.path));
+    subtitles
+}
@@ -0,0 +1,45 @@
+use std::{fs, path, time};
+
+/// An mp4's duration from its movie header, next to the longest of its tracks
+#[derive(Debug, Clone)]
+pub struct Mp4Durations {
+    pub movie: time::Duration,
+    /// Each track's duration from its own media header, so it doesn't rely on the movie's
+    pub longest_track: Option<time::Duration>,
+}
+
+/// The duration in the movie header, which counts in units of the header's timescale
+pub fn movie_duration(mvhd: &mp4::MvhdBox) -> time::Duration {
+    scaled_duration(mvhd.duration, mvhd.timescale)
+}
+
+/// The longest of the tracks, each counting in units of its own media timescale
+pub fn longest_track_duration(moov: &mp4::MoovBox) -> Option<time::Duration> {
+    moov.traks
+        .iter()
+        .map(|trak| scaled_duration(trak.mdia.mdhd.duration, trak.mdia.mdhd.timescale))
+        .max()
+}
+
+pub fn read_mp4_durations<P: AsRef<path::Path>>(file_path: P) -> Result<Mp4Durations, mp4::Error> {
+    let file = fs::File::open(file_path).map_err(mp4::Error::IoError)?;
+    let raw_metadata = mp4::read_mp4(file)?;
+
+    Ok(Mp4Durations {
+        movie: movie_duration(&raw_metadata.moov.mvhd),
+        longest_track: longest_track_duration(&raw_metadata.moov),
+    })
+}
+
+/// Turn a count of `timescale`ths of a second into a duration, an unset (zero) timescale or a
+/// duration of all ones (which mp4 uses for unknown) give zero
+fn scaled_duration(duration: u64, timescale: u32) -> time::Duration {
+    if timescale == 0 || duration == u64::MAX || duration == u32::MAX as u64 {
+        return time::Duration::ZERO;
+    }
+    let timescale = timescale as u64;
+    time::Duration::new(
+        duration / timescale,
+        ((duration % timescale) * 1_000_000_000 / timescale) as u32,
+    )
+}
@@ -0,0 +1,19 @@
+use std::path;
+
+use serde::{Deserialize, Serialize};
+
+/// A file found while scanning a library which could not be loaded, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanError {
+    pub path: path::PathBuf,
+    pub reason: String,
+}
+
+impl ScanError {
+    pub fn new<E: std::fmt::Display>(path: path::PathBuf, error: E) -> Self {
+        ScanError {
+            path,
+            reason: error.to_string(),
+        }
+    }
+}
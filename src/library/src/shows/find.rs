@@ -1,11 +1,89 @@
 use std::sync::mpsc;
-use std::{fs, path, str, time};
+use std::{fs, io, path, str, time};
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::{matroska, mediatracks, mp4time, ScanError};
 
 pub use super::smodel::{Error, ShowMetadata};
 
-/// Recursively find all show metadata
+/// What a show or season directory's metadata.json can say about the episodes in it
+#[derive(Deserialize, Default)]
+struct ShowDirectoryMetadata {
+    show: Option<String>,
+    /// About the show in a show directory, or about the season in a season directory
+    description: Option<String>,
+    /// 0 for specials
+    season: Option<u32>,
+    grouping: Option<String>,
+    #[serde(default)]
+    episodes: Vec<EpisodeSidecar>,
+}
+
+/// What a season directory's metadata.json says about one of its episodes
+#[derive(Deserialize, Clone)]
+struct EpisodeSidecar {
+    /// The episode's file name, for when its number alone doesn't pick it out
+    file: Option<String>,
+    episode: Option<u32>,
+    /// Which part this is, for a multi-part episode
+    part: Option<u32>,
+    title: Option<String>,
+    plot: Option<String>,
+    /// Like "2005/02/21"
+    air_date: Option<String>,
+}
+
+/// The metadata.json files around an episode, one for its season and one for its show, which
+/// are the same file when the episodes sit right in the show's directory
+struct EpisodeSidecars {
+    season: ShowDirectoryMetadata,
+    show: Option<ShowDirectoryMetadata>,
+}
+
+/// The pieces of a file name like "Show Name - S02E05 - Episode Name"
+struct EpisodeFileName {
+    show: String,
+    season_number: u32,
+    episode_sort: u32,
+    episode_name: String,
+}
+
+/// What an mp4's tags say about its episode, which is nothing for the other containers
+#[derive(Default)]
+struct EpisodeTags {
+    show: Option<String>,
+    season_number: Option<u32>,
+    episode_sort: Option<u32>,
+    episode_name: Option<String>,
+}
+
+/// Which episode a file is, along with its metadata.json entry if it has one
+struct IdentifiedEpisode {
+    show: String,
+    season_number: u32,
+    episode_sort: u32,
+    episode_id: Option<String>,
+    sidecar: Option<EpisodeSidecar>,
+}
+
+/// Recursively find all show metadata, printing the files which could not be loaded
 pub fn find_shows_in_dir<P: AsRef<path::Path>>(show_root_path: P) -> Vec<ShowMetadata> {
-    let all_paths = find_show_file_paths(show_root_path.as_ref().to_path_buf());
+    let (shows, errors) = scan_shows_in_dir(show_root_path);
+    for error in errors.iter() {
+        eprintln!("error: {}: {}", error.path.display(), error.reason);
+    }
+    shows
+}
+
+/// Recursively find all show metadata, along with the files which could not be loaded
+pub fn scan_shows_in_dir<P: AsRef<path::Path>>(
+    show_root_path: P,
+) -> (Vec<ShowMetadata>, Vec<ScanError>) {
+    let mut errors = Vec::new();
+    let all_paths =
+        find_show_file_paths_reporting(show_root_path.as_ref().to_path_buf(), &mut errors);
 
     let mut all_show_metadata = Vec::new();
 
@@ -23,36 +101,52 @@ pub fn find_shows_in_dir<P: AsRef<path::Path>>(show_root_path: P) -> Vec<ShowMet
 
         let show_path = show_root_path.as_ref().to_path_buf().clone();
 
-        tpool.execute(
-            move || match find_show_metadata(&show_path, &specific_show_path) {
-                Ok(v) => tx
-                    .send(v)
-                    .expect("I hope the show metadata rx is receiving"),
-                Err(ref e) => eprintln!("error: {:?}", e),
-            },
-        );
+        tpool.execute(move || {
+            let result = find_show_metadata(&show_path, &specific_show_path)
+                .map_err(|e| ScanError::new(specific_show_path, e));
+            tx.send(result)
+                .expect("I hope the show metadata rx is receiving")
+        });
     }
 
     drop(tx);
 
-    for show_metadata in rx {
-        all_show_metadata.push(show_metadata);
+    for result in rx {
+        match result {
+            Ok(show_metadata) => all_show_metadata.push(show_metadata),
+            Err(error) => errors.push(error),
+        }
     }
 
-    all_show_metadata
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+    (all_show_metadata, errors)
 }
 
 /// Recursively find the paths for all show files in a directory
 pub fn find_show_file_paths(current_path: path::PathBuf) -> Vec<path::PathBuf> {
+    let mut errors = Vec::new();
+    let paths = find_show_file_paths_reporting(current_path, &mut errors);
+    for error in errors.iter() {
+        eprintln!("error: {}: {}", error.path.display(), error.reason);
+    }
+    paths
+}
+
+/// Recursively find the paths for all show files in a directory, noting the directories
+/// which could not be read
+fn find_show_file_paths_reporting(
+    current_path: path::PathBuf,
+    errors: &mut Vec<ScanError>,
+) -> Vec<path::PathBuf> {
     if current_path.is_file() {
-        let fileext = current_path
-            .extension()
-            .expect("filename should have ext")
-            .to_str()
-            .unwrap();
+        let fileext = match current_path.extension().and_then(|x| x.to_str()) {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
 
         return match fileext {
-            "m4v" | "mp4" => vec![current_path.clone()],
+            "m4v" | "mp4" | "mkv" | "webm" => vec![current_path.clone()],
             "jpg" | "json" => Vec::new(), // shows should have jpg box art and json metadata
             // files, but there's nothing to do with them here
             _ => {
@@ -63,10 +157,21 @@ pub fn find_show_file_paths(current_path: path::PathBuf) -> Vec<path::PathBuf> {
     }
     if current_path.is_dir() {
         let mut ret = Vec::new();
-        for entry in current_path.read_dir().unwrap() {
-            let entry = entry.unwrap();
-            let mut children = find_show_file_paths(entry.path());
-            ret.append(&mut children);
+        let entries = match current_path.read_dir() {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(ScanError::new(current_path, Error::Unreadable(e)));
+                return ret;
+            }
+        };
+        for entry in entries {
+            match entry {
+                Ok(entry) => {
+                    let mut children = find_show_file_paths_reporting(entry.path(), errors);
+                    ret.append(&mut children);
+                }
+                Err(e) => errors.push(ScanError::new(current_path.clone(), Error::Unreadable(e))),
+            }
         }
         return ret;
     }
@@ -89,61 +194,65 @@ pub fn find_show_metadata(
     );
     let fileext = path::Path::new(show_path)
         .extension()
-        .unwrap()
-        .to_str()
-        .unwrap();
+        .and_then(|x| x.to_str());
 
     match fileext {
-        "m4v" | "mp4" => find_mp4_metadata(orig_scan_path, show_path),
+        Some("m4v") | Some("mp4") => find_mp4_metadata(orig_scan_path, show_path),
+        Some("mkv") | Some("webm") => find_matroska_metadata(orig_scan_path, show_path),
         _ => Err(Error::NonMP4File),
     }
 }
 
-/// Extract the show metadata for a "MPEG-4 Part 14" or "MP4" file
+/// Extract the show metadata for a "MPEG-4 Part 14" or "MP4" file, where whatever the tags
+/// leave out comes from the directory's metadata.json or the file name
 /// https://en.wikipedia.org/wiki/MP4_file_format
 /// https://en.wikipedia.org/wiki/Comparison_of_video_container_formats
 fn find_mp4_metadata(
     orig_scan_path: &path::PathBuf,
     show_path: &path::PathBuf,
 ) -> Result<ShowMetadata, Error> {
-    let show_file = fs::File::open(show_path).unwrap();
-    let raw_metadata = mp4::read_mp4(show_file).unwrap();
-
-    let tag = mp4ameta::Tag::read_from_path(show_path).unwrap_or_else(|_| {
-        panic!(
-            "could not load m4a file: {}",
-            show_path
-                .clone()
-                .into_os_string()
-                .to_string_lossy()
-                .to_string()
-        )
-    });
-
-    // for title and maybe more metadata
-    let udta = raw_metadata.moov.udta.unwrap();
+    let show_file = fs::File::open(show_path).map_err(Error::Unreadable)?;
+    let raw_metadata = mp4::read_mp4(show_file).map_err(Error::Mp4)?;
+
+    let tag = mp4ameta::Tag::read_from_path(show_path).map_err(Error::Tags)?;
+
     // for duration
     let mvhd = raw_metadata.moov.mvhd;
 
-    let title = match udta.meta.unwrap() {
-        mp4::MetaBox::Mdir { ilst } => {
-            let ilst = ilst.unwrap();
-            //eprintln!("ilst {:?}", ilst.items);
-            let title_data = &ilst.items.get(&mp4::MetadataKey::Title).unwrap().data;
-            if title_data.data_type == mp4::DataType::Text {
-                Ok(str::from_utf8(title_data.data.as_slice())
-                    .unwrap()
-                    .to_string())
-            } else {
-                Err(Error::NonTextTitle)
-            }
-        }
-        _ => Err(Error::NonMP4File),
-    }?;
+    let embedded_title = match raw_metadata
+        .moov
+        .udta
+        .ok_or(Error::NoUserData)
+        .and_then(read_mp4_title)
+    {
+        Ok(embedded_title) => Some(embedded_title),
+        Err(Error::NoUserData) | Err(Error::NoMetadata) | Err(Error::NoTitle) => None,
+        Err(e) => return Err(e),
+    };
 
-    let duration = time::Duration::from_millis(mvhd.duration);
+    let duration = mp4time::movie_duration(&mvhd);
 
-    let last_mod = fs::metadata(show_path).unwrap().modified().unwrap();
+    let tracks = mediatracks::MediaTracks {
+        embedded: mediatracks::find_mp4_tracks(&raw_metadata.moov.traks),
+        sidecar_subtitles: mediatracks::find_sidecar_subtitles(show_path),
+    };
+
+    let last_mod = read_last_modified(show_path)?;
+
+    let sidecars = find_episode_sidecars(orig_scan_path, show_path);
+
+    let file_stem = show_path.file_stem().unwrap().to_string_lossy().to_string();
+    let episode = identify_episode(
+        &sidecars,
+        show_path,
+        &file_stem,
+        EpisodeTags {
+            show: tag.tv_show_name().map(|x| x.to_string()),
+            season_number: tag.tv_season(),
+            episode_sort: tag.tv_episode(),
+            episode_name: tag.tv_episode_name().map(|x| x.to_string()),
+        },
+    )?;
 
     Ok(ShowMetadata {
         full_path: show_path.clone(),
@@ -154,14 +263,331 @@ fn find_mp4_metadata(
             .to_path_buf(),
         last_modified: last_mod,
         duration,
+        tracks,
+
+        album: tag
+            .album()
+            .map(|x| x.to_string())
+            .unwrap_or(episode.show.clone()),
+        show: episode.show,
+        season_number: episode.season_number,
+        grouping: tag
+            .grouping()
+            .map(|x| x.to_string())
+            .or(sidecars.season.grouping.clone()),
+        episode_id: episode.episode_id,
+        episode_sort: episode.episode_sort,
+        episode_sort_tiebreak: episode.sidecar.as_ref().and_then(|x| x.part),
+        title: embedded_title.unwrap_or(file_stem),
 
-        show: tag.tv_show_name().unwrap().to_string(),
-        album: tag.album().unwrap().to_string(),
-        season_number: tag.tv_season().unwrap(),
-        grouping: tag.grouping().map(|x| x.to_string()),
-        episode_id: tag.tv_episode_name().map(|x| x.to_string()),
-        episode_sort: tag.tv_episode().unwrap(),
-        episode_sort_tiebreak: None,
-        title,
+        show_description: sidecars.show_description(),
+        season_description: sidecars.season_description(),
+        plot: episode.sidecar.as_ref().and_then(|x| x.plot.clone()),
+        air_date: episode.sidecar.as_ref().and_then(|x| x.parse_air_date()),
     })
 }
+
+/// Extract the show metadata for a Matroska (or WebM) file, which has no show/season/episode
+/// tags we read, so those come from the directory's metadata.json or the file name
+fn find_matroska_metadata(
+    orig_scan_path: &path::PathBuf,
+    show_path: &path::PathBuf,
+) -> Result<ShowMetadata, Error> {
+    let info = matroska::read_matroska_info(show_path).map_err(Error::Matroska)?;
+
+    let sidecars = find_episode_sidecars(orig_scan_path, show_path);
+
+    let file_stem = show_path.file_stem().unwrap().to_string_lossy().to_string();
+    let episode = identify_episode(&sidecars, show_path, &file_stem, EpisodeTags::default())?;
+
+    let last_mod = read_last_modified(show_path)?;
+
+    Ok(ShowMetadata {
+        full_path: show_path.clone(),
+        rel_path: show_path
+            .to_path_buf()
+            .strip_prefix(orig_scan_path)
+            .unwrap()
+            .to_path_buf(),
+        last_modified: last_mod,
+        duration: info.duration.unwrap_or_default(),
+        tracks: mediatracks::MediaTracks {
+            embedded: Vec::new(),
+            sidecar_subtitles: mediatracks::find_sidecar_subtitles(show_path),
+        },
+
+        album: episode.show.clone(),
+        episode_id: episode.episode_id.or(info.title.clone()),
+        title: info.title.unwrap_or(file_stem),
+        show: episode.show,
+        season_number: episode.season_number,
+        grouping: sidecars.season.grouping.clone(),
+        episode_sort: episode.episode_sort,
+        episode_sort_tiebreak: episode.sidecar.as_ref().and_then(|x| x.part),
+
+        show_description: sidecars.show_description(),
+        season_description: sidecars.season_description(),
+        plot: episode.sidecar.as_ref().and_then(|x| x.plot.clone()),
+        air_date: episode.sidecar.as_ref().and_then(|x| x.parse_air_date()),
+    })
+}
+
+/// Work out which episode a file is, from its tags first, then the directory's metadata.json,
+/// then a file name like "Show Name - S02E05 - Episode Name"
+fn identify_episode(
+    sidecars: &EpisodeSidecars,
+    show_path: &path::Path,
+    file_stem: &str,
+    tags: EpisodeTags,
+) -> Result<IdentifiedEpisode, Error> {
+    let maybe_file_name = parse_episode_file_name(file_stem);
+
+    let show = match (
+        tags.show,
+        sidecars.show_name(),
+        maybe_file_name.as_ref().map(|x| x.show.clone()),
+    ) {
+        (Some(show), _, _) | (None, Some(show), _) => show,
+        (None, None, Some(show)) if !show.is_empty() => show,
+        _ => return Err(Error::MissingEpisodeInfo),
+    };
+    let season_number = match (
+        tags.season_number,
+        sidecars.season.season,
+        maybe_file_name.as_ref().map(|x| x.season_number),
+    ) {
+        (Some(season_number), _, _)
+        | (None, Some(season_number), _)
+        | (None, None, Some(season_number)) => season_number,
+        (None, None, None) => return Err(Error::MissingEpisodeInfo),
+    };
+    let episode_sort = match (
+        tags.episode_sort,
+        sidecars
+            .find_episode_by_file(show_path)
+            .and_then(|x| x.episode),
+        maybe_file_name.as_ref().map(|x| x.episode_sort),
+    ) {
+        (Some(episode_sort), _, _)
+        | (None, Some(episode_sort), _)
+        | (None, None, Some(episode_sort)) => episode_sort,
+        (None, None, None) => return Err(Error::MissingEpisodeInfo),
+    };
+    let sidecar = sidecars.find_episode(show_path, episode_sort);
+    let episode_id = match (
+        sidecar.as_ref().and_then(|x| x.title.clone()),
+        tags.episode_name,
+        maybe_file_name.map(|x| x.episode_name),
+    ) {
+        (Some(sidecar_title), _, _) => Some(sidecar_title),
+        (None, Some(episode_name), _) => Some(episode_name),
+        (None, None, Some(episode_name)) if !episode_name.is_empty() => Some(episode_name),
+        _ => None,
+    };
+
+    Ok(IdentifiedEpisode {
+        show,
+        season_number,
+        episode_sort,
+        episode_id,
+        sidecar,
+    })
+}
+
+/// Read the text title out of an mp4's udta box
+fn read_mp4_title(udta: mp4::UdtaBox) -> Result<String, Error> {
+    match udta.meta.ok_or(Error::NoMetadata)? {
+        mp4::MetaBox::Mdir { ilst } => {
+            let ilst = ilst.ok_or(Error::NoMetadata)?;
+            let title_data = &ilst
+                .items
+                .get(&mp4::MetadataKey::Title)
+                .ok_or(Error::NoTitle)?
+                .data;
+            if title_data.data_type == mp4::DataType::Text {
+                str::from_utf8(title_data.data.as_slice())
+                    .map(|x| x.to_string())
+                    .map_err(|_| Error::NonTextTitle)
+            } else {
+                Err(Error::NonTextTitle)
+            }
+        }
+        _ => Err(Error::NoMetadata),
+    }
+}
+
+fn read_last_modified(show_path: &path::PathBuf) -> Result<time::SystemTime, Error> {
+    fs::metadata(show_path)
+        .and_then(|x| x.modified())
+        .map_err(Error::Unreadable)
+}
+
+/// Read the season's metadata.json next to the episode, and the show's in the first directory
+/// under the scan path, when the episode is nested deeper than that
+fn find_episode_sidecars(orig_scan_path: &path::Path, show_path: &path::Path) -> EpisodeSidecars {
+    let season_dir = show_path.parent().unwrap().to_path_buf();
+
+    let maybe_show_dir = show_path
+        .strip_prefix(orig_scan_path)
+        .ok()
+        .and_then(|rel_path| rel_path.components().next())
+        .map(|first| orig_scan_path.join(first))
+        .filter(|show_dir| show_dir.is_dir());
+
+    EpisodeSidecars {
+        season: find_show_directory_metadata(&season_dir),
+        show: match maybe_show_dir {
+            Some(show_dir) if show_dir != season_dir => {
+                Some(find_show_directory_metadata(&show_dir))
+            }
+            _ => None,
+        },
+    }
+}
+
+impl EpisodeSidecars {
+    fn show_name(&self) -> Option<String> {
+        self.season
+            .show
+            .clone()
+            .or(self.show.as_ref().and_then(|x| x.show.clone()))
+    }
+
+    fn show_description(&self) -> Option<String> {
+        match self.show {
+            Some(ref show) => show.description.clone(),
+            None => self.season.description.clone(),
+        }
+    }
+
+    fn season_description(&self) -> Option<String> {
+        match self.show {
+            Some(_) => self.season.description.clone(),
+            None => None,
+        }
+    }
+
+    fn find_episode_by_file(&self, show_path: &path::Path) -> Option<EpisodeSidecar> {
+        let file_name = show_path.file_name()?.to_string_lossy().to_string();
+        self.season
+            .episodes
+            .iter()
+            .find(|episode| episode.file.as_ref() == Some(&file_name))
+            .cloned()
+    }
+
+    /// The entry naming this file, or else the only entry (without a file) for this episode
+    fn find_episode(&self, show_path: &path::Path, episode_sort: u32) -> Option<EpisodeSidecar> {
+        match self.find_episode_by_file(show_path) {
+            Some(episode) => Some(episode),
+            None => {
+                let numbered: Vec<&EpisodeSidecar> = self
+                    .season
+                    .episodes
+                    .iter()
+                    .filter(|episode| {
+                        episode.file.is_none() && episode.episode == Some(episode_sort)
+                    })
+                    .collect();
+                match numbered.as_slice() {
+                    [episode] => Some((*episode).clone()),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+impl EpisodeSidecar {
+    fn parse_air_date(&self) -> Option<NaiveDate> {
+        let air_date = self.air_date.as_ref()?;
+        match NaiveDate::parse_from_str(air_date, "%Y/%m/%d") {
+            Ok(date) => Some(date),
+            Err(e) => {
+                eprintln!("could not parse air date: {} {:?}", air_date, e);
+                None
+            }
+        }
+    }
+}
+
+fn find_show_directory_metadata(directory: &path::Path) -> ShowDirectoryMetadata {
+    let metadata_json_file = directory.join("metadata.json");
+
+    match fs::File::open(&metadata_json_file) {
+        Ok(reader) => match serde_json::from_reader(io::BufReader::new(reader)) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                eprintln!(
+                    "could not deserialize data from path: {:?} {:?}",
+                    metadata_json_file.display(),
+                    e
+                );
+                ShowDirectoryMetadata::default()
+            }
+        },
+        Err(_) => ShowDirectoryMetadata::default(),
+    }
+}
+
+/// Find the first "S<season>E<episode>" (in any case) in a file name, splitting the name
+/// around it into the show before and the episode name after
+fn parse_episode_file_name(file_stem: &str) -> Option<EpisodeFileName> {
+    let chars: Vec<char> = file_stem.chars().collect();
+
+    for start in 0..chars.len() {
+        if !chars[start].eq_ignore_ascii_case(&'s') {
+            continue;
+        }
+        if start > 0 && chars[start - 1].is_alphanumeric() {
+            continue;
+        }
+
+        let season_end = digits_end(&chars, start + 1);
+        if season_end == start + 1
+            || season_end >= chars.len()
+            || !chars[season_end].eq_ignore_ascii_case(&'e')
+        {
+            continue;
+        }
+        let episode_end = digits_end(&chars, season_end + 1);
+        if episode_end == season_end + 1 {
+            continue;
+        }
+
+        let season_number = chars[start + 1..season_end]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()?;
+        let episode_sort = chars[season_end + 1..episode_end]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()?;
+
+        return Some(EpisodeFileName {
+            show: trim_separators(&chars[..start].iter().collect::<String>()),
+            season_number,
+            episode_sort,
+            episode_name: trim_separators(&chars[episode_end..].iter().collect::<String>()),
+        });
+    }
+
+    None
+}
+
+fn digits_end(chars: &[char], start: usize) -> usize {
+    let mut end = start;
+    while end < chars.len() && chars[end].is_ascii_digit() {
+        end += 1;
+    }
+    end
+}
+
+/// Turn "Show.Name." or " - Episode Name" into "Show Name" and "Episode Name"
+fn trim_separators(part: &str) -> String {
+    part.replace(['.', '_'], " ")
+        .trim_matches(|c: char| c.is_whitespace() || c == '-')
+        .to_string()
+}
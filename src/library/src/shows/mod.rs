@@ -3,7 +3,9 @@ mod smodel;
 
 pub use find::find_show_file_paths;
 pub use find::find_shows_in_dir;
-pub use smodel::ShowMetadata;
+pub use find::scan_shows_in_dir;
+pub use smodel::{EpisodeOrder, SPECIALS_SEASON_NUMBER};
+pub use smodel::{Error, ShowMetadata};
 pub use smodel::{Show, ShowEpisodeKey, ShowKey, ShowSeason, Shows};
 
 // Example output from ffprobe
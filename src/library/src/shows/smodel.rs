@@ -1,13 +1,48 @@
 use std::collections::BTreeMap;
-use std::{path, time};
+use std::{fmt, io, path, time};
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use crate::{matroska, mediatracks};
+
+/// Specials (and other extras) go in season 0, which is left out of watching in order
+pub const SPECIALS_SEASON_NUMBER: u32 = 0;
+
 /// Possible Errors from Show Searching/Decoding
 #[derive(Debug)]
 pub enum Error {
     NonTextTitle,
     NonMP4File,
+    Matroska(matroska::Error),
+    /// Neither the directory's metadata.json nor the file name say which show/season/episode
+    MissingEpisodeInfo,
+    /// The file couldn't be opened, or its modified time couldn't be read
+    Unreadable(io::Error),
+    Mp4(mp4::Error),
+    Tags(mp4ameta::Error),
+    /// The mp4 has no udta box, which is where its title lives
+    NoUserData,
+    /// The udta box has no metadata item list
+    NoMetadata,
+    NoTitle,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NonTextTitle => write!(f, "title is not text"),
+            Error::NonMP4File => write!(f, "not an mp4 file"),
+            Error::Matroska(e) => write!(f, "{}", e),
+            Error::MissingEpisodeInfo => write!(f, "no show, season or episode number"),
+            Error::Unreadable(e) => write!(f, "could not read file: {}", e),
+            Error::Mp4(e) => write!(f, "could not parse mp4: {}", e),
+            Error::Tags(e) => write!(f, "could not read mp4 tags: {}", e),
+            Error::NoUserData => write!(f, "no user data (udta) box"),
+            Error::NoMetadata => write!(f, "no metadata item list in the udta box"),
+            Error::NoTitle => write!(f, "no title tag"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Deserialize, Serialize)]
@@ -15,6 +50,15 @@ pub struct ShowEpisodeKey {
     pub show: ShowKey,
     pub season_number: u32,
     pub episode_sort: u32,
+    /// Only set for multi-part episodes, so keys tracked before parts existed still match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode_sort_tiebreak: Option<u32>,
+}
+
+impl ShowEpisodeKey {
+    pub fn is_special(&self) -> bool {
+        self.season_number == SPECIALS_SEASON_NUMBER
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +67,7 @@ pub struct ShowMetadata {
     pub rel_path: path::PathBuf,
     pub last_modified: time::SystemTime,
     pub duration: time::Duration,
+    pub tracks: mediatracks::MediaTracks,
 
     pub show: String,                       // .tv_show_name
     pub album: String,                      // .album
@@ -30,18 +75,28 @@ pub struct ShowMetadata {
     pub grouping: Option<String>,           // .grouping
     pub episode_id: Option<String>,         // .tv_episode_name
     pub episode_sort: u32,                  // .tv_episode
-    pub episode_sort_tiebreak: Option<u32>, // ??
+    pub episode_sort_tiebreak: Option<u32>, // part, for multi-part episodes
     pub title: String,                      // .title
+
+    pub show_description: Option<String>,   // show metadata.json
+    pub season_description: Option<String>, // season metadata.json
+    pub plot: Option<String>,               // season metadata.json
+    pub air_date: Option<NaiveDate>,        // season metadata.json
 }
 
 impl ShowMetadata {
     pub fn local_display_name(&self) -> String {
-        match self.episode_id {
-            Some(ref v) => v.clone(),
-            None => format!("Episode: {}", self.episode_sort),
+        match (self.episode_id.as_ref(), self.episode_sort_tiebreak) {
+            (Some(v), _) => v.clone(),
+            (None, Some(part)) => format!("Episode: {} (Part {})", self.episode_sort, part),
+            (None, None) => format!("Episode: {}", self.episode_sort),
         }
     }
 
+    pub fn is_special(&self) -> bool {
+        self.season_number == SPECIALS_SEASON_NUMBER
+    }
+
     pub fn get_key(&self) -> ShowEpisodeKey {
         ShowEpisodeKey {
             show: ShowKey {
@@ -49,10 +104,18 @@ impl ShowMetadata {
             },
             season_number: self.season_number,
             episode_sort: self.episode_sort,
+            episode_sort_tiebreak: self.episode_sort_tiebreak,
         }
     }
+
+    fn episode_order(&self) -> EpisodeOrder {
+        (self.episode_sort, self.episode_sort_tiebreak)
+    }
 }
 
+/// Where an episode falls in its season, by its number and then its part
+pub type EpisodeOrder = (u32, Option<u32>);
+
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct ShowKey {
@@ -73,7 +136,7 @@ impl Shows {
     pub fn from_vec(vec: &Vec<ShowMetadata>) -> Self {
         let mut shows = BTreeMap::new();
 
-        for show_metadata in vec.iter() {
+        for show_metadata in with_parts_numbered(vec).iter() {
             let show = shows
                 .entry(ShowKey {
                     name: show_metadata.show.clone(),
@@ -81,6 +144,7 @@ impl Shows {
                 .or_insert(Show {
                     name: show_metadata.show.clone(),
                     album: show_metadata.album.clone(),
+                    description: None,
                     seasons: BTreeMap::new(),
                 });
 
@@ -101,23 +165,69 @@ impl Shows {
     pub fn next_episode(&self, key: &ShowEpisodeKey) -> ShowEpisodeKey {
         self.get_show(&key.show).unwrap().next_episode(key)
     }
+
+    pub fn get_episode(&self, key: &ShowEpisodeKey) -> Option<&ShowMetadata> {
+        self.get_show(&key.show)?
+            .get_season(&key.season_number)?
+            .get_episodes()
+            .get(&(key.episode_sort, key.episode_sort_tiebreak))
+    }
+}
+
+/// Episodes which share a number in their season (and have no part from a metadata.json) are
+/// the parts of a multi-part episode, so they are numbered as parts in file name order
+fn with_parts_numbered(vec: &[ShowMetadata]) -> Vec<ShowMetadata> {
+    let mut by_episode: BTreeMap<(String, u32, u32), Vec<&ShowMetadata>> = BTreeMap::new();
+    for show_metadata in vec.iter() {
+        by_episode
+            .entry((
+                show_metadata.show.clone(),
+                show_metadata.season_number,
+                show_metadata.episode_sort,
+            ))
+            .or_default()
+            .push(show_metadata);
+    }
+
+    let mut numbered = Vec::new();
+    for (_, mut episodes) in by_episode.into_iter() {
+        let needs_parts = episodes.len() > 1
+            && episodes
+                .iter()
+                .all(|episode| episode.episode_sort_tiebreak.is_none());
+        episodes.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+        for (index, episode) in episodes.into_iter().enumerate() {
+            let mut episode = episode.clone();
+            if needs_parts {
+                episode.episode_sort_tiebreak = Some(index as u32 + 1);
+            }
+            numbered.push(episode);
+        }
+    }
+    numbered
 }
 
 pub struct Show {
     name: String,
     #[allow(unused)]
     album: String,
+    description: Option<String>,
     seasons: BTreeMap<u32, ShowSeason>,
 }
 
 impl Show {
     pub fn add(&mut self, metadata: &ShowMetadata) {
+        if self.description.is_none() {
+            self.description = metadata.show_description.clone();
+        }
+
         let seasons = self
             .seasons
             .entry(metadata.season_number)
             .or_insert(ShowSeason {
                 number: metadata.season_number,
                 name: metadata.grouping.clone(),
+                description: None,
                 episodes: BTreeMap::new(),
             });
 
@@ -128,6 +238,10 @@ impl Show {
         self.name.clone()
     }
 
+    pub fn get_description(&self) -> &Option<String> {
+        &self.description
+    }
+
     pub fn get_season(&self, season_id: &u32) -> Option<&ShowSeason> {
         self.seasons.get(season_id)
     }
@@ -136,9 +250,24 @@ impl Show {
         &self.seasons
     }
 
+    /// Every episode outside of the specials, in watching order
+    pub fn get_regular_episodes(&self) -> Vec<&ShowMetadata> {
+        self.seasons
+            .values()
+            .filter(|season| !season.is_specials())
+            .flat_map(|season| season.get_episodes().values())
+            .collect()
+    }
+
+    /// The first season that isn't specials, unless there are only specials
     pub fn get_first_season(&self) -> &ShowSeason {
-        let (first_key, _) = self.seasons.first_key_value().unwrap();
-        self.seasons.get(first_key).unwrap()
+        match self.seasons.range((SPECIALS_SEASON_NUMBER + 1)..).next() {
+            Some((_, season)) => season,
+            None => {
+                let (first_key, _) = self.seasons.first_key_value().unwrap();
+                self.seasons.get(first_key).unwrap()
+            }
+        }
     }
 
     fn next_episode(&self, key: &ShowEpisodeKey) -> ShowEpisodeKey {
@@ -157,7 +286,7 @@ impl Show {
 
     fn next_season(&self, key: &ShowEpisodeKey) -> Option<&ShowSeason> {
         self.seasons
-            .range((key.episode_sort + 1)..)
+            .range((key.season_number + 1)..)
             .next()
             .map(|x| x.1)
     }
@@ -166,7 +295,8 @@ impl Show {
 pub struct ShowSeason {
     number: u32,
     name: Option<String>,
-    episodes: BTreeMap<u32, ShowMetadata>,
+    description: Option<String>,
+    episodes: BTreeMap<EpisodeOrder, ShowMetadata>,
 }
 
 impl ShowSeason {
@@ -174,12 +304,24 @@ impl ShowSeason {
         self.number
     }
 
-    pub fn get_episodes(&self) -> &BTreeMap<u32, ShowMetadata> {
+    pub fn get_episodes(&self) -> &BTreeMap<EpisodeOrder, ShowMetadata> {
         &self.episodes
     }
 
+    /// The episode with this number, or its first part when it is a multi-part episode
     pub fn get_episode(&self, episode_id: &u32) -> Option<&ShowMetadata> {
-        self.episodes.get(episode_id)
+        self.episodes
+            .range((*episode_id, None)..=(*episode_id, Some(u32::MAX)))
+            .next()
+            .map(|x| x.1)
+    }
+
+    pub fn get_description(&self) -> &Option<String> {
+        &self.description
+    }
+
+    pub fn is_specials(&self) -> bool {
+        self.number == SPECIALS_SEASON_NUMBER
     }
 
     pub fn get_first_episode(&self) -> &ShowMetadata {
@@ -188,28 +330,36 @@ impl ShowSeason {
     }
 
     pub fn pretty_display(&self) -> String {
-        match self.name {
-            Some(ref name) => format!("{:02} : {}", self.number, name),
-            None => format!("Season {:02}", self.number),
+        match (self.name.as_ref(), self.is_specials()) {
+            (Some(name), _) => format!("{:02} : {}", self.number, name),
+            (None, true) => "Specials".to_string(),
+            (None, false) => format!("Season {:02}", self.number),
         }
     }
 
     fn add(&mut self, metadata: &ShowMetadata) {
-        let maybe_conflict = self
-            .episodes
-            .insert(metadata.episode_sort, metadata.clone());
-        match maybe_conflict {
-            Some(conflict) => panic!(
-                "found a conflict with {} {:?} {:?}",
-                metadata.episode_sort, conflict, metadata
+        if self.description.is_none() {
+            self.description = metadata.season_description.clone();
+        }
+
+        match self.episodes.get(&metadata.episode_order()) {
+            Some(conflict) => eprintln!(
+                "skipping {:?}, it has the same episode number and part as {:?}",
+                metadata.rel_path, conflict.rel_path
             ),
-            None => (),
+            None => {
+                self.episodes
+                    .insert(metadata.episode_order(), metadata.clone());
+            }
         }
     }
 
     fn maybe_next_episode(&self, key: &ShowEpisodeKey) -> Option<ShowEpisodeKey> {
         self.episodes
-            .range((key.episode_sort + 1)..)
+            .range((
+                std::ops::Bound::Excluded((key.episode_sort, key.episode_sort_tiebreak)),
+                std::ops::Bound::Unbounded,
+            ))
             .next()
             .map(|x| x.1.get_key())
     }
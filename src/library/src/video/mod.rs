@@ -1,16 +1,42 @@
 use std::sync::mpsc;
-use std::{fs, io, path, str, time};
+use std::{fmt, fs, io, path, str, time};
 
 use chrono::NaiveDate;
 use mp4;
 use serde::{Deserialize, Serialize};
 use serde_json;
 
+use crate::{matroska, mediatracks, mp4time, ScanError};
+
 /// Possible Errors from Movie Searching/Decoding
 #[derive(Debug)]
 pub enum Error {
     NonTextTitle,
     NonMP4File,
+    Matroska(matroska::Error),
+    /// The file couldn't be opened, or its modified time couldn't be read
+    Unreadable(io::Error),
+    Mp4(mp4::Error),
+    /// The mp4 has no udta box, which is where its title lives
+    NoUserData,
+    /// The udta box has no metadata item list
+    NoMetadata,
+    NoTitle,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NonTextTitle => write!(f, "title is not text"),
+            Error::NonMP4File => write!(f, "not an mp4 file"),
+            Error::Matroska(e) => write!(f, "{}", e),
+            Error::Unreadable(e) => write!(f, "could not read file: {}", e),
+            Error::Mp4(e) => write!(f, "could not parse mp4: {}", e),
+            Error::NoUserData => write!(f, "no user data (udta) box"),
+            Error::NoMetadata => write!(f, "no metadata item list in the udta box"),
+            Error::NoTitle => write!(f, "no title tag"),
+        }
+    }
 }
 
 /// Parsed and Normalized Movie Data
@@ -22,18 +48,30 @@ pub struct MovieMetadata {
     pub last_modified: time::SystemTime,
     pub duration: time::Duration,
     pub extra: Option<ExtraMetadata>,
+    #[serde(default)]
+    pub tracks: mediatracks::MediaTracks,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtraMetadata {
-    #[serde(with = "slash_date")]
-    pub release: NaiveDate,
+    /// For files which don't carry their own title
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default, with = "slash_date")]
+    pub release: Option<NaiveDate>,
+    #[serde(default)]
     pub genres: Vec<String>,
+    #[serde(default)]
     pub production: Vec<String>,
+    #[serde(default)]
     pub cast: Vec<String>,
+    #[serde(default)]
     pub producers: Vec<String>,
+    #[serde(default)]
     pub directors: Vec<String>,
+    #[serde(default)]
     pub writers: Vec<String>,
+    #[serde(default)]
     pub series: Option<SeriesInfo>,
 }
 
@@ -43,7 +81,10 @@ pub struct SeriesInfo {
     pub name: String,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+/// Identifies a movie by its path relative to the movie library, so it stays the same when
+/// the title or other metadata is edited
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct MovieID {
     inner_id: String,
 }
@@ -51,14 +92,26 @@ pub struct MovieID {
 impl MovieMetadata {
     pub fn get_id(&self) -> MovieID {
         MovieID {
-            inner_id: self.title.clone(),
+            inner_id: self.relative_path.to_string_lossy().to_string(),
         }
     }
 }
 
-/// Recursively find all movies with metadata
+/// Recursively find all movies with metadata, printing the files which could not be loaded
 pub fn find_movies_in_dir<P: AsRef<path::Path>>(movie_path: P) -> Vec<MovieMetadata> {
-    let all_paths = find_movie_paths(movie_path.as_ref().to_path_buf());
+    let (movies, errors) = scan_movies_in_dir(movie_path);
+    for error in errors.iter() {
+        eprintln!("error: {}: {}", error.path.display(), error.reason);
+    }
+    movies
+}
+
+/// Recursively find all movies with metadata, along with the files which could not be loaded
+pub fn scan_movies_in_dir<P: AsRef<path::Path>>(
+    movie_path: P,
+) -> (Vec<MovieMetadata>, Vec<ScanError>) {
+    let mut errors = Vec::new();
+    let all_paths = find_movie_paths_reporting(movie_path.as_ref().to_path_buf(), &mut errors);
 
     let mut all_movie_metadata = Vec::new();
 
@@ -76,35 +129,52 @@ pub fn find_movies_in_dir<P: AsRef<path::Path>>(movie_path: P) -> Vec<MovieMetad
 
         let movie_path = movie_path.as_ref().to_path_buf().clone();
 
-        tpool.execute(
-            move || match find_movie_metadata(&movie_path, &specific_movie_path) {
-                Ok(v) => tx
-                    .send(v)
-                    .expect("I hope the movie metadata rx is receiving"),
-                Err(ref e) => eprintln!("error: {:?}", e),
-            },
-        );
+        tpool.execute(move || {
+            let result = find_movie_metadata(&movie_path, &specific_movie_path)
+                .map_err(|e| ScanError::new(specific_movie_path, e));
+            tx.send(result)
+                .expect("I hope the movie metadata rx is receiving")
+        });
     }
 
     drop(tx);
 
-    for movie_metadata in rx {
-        all_movie_metadata.push(movie_metadata);
+    for result in rx {
+        match result {
+            Ok(movie_metadata) => all_movie_metadata.push(movie_metadata),
+            Err(error) => errors.push(error),
+        }
     }
 
-    all_movie_metadata
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+    (all_movie_metadata, errors)
 }
 
 /// Recursively find the paths for all movie files in a directory
 pub fn find_movie_paths(current_path: path::PathBuf) -> Vec<path::PathBuf> {
+    let mut errors = Vec::new();
+    let paths = find_movie_paths_reporting(current_path, &mut errors);
+    for error in errors.iter() {
+        eprintln!("error: {}: {}", error.path.display(), error.reason);
+    }
+    paths
+}
+
+/// Recursively find the paths for all movie files in a directory, noting the directories
+/// which could not be read
+fn find_movie_paths_reporting(
+    current_path: path::PathBuf,
+    errors: &mut Vec<ScanError>,
+) -> Vec<path::PathBuf> {
     if current_path.is_file() {
-        let fileext = match current_path.extension() {
-            Some(v) => v.to_str().unwrap(),
+        let fileext = match current_path.extension().and_then(|x| x.to_str()) {
+            Some(v) => v,
             None => return Vec::new(),
         };
 
         return match fileext {
-            "m4v" | "mp4" => vec![current_path.clone()],
+            "m4v" | "mp4" | "mkv" | "webm" => vec![current_path.clone()],
             "jpg" | "json" => Vec::new(), // movies should have jpg box art and json metadata
             // files, but there's nothing to do with them here
             _ => {
@@ -115,10 +185,21 @@ pub fn find_movie_paths(current_path: path::PathBuf) -> Vec<path::PathBuf> {
     }
     if current_path.is_dir() {
         let mut ret = Vec::new();
-        for entry in current_path.read_dir().unwrap() {
-            let entry = entry.unwrap();
-            let mut children = find_movie_paths(entry.path());
-            ret.append(&mut children);
+        let entries = match current_path.read_dir() {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(ScanError::new(current_path, Error::Unreadable(e)));
+                return ret;
+            }
+        };
+        for entry in entries {
+            match entry {
+                Ok(entry) => {
+                    let mut children = find_movie_paths_reporting(entry.path(), errors);
+                    ret.append(&mut children);
+                }
+                Err(e) => errors.push(ScanError::new(current_path.clone(), Error::Unreadable(e))),
+            }
         }
         return ret;
     }
@@ -141,51 +222,52 @@ pub fn find_movie_metadata(
     );
     let fileext = path::Path::new(movie_path)
         .extension()
-        .unwrap()
-        .to_str()
-        .unwrap();
+        .and_then(|x| x.to_str());
 
     match fileext {
-        "m4v" | "mp4" => find_mp4_metadata(orig_scan_path, movie_path),
+        Some("m4v") | Some("mp4") => find_mp4_metadata(orig_scan_path, movie_path),
+        Some("mkv") | Some("webm") => find_matroska_metadata(orig_scan_path, movie_path),
         _ => Err(Error::NonMP4File),
     }
 }
 
-/// Extract the movie metadata for a "MPEG-4 Part 14" or "MP4" file
+/// Extract the movie metadata for a "MPEG-4 Part 14" or "MP4" file, whose title falls back to
+/// the metadata.json next to it and then to the file name when it has no title tag
 /// https://en.wikipedia.org/wiki/MP4_file_format
 /// https://en.wikipedia.org/wiki/Comparison_of_video_container_formats
 fn find_mp4_metadata(
     orig_scan_path: &path::PathBuf,
     movie_path: &path::PathBuf,
 ) -> Result<MovieMetadata, Error> {
-    let movie_file = fs::File::open(movie_path).unwrap();
-    let raw_metadata = mp4::read_mp4(movie_file).unwrap();
+    let movie_file = fs::File::open(movie_path).map_err(Error::Unreadable)?;
+    let raw_metadata = mp4::read_mp4(movie_file).map_err(Error::Mp4)?;
 
-    let udta = raw_metadata.moov.udta.unwrap();
     let mvhd = raw_metadata.moov.mvhd;
 
-    let title = match udta.meta.unwrap() {
-        mp4::MetaBox::Mdir { ilst } => {
-            let ilst = ilst.unwrap();
-            //eprintln!("ilst {:?}", ilst.items);
-            let title_data = &ilst.items.get(&mp4::MetadataKey::Title).unwrap().data;
-            if title_data.data_type == mp4::DataType::Text {
-                Ok(str::from_utf8(title_data.data.as_slice())
-                    .unwrap()
-                    .to_string())
-            } else {
-                Err(Error::NonTextTitle)
-            }
-        }
-        _ => Err(Error::NonMP4File),
-    }?;
+    let embedded_title = match raw_metadata
+        .moov
+        .udta
+        .ok_or(Error::NoUserData)
+        .and_then(read_mp4_title)
+    {
+        Ok(embedded_title) => Some(embedded_title),
+        Err(Error::NoUserData) | Err(Error::NoMetadata) | Err(Error::NoTitle) => None,
+        Err(e) => return Err(e),
+    };
 
-    let duration = time::Duration::from_millis(mvhd.duration);
+    let duration = mp4time::movie_duration(&mvhd);
 
-    let last_mod = fs::metadata(movie_path).unwrap().modified().unwrap();
+    let tracks = mediatracks::MediaTracks {
+        embedded: mediatracks::find_mp4_tracks(&raw_metadata.moov.traks),
+        sidecar_subtitles: mediatracks::find_sidecar_subtitles(movie_path),
+    };
+
+    let last_mod = read_last_modified(movie_path)?;
 
     let extra = find_extra_metadata(movie_path);
 
+    let title = fallback_title(embedded_title, extra.as_ref(), movie_path);
+
     Ok(MovieMetadata {
         title,
         path: movie_path.clone(),
@@ -197,9 +279,86 @@ fn find_mp4_metadata(
         last_modified: last_mod,
         duration,
         extra,
+        tracks,
+    })
+}
+
+/// Extract the movie metadata for a Matroska (or WebM) file, whose title falls back like an mp4's
+fn find_matroska_metadata(
+    orig_scan_path: &path::PathBuf,
+    movie_path: &path::PathBuf,
+) -> Result<MovieMetadata, Error> {
+    let info = matroska::read_matroska_info(movie_path).map_err(Error::Matroska)?;
+
+    let extra = find_extra_metadata(movie_path);
+
+    let title = fallback_title(info.title, extra.as_ref(), movie_path);
+
+    let last_mod = read_last_modified(movie_path)?;
+
+    Ok(MovieMetadata {
+        title,
+        path: movie_path.clone(),
+        relative_path: movie_path
+            .to_path_buf()
+            .strip_prefix(orig_scan_path)
+            .unwrap()
+            .to_path_buf(),
+        last_modified: last_mod,
+        duration: info.duration.unwrap_or_default(),
+        extra,
+        tracks: mediatracks::MediaTracks {
+            embedded: Vec::new(),
+            sidecar_subtitles: mediatracks::find_sidecar_subtitles(movie_path),
+        },
     })
 }
 
+/// The embedded title, or else the metadata.json title, or else the file name
+fn fallback_title(
+    embedded_title: Option<String>,
+    extra: Option<&ExtraMetadata>,
+    movie_path: &path::Path,
+) -> String {
+    match (embedded_title, extra.and_then(|x| x.title.clone())) {
+        (Some(embedded_title), _) => embedded_title,
+        (None, Some(sidecar_title)) => sidecar_title,
+        (None, None) => movie_path
+            .file_stem()
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+    }
+}
+
+/// Read the text title out of an mp4's udta box
+fn read_mp4_title(udta: mp4::UdtaBox) -> Result<String, Error> {
+    match udta.meta.ok_or(Error::NoMetadata)? {
+        mp4::MetaBox::Mdir { ilst } => {
+            let ilst = ilst.ok_or(Error::NoMetadata)?;
+            let title_data = &ilst
+                .items
+                .get(&mp4::MetadataKey::Title)
+                .ok_or(Error::NoTitle)?
+                .data;
+            if title_data.data_type == mp4::DataType::Text {
+                str::from_utf8(title_data.data.as_slice())
+                    .map(|x| x.to_string())
+                    .map_err(|_| Error::NonTextTitle)
+            } else {
+                Err(Error::NonTextTitle)
+            }
+        }
+        _ => Err(Error::NoMetadata),
+    }
+}
+
+fn read_last_modified(movie_path: &path::PathBuf) -> Result<time::SystemTime, Error> {
+    fs::metadata(movie_path)
+        .and_then(|x| x.modified())
+        .map_err(Error::Unreadable)
+}
+
 fn find_extra_metadata(movie_path: &path::PathBuf) -> Option<ExtraMetadata> {
     let parent_dir = movie_path.parent().unwrap();
 
@@ -236,20 +395,33 @@ mod slash_date {
 
     const FORMAT: &str = "%Y/%m/%d";
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        let date = NaiveDate::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)?;
-        Ok(date)
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) => {
+                let date =
+                    NaiveDate::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)?;
+                Ok(Some(date))
+            }
+            None => Ok(None),
+        }
     }
 
-    pub fn serialize<'s, S>(naive_date: &'s NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<'s, S>(
+        naive_date: &'s Option<NaiveDate>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let date = NaiveDate::format(&naive_date, FORMAT).to_string();
-        serializer.serialize_str(date.as_str())
+        match naive_date {
+            Some(naive_date) => {
+                let date = NaiveDate::format(naive_date, FORMAT).to_string();
+                serializer.serialize_str(date.as_str())
+            }
+            None => serializer.serialize_none(),
+        }
     }
 }
@@ -0,0 +1,437 @@
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Release, DEFAULT_BASE_URL};
+
+pub const DEFAULT_USER_AGENT: &str = "musiqapp/0.9.0 ( quintenpalmer@protonmail.com )";
+
+/// MusicBrainz asks for no more than one request per second
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 1.0;
+
+/// How many times a request answered with a 503 is tried again before giving up
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Used when a 503 comes without a Retry-After we can read
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(2);
+
+/// The longest a Retry-After is waited for, however long MusicBrainz asks for
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub enum Error {
+    /// MusicBrainz kept answering 503 after all of the retries
+    ServiceUnavailable,
+    /// Any other non-success status, with the response body
+    Status(u16, String),
+    /// The request never got a response (dns, connection, tls, ...)
+    Transport(String),
+    /// A rate limit which could never let a request through
+    InvalidRateLimit(String),
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ServiceUnavailable => write!(f, "musicbrainz is unavailable (503)"),
+            Error::Status(status, body) => write!(f, "musicbrainz returned {}: {}", status, body),
+            Error::Transport(e) => write!(f, "could not reach musicbrainz: {}", e),
+            Error::InvalidRateLimit(e) => write!(f, "invalid rate limit: {}", e),
+            Error::Io(e) => write!(f, "could not read musicbrainz response: {}", e),
+            Error::Json(e) => write!(f, "could not parse musicbrainz response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+/// A token bucket, holding up to `capacity` requests which refill at `per_second`
+pub struct RateLimiter {
+    capacity: f64,
+    per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// The rate has to be positive and the bucket has to hold at least one request
+    pub fn new(per_second: f64, capacity: f64) -> Result<Self, Error> {
+        if !(per_second > 0.0 && per_second.is_finite()) {
+            return Err(Error::InvalidRateLimit(format!(
+                "{} requests per second",
+                per_second
+            )));
+        }
+        if !(capacity >= 1.0 && capacity.is_finite()) {
+            return Err(Error::InvalidRateLimit(format!(
+                "a burst of {} requests",
+                capacity
+            )));
+        }
+        Ok(RateLimiter {
+            capacity,
+            per_second,
+            tokens: capacity,
+            last_refill: Instant::now(),
+        })
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// How long to wait before a token is free, taking it when there is one
+    pub fn try_acquire(&mut self) -> Option<Duration> {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+/// Talks to MusicBrainz, spacing requests out and retrying when it is busy
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    user_agent: String,
+    max_retries: u32,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
+
+impl Client {
+    /// A client for `MUSICBRAINZ_BASE_URL`, or musicbrainz.org when it is not set
+    pub fn new() -> Self {
+        Client {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: std::env::var("MUSICBRAINZ_BASE_URL").unwrap_or(DEFAULT_BASE_URL.to_string()),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            limiter: Mutex::new(RateLimiter::new(DEFAULT_REQUESTS_PER_SECOND, 1.0).unwrap()),
+        }
+    }
+
+    pub fn with_base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn with_rate_limit(self, per_second: f64, burst: f64) -> Result<Self, Error> {
+        Ok(Client {
+            limiter: Mutex::new(RateLimiter::new(per_second, burst)?),
+            ..self
+        })
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn query_for_artist_raw(&self, query: String) -> Result<String, Error> {
+        self.get_raw("artist", &[("query", query.as_str()), ("limit", "5")])
+    }
+
+    /// Search for release groups (the albums, across all of their releases) by an artist
+    pub fn query_for_release_group_raw(
+        &self,
+        artist_name: String,
+        album_name: String,
+    ) -> Result<String, Error> {
+        let query = format!(
+            "releasegroup:{} AND artist:{}",
+            lucene_phrase(&album_name),
+            lucene_phrase(&artist_name)
+        );
+        self.get_raw(
+            "release-group",
+            &[("query", query.as_str()), ("limit", "5")],
+        )
+    }
+
+    /// Search for specific releases of an album by an artist
+    pub fn query_for_release_raw(
+        &self,
+        artist_name: String,
+        album_name: String,
+    ) -> Result<String, Error> {
+        let query = format!(
+            "release:{} AND artist:{}",
+            lucene_phrase(&album_name),
+            lucene_phrase(&artist_name)
+        );
+        self.get_raw("release", &[("query", query.as_str()), ("limit", "5")])
+    }
+
+    /// Search for recordings (tracks) by an artist
+    pub fn query_for_recording_raw(
+        &self,
+        artist_name: String,
+        track_title: String,
+    ) -> Result<String, Error> {
+        let query = format!(
+            "recording:{} AND artist:{}",
+            lucene_phrase(&track_title),
+            lucene_phrase(&artist_name)
+        );
+        self.get_raw("recording", &[("query", query.as_str()), ("limit", "5")])
+    }
+
+    /// Look up a release with its labels, track list and tags
+    pub fn lookup_release_raw(&self, release_id: String) -> Result<String, Error> {
+        self.get_raw(
+            format!("release/{}", release_id).as_str(),
+            &[(
+                "inc",
+                "artist-credits+labels+recordings+release-groups+tags",
+            )],
+        )
+    }
+
+    pub fn lookup_release(&self, release_id: String) -> Result<Release, Error> {
+        Ok(serde_json::from_str(
+            self.lookup_release_raw(release_id)?.as_str(),
+        )?)
+    }
+
+    pub fn lookup_release_group_raw(&self, release_group_id: String) -> Result<String, Error> {
+        self.get_raw(
+            format!("release-group/{}", release_group_id).as_str(),
+            &[("inc", "artist-credits+releases+tags")],
+        )
+    }
+
+    pub fn lookup_recording_raw(&self, recording_id: String) -> Result<String, Error> {
+        self.get_raw(
+            format!("recording/{}", recording_id).as_str(),
+            &[("inc", "artist-credits+releases+tags")],
+        )
+    }
+
+    fn get_raw(&self, path: &str, query_pairs: &[(&str, &str)]) -> Result<String, Error> {
+        let url = format!("{}/{}", self.base_url, path);
+
+        let mut attempt = 0;
+        loop {
+            self.wait_for_turn();
+
+            let request = self
+                .agent
+                .get(url.as_str())
+                .set("Accept", "application/json")
+                .set("User-Agent", self.user_agent.as_str())
+                .query_pairs(query_pairs.iter().cloned());
+
+            match request.call() {
+                Ok(resp) => return Ok(io::read_to_string(resp.into_reader())?),
+                Err(ureq::Error::Status(503, resp)) => {
+                    if attempt >= self.max_retries {
+                        return Err(Error::ServiceUnavailable);
+                    }
+                    attempt += 1;
+                    let retry_after = resp
+                        .header("Retry-After")
+                        .and_then(parse_retry_after)
+                        .unwrap_or(DEFAULT_RETRY_AFTER);
+                    thread::sleep(retry_after);
+                }
+                Err(ureq::Error::Status(status, resp)) => {
+                    return Err(Error::Status(
+                        status,
+                        resp.into_string().unwrap_or_default(),
+                    ))
+                }
+                Err(ureq::Error::Transport(e)) => return Err(Error::Transport(e.to_string())),
+            }
+        }
+    }
+
+    fn wait_for_turn(&self) {
+        loop {
+            let wait = self.limiter.lock().unwrap().try_acquire();
+            match wait {
+                Some(duration) => thread::sleep(duration),
+                None => return,
+            }
+        }
+    }
+}
+
+/// Only the delay-seconds form is read, an http date falls back to the default wait
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|seconds| Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+}
+
+/// Quote a search term so that it is matched as a whole, escaping what would end the quote
+fn lucene_phrase(term: &str) -> String {
+    format!("\"{}\"", term.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    use super::*;
+
+    /// Answer one request per connection with each of `responses` in turn,
+    /// sending back when each request came in
+    fn serve(responses: Vec<String>) -> (String, mpsc::Receiver<Instant>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for response in responses.into_iter() {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let _ = tx.send(Instant::now());
+                let mut stream = reader.into_inner();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (base_url, rx)
+    }
+
+    fn response(status: &str, headers: &[&str], body: &str) -> String {
+        let mut response = format!("HTTP/1.1 {}\r\nConnection: close\r\n", status);
+        for header in headers.iter() {
+            response.push_str(header);
+            response.push_str("\r\n");
+        }
+        response.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+        response
+    }
+
+    fn test_client(base_url: &str) -> Client {
+        Client::new()
+            .with_base_url(base_url)
+            .with_rate_limit(1000.0, 1.0)
+            .unwrap()
+    }
+
+    #[test]
+    fn retries_after_503_then_succeeds() {
+        let (base_url, requests) = serve(vec![
+            response("503 Service Unavailable", &["Retry-After: 1"], ""),
+            response("200 OK", &[], "{}"),
+        ]);
+
+        let result = test_client(&base_url).query_for_artist_raw("a".to_string());
+
+        assert_eq!(result.unwrap(), "{}");
+        let first = requests.recv().unwrap();
+        let second = requests.recv().unwrap();
+        assert!(second.duration_since(first) >= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let unavailable = response("503 Service Unavailable", &["Retry-After: 0"], "");
+        let (base_url, requests) = serve(vec![unavailable; 3]);
+
+        let result = test_client(&base_url)
+            .with_max_retries(2)
+            .query_for_artist_raw("a".to_string());
+
+        assert!(matches!(result, Err(Error::ServiceUnavailable)));
+        assert_eq!(requests.iter().count(), 3);
+    }
+
+    #[test]
+    fn other_statuses_are_errors_with_the_body() {
+        let (base_url, _requests) = serve(vec![response("404 Not Found", &[], "no such release")]);
+
+        let result = test_client(&base_url).lookup_release_raw("x".to_string());
+
+        match result {
+            Err(Error::Status(404, body)) => assert_eq!(body, "no such release"),
+            other => panic!("expected a 404 status error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn spaces_requests_by_the_rate_limit() {
+        let (base_url, requests) = serve(vec![response("200 OK", &[], "{}"); 3]);
+        let client = Client::new()
+            .with_base_url(base_url)
+            .with_rate_limit(10.0, 1.0)
+            .unwrap();
+
+        for _ in 0..3 {
+            client.query_for_artist_raw("a".to_string()).unwrap();
+        }
+
+        let times = requests.iter().collect::<Vec<Instant>>();
+        assert_eq!(times.len(), 3);
+        for pair in times.windows(2) {
+            assert!(pair[1].duration_since(pair[0]) >= Duration::from_millis(90));
+        }
+    }
+
+    #[test]
+    fn rejects_rate_limits_that_never_let_a_request_through() {
+        assert!(Client::new().with_rate_limit(0.0, 1.0).is_err());
+        assert!(Client::new().with_rate_limit(-1.0, 1.0).is_err());
+        assert!(Client::new().with_rate_limit(f64::NAN, 1.0).is_err());
+        assert!(Client::new().with_rate_limit(1.0, 0.5).is_err());
+        assert!(Client::new().with_rate_limit(1.0, 1.0).is_ok());
+    }
+
+    #[test]
+    fn clamps_retry_after() {
+        assert_eq!(parse_retry_after("5"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after("99999999"), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+}
@@ -1,15 +1,17 @@
-use std::io;
+mod client;
 
 use serde_json;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+pub use client::{Client, Error, RateLimiter};
+
+#[derive(Debug, Deserialize)]
 pub struct ArtistListResult {
     pub artists: Vec<Artist>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Artist {
     pub id: String,
     pub score: i32,
@@ -21,7 +23,7 @@ pub struct Artist {
     pub tags: Option<Vec<ArtistTag>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ArtistNameAlias {
     pub name: String,
     #[serde(alias = "sort-name")]
@@ -30,7 +32,7 @@ pub struct ArtistNameAlias {
     pub type_: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ArtistTag {
     pub count: i32,
     pub name: String,
@@ -46,20 +48,217 @@ impl ArtistListResult {
     }
 }
 
-pub fn query_for_artist_raw(query: String) -> String {
-    let mut request = ureq::get("https://musicbrainz.org/ws/2/artist");
-    request = request.set("Accept", "application/json");
-    request = request.set(
-        "User-Agent",
-        "musiqapp/0.9.0 ( quintenpalmer@protonmail.com )",
-    );
-    request = request.query_pairs([("query", query.as_str()), ("limit", "5")]);
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tag {
+    pub count: i32,
+    pub name: String,
+}
+
+/// One artist in the (possibly several) artists a release or recording is credited to
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArtistCredit {
+    pub name: String,
+    pub joinphrase: Option<String>,
+    pub artist: ArtistReference,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArtistReference {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseGroupListResult {
+    #[serde(alias = "release-groups")]
+    pub release_groups: Vec<ReleaseGroup>,
+}
+
+/// An album across all of its releases (editions, reissues, regional releases)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReleaseGroup {
+    pub id: String,
+    pub score: Option<i32>,
+    pub title: String,
+    #[serde(alias = "primary-type")]
+    pub primary_type: Option<String>,
+    #[serde(alias = "first-release-date")]
+    pub first_release_date: Option<String>,
+    #[serde(alias = "artist-credit")]
+    pub artist_credit: Option<Vec<ArtistCredit>>,
+    pub releases: Option<Vec<ReleaseReference>>,
+    pub tags: Option<Vec<Tag>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReleaseGroupReference {
+    pub id: String,
+    pub title: String,
+    #[serde(alias = "primary-type")]
+    pub primary_type: Option<String>,
+    #[serde(alias = "first-release-date")]
+    pub first_release_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseListResult {
+    pub releases: Vec<Release>,
+}
+
+/// A specific release of an album, with its media and tracks when it was looked up
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Release {
+    pub id: String,
+    pub score: Option<i32>,
+    pub title: String,
+    pub status: Option<String>,
+    pub date: Option<String>,
+    pub country: Option<String>,
+    #[serde(alias = "artist-credit")]
+    pub artist_credit: Option<Vec<ArtistCredit>>,
+    #[serde(alias = "release-group")]
+    pub release_group: Option<ReleaseGroupReference>,
+    #[serde(alias = "label-info")]
+    pub label_info: Option<Vec<LabelInfo>>,
+    pub media: Option<Vec<Medium>>,
+    pub tags: Option<Vec<Tag>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReleaseReference {
+    pub id: String,
+    pub title: String,
+    pub status: Option<String>,
+    pub date: Option<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LabelInfo {
+    #[serde(alias = "catalog-number")]
+    pub catalog_number: Option<String>,
+    pub label: Option<Label>,
+}
 
-    let resp = request.call().unwrap();
-    //eprintln!("{}", resp.status());
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Label {
+    pub id: String,
+    pub name: String,
+}
 
-    let resp_body = io::read_to_string(resp.into_reader()).unwrap();
-    //println!("{}", resp_body);
+/// A disc (or other medium) of a release
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Medium {
+    pub position: Option<u32>,
+    pub format: Option<String>,
+    #[serde(alias = "track-count")]
+    pub track_count: u32,
+    pub tracks: Option<Vec<Track>>,
+}
 
-    resp_body
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Track {
+    pub id: String,
+    pub position: u32,
+    pub number: String,
+    pub title: String,
+    /// In milliseconds
+    pub length: Option<u64>,
+    pub recording: Option<RecordingReference>,
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordingReference {
+    pub id: String,
+    pub title: String,
+    pub length: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordingListResult {
+    pub recordings: Vec<Recording>,
+}
+
+/// A recorded track, which can appear on many releases
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recording {
+    pub id: String,
+    pub score: Option<i32>,
+    pub title: String,
+    /// In milliseconds
+    pub length: Option<u64>,
+    #[serde(alias = "first-release-date")]
+    pub first_release_date: Option<String>,
+    #[serde(alias = "artist-credit")]
+    pub artist_credit: Option<Vec<ArtistCredit>>,
+    pub releases: Option<Vec<ReleaseReference>>,
+    pub tags: Option<Vec<Tag>>,
+}
+
+impl ReleaseGroupListResult {
+    pub fn from_json(json_str: String) -> ReleaseGroupListResult {
+        let mut result: ReleaseGroupListResult = serde_json::from_str(json_str.as_str()).unwrap();
+
+        result.release_groups.sort_by_key(|x| x.score);
+
+        result
+    }
+}
+
+impl ReleaseListResult {
+    pub fn from_json(json_str: String) -> ReleaseListResult {
+        let mut result: ReleaseListResult = serde_json::from_str(json_str.as_str()).unwrap();
+
+        result.releases.sort_by_key(|x| x.score);
+
+        result
+    }
+}
+
+impl RecordingListResult {
+    pub fn from_json(json_str: String) -> RecordingListResult {
+        let mut result: RecordingListResult = serde_json::from_str(json_str.as_str()).unwrap();
+
+        result.recordings.sort_by_key(|x| x.score);
+
+        result
+    }
+}
+
+impl ReleaseGroup {
+    pub fn from_json(json_str: String) -> ReleaseGroup {
+        serde_json::from_str(json_str.as_str()).unwrap()
+    }
+}
+
+impl Release {
+    pub fn from_json(json_str: String) -> Release {
+        serde_json::from_str(json_str.as_str()).unwrap()
+    }
+
+    pub fn artist_credit_name(&self) -> Option<String> {
+        self.artist_credit.as_deref().map(fold_artist_credit)
+    }
+}
+
+impl Recording {
+    pub fn from_json(json_str: String) -> Recording {
+        serde_json::from_str(json_str.as_str()).unwrap()
+    }
+}
+
+/// The credited artists as MusicBrainz writes them, e.g. "A feat. B"
+pub fn fold_artist_credit(credits: &[ArtistCredit]) -> String {
+    credits.iter().fold("".to_string(), |total, credit| {
+        format!(
+            "{}{}{}",
+            total,
+            credit.name,
+            credit.joinphrase.clone().unwrap_or_default()
+        )
+    })
+}
+
+/// Where MusicBrainz is reached, which `MUSICBRAINZ_BASE_URL` can point somewhere else,
+/// e.g. at a local stand-in
+pub const DEFAULT_BASE_URL: &str = "https://musicbrainz.org/ws/2";
@@ -0,0 +1,143 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use musiqcore::datastore::jsonbacked::{common, tracker};
+use musiqcore::datastore::localfs;
+
+use crate::model::{FullTrackMetadata, TrackUniqueIdentifier};
+
+const MAX_CLIENT_ID_LEN: usize = 64;
+
+/// Who a client is, which names the files its data is kept in. Browsers pick their own ids,
+/// and Subsonic clients go by their user, each under its own prefix so the two never collide.
+#[derive(Debug, Clone)]
+pub struct ClientID {
+    inner: String,
+}
+
+impl ClientID {
+    /// The id a browser picks for itself
+    pub fn parse(raw: &str) -> Option<Self> {
+        ClientID::with_prefix("browser", raw)
+    }
+
+    /// The id plays from a Subsonic client are recorded under, one per user
+    pub fn subsonic(username: &str) -> Option<Self> {
+        ClientID::with_prefix("subsonic", username)
+    }
+
+    /// Only plain ids are allowed, since they end up in file names
+    fn with_prefix(prefix: &str, raw: &str) -> Option<Self> {
+        let valid = !raw.is_empty()
+            && raw.len() <= MAX_CLIENT_ID_LEN
+            && raw
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        match valid {
+            true => Some(ClientID {
+                inner: format!("{}-{}", prefix, raw),
+            }),
+            false => None,
+        }
+    }
+}
+
+/// Points at a track by the same ids used in the webserver paths, as strings
+/// since they do not fit in a javascript number
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrackKey {
+    pub artist_id: String,
+    pub album_id: String,
+    pub disc: u64,
+    pub track: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RawClientQueue {
+    pub current: Option<TrackKey>,
+    pub queue: Vec<TrackKey>,
+    pub history: Vec<TrackKey>,
+}
+
+/// Listens go in `data/tracker/`, next to the app's own tracker files, so the
+/// app picks them up (when `allowed_tracker_files` is set, it has to list them)
+pub fn record_listen(
+    app_data_path: &PathBuf,
+    client_id: &ClientID,
+    track: &FullTrackMetadata,
+    date_time: DateTime<Local>,
+) -> Result<(), String> {
+    let (raw_tracker, tracker_path): (tracker::RawTrackedPayload, PathBuf) =
+        common::bootstrap_raw_data(
+            app_data_path,
+            vec![
+                "data",
+                "tracker",
+                format!("webserver-{}.json", client_id.inner).as_str(),
+            ],
+        );
+
+    let mut tracks = raw_tracker.to_btree_map();
+    tracks
+        .entry(TrackUniqueIdentifier::from_track(track))
+        .or_insert(Vec::new())
+        .push(date_time);
+
+    write_json(
+        &tracker_path,
+        &tracker::RawTrackedPayload::from_btree_map(&tracks),
+    )
+}
+
+pub fn get_queue(app_data_path: &PathBuf, client_id: &ClientID) -> RawClientQueue {
+    common::maybe_get_existing_raw_data(&queue_path(app_data_path, client_id)).unwrap_or_default()
+}
+
+pub fn set_queue(
+    app_data_path: &PathBuf,
+    client_id: &ClientID,
+    queue: &RawClientQueue,
+) -> Result<(), String> {
+    write_json(&queue_path(app_data_path, client_id), queue)
+}
+
+fn queue_path(app_data_path: &PathBuf, client_id: &ClientID) -> PathBuf {
+    localfs::build_tree_for_file(
+        app_data_path,
+        vec![
+            "data",
+            "webserver",
+            "queues",
+            format!("{}.json", client_id.inner).as_str(),
+        ],
+    )
+}
+
+fn write_json<T: Serialize>(path: &PathBuf, value: &T) -> Result<(), String> {
+    let file =
+        fs::File::create(path).map_err(|e| format!("could not create file {:?}: {}", path, e))?;
+    serde_json::to_writer(io::BufWriter::new(file), value)
+        .map_err(|e| format!("could not write file {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browser_ids_cannot_take_a_subsonic_users_files() {
+        let browser = ClientID::parse("subsonic-alice").unwrap();
+        let subsonic = ClientID::subsonic("alice").unwrap();
+        assert_ne!(browser.inner, subsonic.inner);
+    }
+
+    #[test]
+    fn ids_outside_file_name_characters_are_refused() {
+        assert!(ClientID::parse("../alice").is_none());
+        assert!(ClientID::subsonic("").is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Settings that only the webserver needs, kept in `webserver.json`
+/// next to the app's `config.json`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct WebserverConfig {
+    /// The address to listen on, only this machine (`127.0.0.1`) by default
+    pub bind_address: Option<IpAddr>,
+    /// Serve https instead of http
+    pub tls: Option<TlsConfig>,
+    /// When any are set up, every request has to come from one of these users
+    #[serde(default)]
+    pub users: Vec<UserAccount>,
+    /// Serve other machines with no users set up, letting anyone on the network in
+    #[serde(default)]
+    pub allow_anonymous: bool,
+    #[serde(default)]
+    pub transcoding: TranscodingConfig,
+    /// Where to find movies, instead of the app's `movie_path`
+    pub movie_path: Option<PathBuf>,
+    /// Where to find shows, instead of the app's `show_path`
+    pub show_path: Option<PathBuf>,
+}
+
+/// Subsonic token authentication hashes the password with a salt, so it is kept as is
+#[derive(Deserialize, Debug, Clone)]
+pub struct UserAccount {
+    pub username: String,
+    pub password: String,
+    /// Accepted as `Authorization: Bearer <token>`, for clients that should not keep the password
+    #[serde(default)]
+    pub tokens: Vec<String>,
+}
+
+/// PEM files for the certificate chain and its private key
+#[derive(Deserialize, Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct TranscodingConfig {
+    /// Program and arguments to run instead of ffmpeg, where `{input}`, `{output}`,
+    /// `{bitrate}` (in kbit/s) and `{format}` (the output extension) are filled in
+    pub encoder_command: Option<Vec<String>>,
+    /// Where transcoded files are kept, `cache/transcoded/` in the app data by default
+    pub cache_path: Option<PathBuf>,
+    /// How many MB of transcoded files are kept before the oldest are removed, 2048 by default
+    pub cache_max_mb: Option<u64>,
+    /// Serve files from the app's `compressed_library_path` instead of transcoding,
+    /// when one is there in the requested format
+    #[serde(default)]
+    pub use_compressed_library: bool,
+}
+
+/// The settings the webserver shares with the app's `config.json`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AppConfigPaths {
+    pub movie_path: Option<PathBuf>,
+    pub show_path: Option<PathBuf>,
+    pub compressed_library_path: Option<PathBuf>,
+}
+
+impl WebserverConfig {
+    pub fn default_path() -> PathBuf {
+        musiqcore::model::functions::get_default_config_path().with_file_name("webserver.json")
+    }
+
+    /// A missing config file means nothing beyond the defaults is configured
+    pub fn load(path: &Path) -> Self {
+        match fs::File::open(path) {
+            Ok(file) => serde_json::from_reader(io::BufReader::new(file)).unwrap(),
+            Err(_) => {
+                println!("no webserver config at {:?}, using defaults", path);
+                WebserverConfig::default()
+            }
+        }
+    }
+
+    pub fn get_bind_address(&self) -> IpAddr {
+        self.bind_address
+            .unwrap_or(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+    }
+
+    pub fn get_user(&self, username: &str) -> Option<&UserAccount> {
+        self.users.iter().find(|user| user.username == username)
+    }
+
+    pub fn get_user_by_token(&self, token: &str) -> Option<&UserAccount> {
+        self.users
+            .iter()
+            .find(|user| user.tokens.iter().any(|user_token| user_token == token))
+    }
+}
+
+/// Read the paths the webserver needs from the app config, without requiring
+/// the rest of it to be there
+pub fn read_app_config_paths() -> AppConfigPaths {
+    let app_config_path = musiqcore::model::functions::get_default_config_path();
+    let file = match fs::File::open(&app_config_path) {
+        Ok(file) => file,
+        Err(e) => {
+            println!("could not open app config {:?}: {}", app_config_path, e);
+            return AppConfigPaths::default();
+        }
+    };
+    match serde_json::from_reader(io::BufReader::new(file)) {
+        Ok(paths) => paths,
+        Err(e) => {
+            println!("could not read app config {:?}: {}", app_config_path, e);
+            AppConfigPaths::default()
+        }
+    }
+}
@@ -1,8 +1,26 @@
 pub fn audio_content_type_from_ext<S: ToString>(ext: S) -> &'static str {
-    match ext.to_string().as_str() {
+    match ext.to_string().to_lowercase().as_str() {
         "flac" => "audio/flac",
         "mp3" => "audio/mp3",
         "m4a" => "audio/mp4",
-        ext => panic!("unknown media file requested: {}", ext),
+        "opus" => "audio/ogg; codecs=opus",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        ext => {
+            println!("unknown audio extension: {}", ext);
+            "application/octet-stream"
+        }
+    }
+}
+
+pub fn video_content_type_from_ext<S: ToString>(ext: S) -> &'static str {
+    match ext.to_string().to_lowercase().as_str() {
+        "mp4" | "m4v" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        ext => {
+            println!("unknown video extension: {}", ext);
+            "application/octet-stream"
+        }
     }
 }
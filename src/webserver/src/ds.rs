@@ -1,21 +1,72 @@
-use std::fs;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
+use chrono::{DateTime, Local};
+
+use musiqcore::datastore::jsonbacked::showtracker::ShowTracker;
+use musiqcore::datastore::localfs;
+
+use crate::clients;
+use crate::config;
 use crate::model::{
-    AlbumInfo, ArtistInfo, FullTrackMetadata, RawLibrary, SortedAlbumDiscs, SortedArtistAlbums,
-    SortedDiscTracks, ID,
+    AlbumInfo, ArtistInfo, FullTrackMetadata, MovieMetadata, RawLibrary, Show, ShowMetadata, Shows,
+    SortedAlbumDiscs, SortedArtistAlbums, SortedDiscTracks, ID,
 };
+use crate::playlists;
+use crate::transcode;
 
 #[derive(Clone)]
 pub struct Datastore {
     library: RawLibrary,
+    movies: Arc<Vec<MovieMetadata>>,
+    shows: Arc<Option<Shows>>,
+    app_data_path: PathBuf,
+    config: Arc<config::WebserverConfig>,
+    compressed_library_path: Option<PathBuf>,
+    client_data_lock: Arc<Mutex<()>>,
+    transcoder: transcode::Transcoder,
 }
 
 impl Datastore {
-    pub fn new(lib_path: PathBuf) -> Self {
+    pub fn new(lib_path: PathBuf, app_data_path: PathBuf, config: config::WebserverConfig) -> Self {
         let library = RawLibrary::new(lib_path).unwrap();
+        let app_config_paths = config::read_app_config_paths();
+
+        let mut movies = match config.movie_path.clone().or(app_config_paths.movie_path) {
+            Some(movie_path) => musiqlibrary::video::find_movies_in_dir(movie_path),
+            None => Vec::new(),
+        };
+        movies.sort_by(|a, b| a.title.cmp(&b.title));
+
+        let shows = config
+            .show_path
+            .clone()
+            .or(app_config_paths.show_path)
+            .map(|show_path| Shows::from_vec(&musiqlibrary::shows::find_shows_in_dir(show_path)));
 
-        Datastore { library: library }
+        let compressed_library_path = match config.transcoding.use_compressed_library {
+            true => app_config_paths.compressed_library_path,
+            false => None,
+        };
+
+        let transcode_cache_path = match config.transcoding.cache_path {
+            Some(ref cache_path) => cache_path.clone(),
+            None => localfs::build_tree_for_dirs(&app_data_path, vec!["cache", "transcoded"]),
+        };
+        let transcoder =
+            transcode::Transcoder::new(config.transcoding.clone(), transcode_cache_path);
+
+        Datastore {
+            library,
+            movies: Arc::new(movies),
+            shows: Arc::new(shows),
+            app_data_path,
+            config: Arc::new(config),
+            compressed_library_path,
+            client_data_lock: Arc::new(Mutex::new(())),
+            transcoder,
+        }
     }
 
     pub fn list_artists(&self) -> Vec<ArtistInfo> {
@@ -156,96 +207,177 @@ impl Datastore {
         artist_info
     }
 
-    pub fn get_artist_from_id(&self, artist_id: ID) -> ArtistInfo {
-        ArtistInfo {
-            artist_id: artist_id.clone(),
-            artist_name: self
-                .library
-                .artists
-                .get(&artist_id)
-                .unwrap()
-                .artist_info
-                .artist_name
-                .to_string(),
-        }
+    pub fn find_artist(&self, artist_id: ID) -> Option<ArtistInfo> {
+        self.library
+            .artists
+            .get(&artist_id)
+            .map(|artist| artist.artist_info.clone())
     }
 
-    pub fn get_artist_album_from_id(&self, artist_id: ID, album_id: ID) -> AlbumInfo {
+    pub fn find_artist_album(&self, artist_id: ID, album_id: ID) -> Option<AlbumInfo> {
         self.library
             .artists
-            .get(&artist_id)
-            .unwrap()
+            .get(&artist_id)?
             .albums
             .get(&album_id)
-            .unwrap()
-            .album_info
-            .clone()
+            .map(|album| album.album_info.clone())
     }
 
-    pub fn get_artist_album_track_name_from_id(
+    pub fn find_artist_album_track(
         &self,
         artist_id: ID,
         album_id: ID,
         disc_no: u64,
         track_no: u64,
-    ) -> FullTrackMetadata {
+    ) -> Option<FullTrackMetadata> {
         self.library
             .artists
-            .get(&artist_id)
-            .unwrap()
+            .get(&artist_id)?
             .albums
-            .get(&album_id)
-            .unwrap()
+            .get(&album_id)?
             .discs
-            .get(&disc_no)
-            .unwrap()
+            .get(&disc_no)?
             .tracks
             .get(&track_no)
-            .unwrap()
-            .clone()
+            .cloned()
+    }
+
+    pub fn get_artist_from_id(&self, artist_id: ID) -> ArtistInfo {
+        self.find_artist(artist_id).unwrap()
     }
 
-    pub fn get_artist_album_track_audio_from_id(
+    pub fn get_artist_album_from_id(&self, artist_id: ID, album_id: ID) -> AlbumInfo {
+        self.find_artist_album(artist_id, album_id).unwrap()
+    }
+
+    pub fn get_artist_album_track_name_from_id(
         &self,
         artist_id: ID,
         album_id: ID,
         disc_no: u64,
         track_no: u64,
-    ) -> Vec<u8> {
-        fs::read(
-            self.library
-                .artists
-                .get(&artist_id)
-                .unwrap()
-                .albums
-                .get(&album_id)
-                .unwrap()
-                .discs
-                .get(&disc_no)
-                .unwrap()
-                .tracks
-                .get(&track_no)
-                .unwrap()
-                .path
-                .clone(),
-        )
-        .unwrap()
-    }
-
-    pub fn get_artist_album_cover(&self, artist_id: ID, album_id: ID) -> Vec<u8> {
-        fs::read(
-            self.library
-                .artists
-                .get(&artist_id)
-                .unwrap()
-                .albums
-                .get(&album_id)
-                .unwrap()
-                .album_info
-                .path
-                .clone()
-                .join("cover.jpg"),
-        )
-        .unwrap()
+    ) -> FullTrackMetadata {
+        self.find_artist_album_track(artist_id, album_id, disc_no, track_no)
+            .unwrap()
+    }
+
+    pub fn get_artist_album_cover_path(&self, artist_id: ID, album_id: ID) -> PathBuf {
+        self.get_artist_album_from_id(artist_id, album_id)
+            .path
+            .join("cover.jpg")
+    }
+
+    pub fn record_client_listen(
+        &self,
+        client_id: &clients::ClientID,
+        track: &FullTrackMetadata,
+        date_time: DateTime<Local>,
+    ) -> Result<(), String> {
+        let _guard = self.client_data_lock.lock().unwrap();
+        clients::record_listen(&self.app_data_path, client_id, track, date_time)
+    }
+
+    pub fn get_client_queue(&self, client_id: &clients::ClientID) -> clients::RawClientQueue {
+        let _guard = self.client_data_lock.lock().unwrap();
+        clients::get_queue(&self.app_data_path, client_id)
+    }
+
+    pub fn set_client_queue(
+        &self,
+        client_id: &clients::ClientID,
+        queue: &clients::RawClientQueue,
+    ) -> Result<(), String> {
+        let _guard = self.client_data_lock.lock().unwrap();
+        clients::set_queue(&self.app_data_path, client_id, queue)
+    }
+
+    pub fn list_playlists(&self) -> playlists::Playlists {
+        playlists::list_playlists(&self.app_data_path)
+    }
+
+    /// The track's file in the profile's format, from the compressed library when
+    /// allowed and possible, otherwise transcoded
+    pub async fn get_transcoded_audio(
+        &self,
+        track: &FullTrackMetadata,
+        profile: &transcode::TranscodeProfile,
+    ) -> Result<(PathBuf, SystemTime), String> {
+        if let Some(ref compressed_library_path) = self.compressed_library_path {
+            if let Some(found) = transcode::find_compressed(compressed_library_path, track, profile)
+            {
+                return Ok(found);
+            }
+        }
+
+        self.transcoder.transcode(track, profile).await
+    }
+
+    pub fn list_movies(&self) -> &Vec<MovieMetadata> {
+        &self.movies
+    }
+
+    /// Movies are identified by their path in the movie library, since titles can repeat
+    pub fn find_movie(&self, movie_id: ID) -> Option<&MovieMetadata> {
+        self.movies
+            .iter()
+            .find(|movie| ID::new(&movie.relative_path) == movie_id)
+    }
+
+    pub fn list_shows(&self) -> Vec<&Show> {
+        match *self.shows {
+            Some(ref shows) => shows.get_shows().values().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn find_show(&self, show_id: ID) -> Option<&Show> {
+        self.list_shows()
+            .into_iter()
+            .find(|show| ID::new(&show.get_name()) == show_id)
+    }
+
+    pub fn find_show_episode(
+        &self,
+        show_id: ID,
+        season_no: u32,
+        episode_no: u32,
+    ) -> Option<&ShowMetadata> {
+        self.find_show(show_id)?
+            .get_season(&season_no)?
+            .get_episode(&episode_no)
+    }
+
+    /// For each show that has been watched, the last episode watched and the one after it,
+    /// read fresh from the app's show tracker since the app keeps writing to it
+    pub fn list_continue_watching(&self) -> Vec<(ShowMetadata, ShowMetadata)> {
+        let shows = match *self.shows {
+            Some(ref shows) => shows,
+            None => return Vec::new(),
+        };
+        let tracker = match ShowTracker::load_read_only(&self.app_data_path) {
+            Some(tracker) => tracker,
+            None => return Vec::new(),
+        };
+
+        shows
+            .get_shows()
+            .iter()
+            .filter(|(_, show)| {
+                !tracker
+                    .get_progress(show.get_regular_episodes().into_iter())
+                    .is_complete()
+            })
+            .filter_map(|(show_key, _)| {
+                let last_viewed = tracker.get_show_most_recently_viewed(show_key)?;
+                let last_episode = shows.get_episode(last_viewed)?;
+                let next_viewed = shows.next_episode(last_viewed);
+                let next_episode = shows.get_episode(&next_viewed)?;
+                Some((last_episode.clone(), next_episode.clone()))
+            })
+            .collect()
+    }
+
+    pub fn get_config(&self) -> &config::WebserverConfig {
+        &self.config
     }
 }
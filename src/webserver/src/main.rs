@@ -1,15 +1,20 @@
+mod clients;
+mod config;
 mod contenttype;
 mod ds;
 mod model;
+mod playlists;
 mod routes;
+mod tls;
+mod transcode;
 
-use std::convert::Infallible;
 use std::env;
 use std::net::SocketAddr;
 use std::path::Path;
 
-use hyper::service::{make_service_fn, service_fn};
-use hyper::Server;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use tokio::net::TcpListener;
 
 use crate::ds::Datastore;
 use crate::routes::service_handler;
@@ -17,30 +22,87 @@ use crate::routes::service_handler;
 #[tokio::main]
 async fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        panic!("Must supply <port>, and <library-path>");
+    if args.len() != 3 && args.len() != 4 {
+        panic!("Must supply <port>, <library-path>, and optionally <app-data-path>");
     }
 
     let port = args[1].parse::<u16>().unwrap();
     let lib_path = Path::new(&args[2]);
+    let app_data_path = match args.get(3) {
+        Some(app_data_path) => Path::new(app_data_path).to_path_buf(),
+        None => musiqcore::model::functions::get_default_data_path(),
+    };
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let config = config::WebserverConfig::load(&config::WebserverConfig::default_path());
 
-    let ds = Datastore::new(lib_path.to_path_buf());
+    let addr = SocketAddr::new(config.get_bind_address(), port);
+    let tls_acceptor = config.tls.as_ref().map(tls::load_acceptor);
+    match (addr.ip().is_loopback(), config.users.is_empty()) {
+        (true, true) => {
+            println!("no users are configured, so anyone on this machine has access");
+        }
+        (false, true) if !config.allow_anonymous => panic!(
+            "refusing to listen on {} with no users configured, add users (or set allow_anonymous) in {:?}",
+            addr.ip(),
+            config::WebserverConfig::default_path()
+        ),
+        (false, true) => {
+            println!(
+                "allow_anonymous is set, so anyone who can reach {} has access",
+                addr.ip()
+            );
+        }
+        (false, false) if config.tls.is_none() => {
+            println!(
+                "warning: listening on {} without tls, so credentials cross the network in cleartext",
+                addr.ip()
+            );
+        }
+        (_, false) => (),
+    };
 
-    let make_service = make_service_fn(move |_conn| {
-        let ds = ds.clone();
-        let service = service_fn(move |req| service_handler(req, ds.clone()));
-        async move { Ok::<_, Infallible>(service) }
-    });
+    let ds = Datastore::new(lib_path.to_path_buf(), app_data_path, config);
+
+    let listener = TcpListener::bind(&addr).await.unwrap();
 
-    let server = Server::bind(&addr)
-        .http1_keepalive(true)
-        .serve(make_service);
+    println!(
+        "Listening on {}://{}",
+        match tls_acceptor {
+            Some(_) => "https",
+            None => "http",
+        },
+        addr
+    );
+
+    loop {
+        let (tcp_stream, remote_addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                eprintln!("could not accept connection: {}", e);
+                continue;
+            }
+        };
+        let ds = ds.clone();
+        let tls_acceptor = tls_acceptor.clone();
 
-    println!("Listening on http://{}", addr);
+        tokio::spawn(async move {
+            let service = service_fn(move |req| service_handler(req, remote_addr, ds.clone()));
+            let mut http = Http::new();
+            http.http1_keep_alive(true);
 
-    if let Err(e) = server.await {
-        eprintln!("server error: {}", e);
+            let served = match tls_acceptor {
+                Some(tls_acceptor) => match tls_acceptor.accept(tcp_stream).await {
+                    Ok(tls_stream) => http.serve_connection(tls_stream, service).await,
+                    Err(e) => {
+                        eprintln!("tls handshake with {} failed: {}", remote_addr, e);
+                        return;
+                    }
+                },
+                None => http.serve_connection(tcp_stream, service).await,
+            };
+            if let Err(e) = served {
+                eprintln!("connection error with {}: {}", remote_addr, e);
+            }
+        });
     }
 }
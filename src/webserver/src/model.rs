@@ -2,8 +2,10 @@ use musiqlibrary;
 
 pub use musiqlibrary::model::{
     AlbumInfo, ArtistInfo, FullTrackMetadata, RawLibrary, SortedAlbumDiscs, SortedArtistAlbums,
-    SortedDiscTracks, ID,
+    SortedDiscTracks, TrackUniqueIdentifier, ID,
 };
+pub use musiqlibrary::shows::{Show, ShowMetadata, Shows};
+pub use musiqlibrary::video::MovieMetadata;
 
 pub enum PlayPriority {
     Now,
@@ -0,0 +1,48 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use musiqcore::datastore::jsonbacked::common;
+use musiqcore::datastore::localfs;
+
+use crate::model::TrackUniqueIdentifier;
+
+/// The parts of the app's `playlists.json` the webserver reads
+#[derive(Deserialize, Default)]
+struct RawPlaylistData {
+    playlists: Vec<Playlist>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Playlist {
+    pub id: u32,
+    pub name: String,
+    pub tracks: Vec<TrackUniqueIdentifier>,
+}
+
+pub struct Playlists {
+    pub playlists: Vec<Playlist>,
+    /// When `playlists.json` was last written, since playlists do not track it themselves
+    pub last_modified: SystemTime,
+}
+
+pub fn list_playlists(app_data_path: &PathBuf) -> Playlists {
+    let json_db_path =
+        localfs::build_tree_for_file(app_data_path, vec!["data", "playlists", "playlists.json"]);
+
+    let raw: RawPlaylistData = match localfs::check_exists(&json_db_path) {
+        true => common::maybe_get_existing_raw_data(&json_db_path).unwrap_or_default(),
+        false => RawPlaylistData::default(),
+    };
+    let mut playlists = raw.playlists;
+    playlists.sort_by_key(|playlist| playlist.id);
+
+    Playlists {
+        playlists,
+        last_modified: fs::metadata(&json_db_path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(UNIX_EPOCH),
+    }
+}
@@ -0,0 +1,253 @@
+use std::cmp::Ordering;
+
+use chrono::Local;
+use serde::de::DeserializeOwned;
+
+use crate::clients;
+use crate::ds::Datastore;
+use crate::model::{AlbumInfo, ArtistInfo, FullTrackMetadata};
+
+use super::super::params::{self, RequestError};
+use super::model;
+use super::query::{compare_names, Query};
+
+const ARTIST_SORT_KEYS: [&str; 1] = ["name"];
+const ALBUM_SORT_KEYS: [&str; 4] = ["name", "artist", "date", "duration"];
+const ARTIST_ALBUM_SORT_KEYS: [&str; 3] = ["name", "date", "duration"];
+/// How many already played tracks are kept with a client's queue
+const MAX_CLIENT_HISTORY: usize = 200;
+
+const TRACK_SORT_KEYS: [&str; 6] = ["artist", "album", "title", "track", "date", "duration"];
+
+pub fn artists(query: &Query, ds: &Datastore) -> Result<model::Page<model::Artist>, RequestError> {
+    let paging = query.paging()?;
+    let sorting = query.sorting(&ARTIST_SORT_KEYS)?;
+
+    let mut artists = ds.list_artists();
+    sorting.apply(&mut artists, compare_artists);
+    Ok(paging.page(&artists, model::Artist::new))
+}
+
+pub fn artist(raw_artist_id: &str, ds: &Datastore) -> Result<model::Artist, RequestError> {
+    let artist_id = params::artist(ds, raw_artist_id)?;
+    Ok(model::Artist::new(&ds.get_artist_from_id(artist_id)))
+}
+
+pub fn artist_albums(
+    raw_artist_id: &str,
+    query: &Query,
+    ds: &Datastore,
+) -> Result<model::Page<model::Album>, RequestError> {
+    let artist_id = params::artist(ds, raw_artist_id)?;
+    let paging = query.paging()?;
+    let sorting = query.sorting(&ARTIST_ALBUM_SORT_KEYS)?;
+
+    let artist = ds.get_artist_from_id(artist_id);
+    let mut albums = ds.list_artist_albums(artist_id);
+    sorting.apply(&mut albums, compare_albums);
+    Ok(paging.page(&albums, |album| model::Album::new(&artist, album)))
+}
+
+pub fn artist_album(
+    raw_artist_id: &str,
+    raw_album_id: &str,
+    ds: &Datastore,
+) -> Result<model::AlbumDetail, RequestError> {
+    let (artist_id, album_id) = params::artist_album(ds, raw_artist_id, raw_album_id)?;
+
+    let artist = ds.get_artist_from_id(artist_id);
+    let album = ds.get_artist_album_from_id(artist_id, album_id);
+
+    let mut discs: Vec<model::Disc> = Vec::new();
+    for track in ds.list_artist_album_tracks(artist_id, album_id).iter() {
+        match discs.last_mut() {
+            Some(disc) if disc.disc == track.disc => disc.tracks.push(model::Track::new(track)),
+            _ => discs.push(model::Disc {
+                disc: track.disc,
+                tracks: vec![model::Track::new(track)],
+            }),
+        }
+    }
+
+    Ok(model::AlbumDetail {
+        album: model::Album::new(&artist, &album),
+        discs,
+    })
+}
+
+pub fn artist_album_track(
+    raw_artist_id: &str,
+    raw_album_id: &str,
+    raw_disc_no: &str,
+    raw_track_no: &str,
+    ds: &Datastore,
+) -> Result<model::Track, RequestError> {
+    let (artist_id, album_id, disc_no, track_no) =
+        params::artist_album_track(ds, raw_artist_id, raw_album_id, raw_disc_no, raw_track_no)?;
+    Ok(model::Track::new(&ds.get_artist_album_track_name_from_id(
+        artist_id, album_id, disc_no, track_no,
+    )))
+}
+
+pub fn albums(query: &Query, ds: &Datastore) -> Result<model::Page<model::Album>, RequestError> {
+    let paging = query.paging()?;
+    let sorting = query.sorting(&ALBUM_SORT_KEYS)?;
+
+    let mut albums = ds.list_albums();
+    sorting.apply(&mut albums, compare_artist_albums);
+    Ok(paging.page(&albums, |(artist, album)| model::Album::new(artist, album)))
+}
+
+pub fn tracks(query: &Query, ds: &Datastore) -> Result<model::Page<model::Track>, RequestError> {
+    let paging = query.paging()?;
+    let sorting = query.sorting(&TRACK_SORT_KEYS)?;
+
+    let mut tracks = ds.list_tracks();
+    sorting.apply(&mut tracks, compare_tracks);
+    Ok(paging.page(&tracks, model::Track::new))
+}
+
+/// Case insensitive substring search over artist names, album names and track titles,
+/// with the paging parameters applying to each of them
+pub fn search(query: &Query, ds: &Datastore) -> Result<model::SearchResults, RequestError> {
+    let search_query = match query.get("q").map(|q| q.trim()) {
+        Some(q) if !q.is_empty() => q.to_string(),
+        _ => {
+            return Err(RequestError::BadRequest(
+                "missing search query q".to_string(),
+            ))
+        }
+    };
+    let paging = query.paging()?;
+    let needle = search_query.to_lowercase();
+
+    let artists: Vec<ArtistInfo> = ds
+        .list_artists()
+        .into_iter()
+        .filter(|artist| artist.artist_name.to_lowercase().contains(&needle))
+        .collect();
+    let albums: Vec<(ArtistInfo, AlbumInfo)> = ds
+        .list_albums()
+        .into_iter()
+        .filter(|(_artist, album)| album.album_name.to_lowercase().contains(&needle))
+        .collect();
+    let tracks: Vec<FullTrackMetadata> = ds
+        .list_tracks()
+        .into_iter()
+        .filter(|track| track.title.to_lowercase().contains(&needle))
+        .collect();
+
+    Ok(model::SearchResults {
+        query: search_query,
+        artists: paging.page(&artists, model::Artist::new),
+        albums: paging.page(&albums, |(artist, album)| model::Album::new(artist, album)),
+        tracks: paging.page(&tracks, model::Track::new),
+    })
+}
+
+/// Record that a client listened to the track in the body, for the app's play history
+pub fn record_listen(
+    raw_client_id: &str,
+    body: &[u8],
+    ds: &Datastore,
+) -> Result<model::Track, RequestError> {
+    let client_id = params::client(raw_client_id)?;
+    let track_key: clients::TrackKey = parse_body(body)?;
+    let track = params::track_key(ds, &track_key)?;
+
+    ds.record_client_listen(&client_id, &track, Local::now())
+        .map_err(RequestError::Internal)?;
+    Ok(model::Track::new(&track))
+}
+
+/// The queue a client last stored, skipping tracks that are no longer in the library
+pub fn client_queue(
+    raw_client_id: &str,
+    ds: &Datastore,
+) -> Result<model::ClientQueue, RequestError> {
+    let client_id = params::client(raw_client_id)?;
+    let raw_queue = ds.get_client_queue(&client_id);
+
+    let to_json = |key: &clients::TrackKey| {
+        params::track_key(ds, key)
+            .ok()
+            .map(|track| model::Track::new(&track))
+    };
+    Ok(model::ClientQueue {
+        current: raw_queue.current.as_ref().and_then(to_json),
+        queue: raw_queue.queue.iter().filter_map(to_json).collect(),
+        history: raw_queue.history.iter().filter_map(to_json).collect(),
+    })
+}
+
+pub fn set_client_queue(
+    raw_client_id: &str,
+    body: &[u8],
+    ds: &Datastore,
+) -> Result<model::ClientQueue, RequestError> {
+    let client_id = params::client(raw_client_id)?;
+    let mut raw_queue: clients::RawClientQueue = parse_body(body)?;
+
+    for key in raw_queue
+        .current
+        .iter()
+        .chain(raw_queue.queue.iter())
+        .chain(raw_queue.history.iter())
+    {
+        params::track_key(ds, key)?;
+    }
+    if raw_queue.history.len() > MAX_CLIENT_HISTORY {
+        raw_queue
+            .history
+            .drain(..raw_queue.history.len() - MAX_CLIENT_HISTORY);
+    }
+
+    ds.set_client_queue(&client_id, &raw_queue)
+        .map_err(RequestError::Internal)?;
+    client_queue(raw_client_id, ds)
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, RequestError> {
+    serde_json::from_slice(body)
+        .map_err(|e| RequestError::BadRequest(format!("invalid request body: {}", e)))
+}
+
+fn compare_artists(_key: &str, a: &ArtistInfo, b: &ArtistInfo) -> Ordering {
+    compare_names(&a.artist_name, &b.artist_name)
+}
+
+fn compare_albums(key: &str, a: &AlbumInfo, b: &AlbumInfo) -> Ordering {
+    match key {
+        "date" => a.start_date.cmp(&b.start_date),
+        "duration" => a.total_duration.cmp(&b.total_duration),
+        _ => compare_names(&a.album_name, &b.album_name),
+    }
+}
+
+fn compare_artist_albums(
+    key: &str,
+    (a_artist, a_album): &(ArtistInfo, AlbumInfo),
+    (b_artist, b_album): &(ArtistInfo, AlbumInfo),
+) -> Ordering {
+    match key {
+        "artist" => compare_names(&a_artist.artist_name, &b_artist.artist_name)
+            .then_with(|| compare_names(&a_album.album_name, &b_album.album_name)),
+        key => compare_albums(key, a_album, b_album),
+    }
+}
+
+fn compare_tracks(key: &str, a: &FullTrackMetadata, b: &FullTrackMetadata) -> Ordering {
+    match key {
+        "album" => compare_names(&a.album, &b.album)
+            .then_with(|| a.disc.cmp(&b.disc))
+            .then_with(|| a.track.cmp(&b.track)),
+        "title" => compare_names(&a.title, &b.title),
+        "track" => a.track.cmp(&b.track),
+        "date" => a.date_number.cmp(&b.date_number),
+        "duration" => a.duration.cmp(&b.duration),
+        _ => compare_names(&a.album_artist, &b.album_artist)
+            .then_with(|| compare_names(&a.album, &b.album))
+            .then_with(|| a.disc.cmp(&b.disc))
+            .then_with(|| a.track.cmp(&b.track)),
+    }
+}
@@ -0,0 +1,91 @@
+mod endpoints;
+mod model;
+pub mod query;
+
+use hyper::{header, Body, Method, Response, StatusCode};
+use serde::Serialize;
+
+use crate::ds::Datastore;
+
+use super::params::RequestError;
+
+use query::Query;
+
+/// Serve the `/api/v1/...` JSON endpoints, `frags` being the path after the version
+pub fn handle_api(
+    method: &Method,
+    frags: &[&str],
+    raw_query: Option<&str>,
+    body: &[u8],
+    ds: Datastore,
+) -> Response<Body> {
+    let query = Query::parse(raw_query);
+    match (method, frags) {
+        (&Method::GET, ["artists"]) => json_response(endpoints::artists(&query, &ds)),
+        (&Method::GET, ["artists", artist_id]) => json_response(endpoints::artist(artist_id, &ds)),
+        (&Method::GET, ["artists", artist_id, "albums"]) => {
+            json_response(endpoints::artist_albums(artist_id, &query, &ds))
+        }
+        (&Method::GET, ["artists", artist_id, "albums", album_id]) => {
+            json_response(endpoints::artist_album(artist_id, album_id, &ds))
+        }
+        (
+            &Method::GET,
+            ["artists", artist_id, "albums", album_id, "discs", disc_no, "tracks", track_no],
+        ) => json_response(endpoints::artist_album_track(
+            artist_id, album_id, disc_no, track_no, &ds,
+        )),
+        (&Method::GET, ["albums"]) => json_response(endpoints::albums(&query, &ds)),
+        (&Method::GET, ["tracks"]) => json_response(endpoints::tracks(&query, &ds)),
+        (&Method::GET, ["search"]) => json_response(endpoints::search(&query, &ds)),
+        (&Method::POST, ["clients", client_id, "listens"]) => {
+            json_response(endpoints::record_listen(client_id, body, &ds))
+        }
+        (&Method::GET, ["clients", client_id, "queue"]) => {
+            json_response(endpoints::client_queue(client_id, &ds))
+        }
+        (&Method::PUT, ["clients", client_id, "queue"]) => {
+            json_response(endpoints::set_client_queue(client_id, body, &ds))
+        }
+        _ => error_response(RequestError::NotFound(format!(
+            "no such endpoint: {} /{}",
+            method,
+            frags.join("/")
+        ))),
+    }
+}
+
+pub fn error_response(e: RequestError) -> Response<Body> {
+    json_response::<()>(Err(e))
+}
+
+fn json_response<T: Serialize>(result: Result<T, RequestError>) -> Response<Body> {
+    let (status, body) = match result {
+        Ok(found) => (StatusCode::OK, serde_json::to_string(&found)),
+        Err(e) => (
+            e.status(),
+            serde_json::to_string(&model::Error {
+                error: e.message().to_string(),
+            }),
+        ),
+    };
+
+    let (status, body) = match body {
+        Ok(body) => (status, body),
+        Err(e) => {
+            println!("could not serialize api response: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "{\"error\": \"internal error\"}".to_string(),
+            )
+        }
+    };
+
+    let mut resp = Response::new(Body::from(body));
+    *resp.status_mut() = status;
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    resp
+}
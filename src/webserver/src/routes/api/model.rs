@@ -0,0 +1,171 @@
+use serde::Serialize;
+
+use crate::contenttype;
+use crate::model::{AlbumInfo, ArtistInfo, FullTrackMetadata, ID};
+
+pub const API_PREFIX: &str = "/api/v1";
+
+#[derive(Serialize)]
+pub struct Error {
+    pub error: String,
+}
+
+/// One page of a longer listing, along with where it sits in the full listing
+#[derive(Serialize)]
+pub struct Page<T> {
+    pub offset: usize,
+    pub limit: usize,
+    pub total: usize,
+    pub items: Vec<T>,
+}
+
+/// Ids are sent as strings, since they do not fit in a javascript number
+#[derive(Serialize)]
+pub struct Artist {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub albums_url: String,
+}
+
+impl Artist {
+    pub fn new(artist: &ArtistInfo) -> Self {
+        Artist {
+            id: artist.artist_id.hashed().to_string(),
+            name: artist.artist_name.clone(),
+            url: artist_url(&artist.artist_id),
+            albums_url: format!("{}/albums", artist_url(&artist.artist_id)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Album {
+    pub id: String,
+    pub name: String,
+    pub artist_id: String,
+    pub artist_name: String,
+    pub genres: Vec<String>,
+    pub start_date: u32,
+    pub end_date: u32,
+    pub duration_seconds: u64,
+    pub url: String,
+    pub cover_url: String,
+}
+
+impl Album {
+    pub fn new(artist: &ArtistInfo, album: &AlbumInfo) -> Self {
+        Album {
+            id: album.album_id.hashed().to_string(),
+            name: album.album_name.clone(),
+            artist_id: artist.artist_id.hashed().to_string(),
+            artist_name: artist.artist_name.clone(),
+            genres: album.genres.iter().cloned().collect(),
+            start_date: album.start_date,
+            end_date: album.end_date,
+            duration_seconds: album.total_duration.as_secs(),
+            url: album_url(&artist.artist_id, &album.album_id),
+            cover_url: cover_url(&artist.artist_id, &album.album_id),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AlbumDetail {
+    #[serde(flatten)]
+    pub album: Album,
+    pub discs: Vec<Disc>,
+}
+
+#[derive(Serialize)]
+pub struct Disc {
+    pub disc: u64,
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Serialize)]
+pub struct Track {
+    pub title: String,
+    pub disc: u64,
+    pub track: u64,
+    pub album_id: String,
+    pub album: String,
+    pub album_artist_id: String,
+    pub album_artist: String,
+    pub track_artist_id: String,
+    pub track_artist: String,
+    pub genre: String,
+    pub date: u32,
+    pub duration_seconds: u64,
+    pub content_type: String,
+    pub url: String,
+    pub audio_url: String,
+    pub cover_url: String,
+}
+
+impl Track {
+    pub fn new(track: &FullTrackMetadata) -> Self {
+        let track_url = format!(
+            "{}/discs/{}/tracks/{}",
+            album_url(&track.album_artist_id, &track.album_id),
+            track.disc,
+            track.track,
+        );
+        Track {
+            title: track.title.clone(),
+            disc: track.disc,
+            track: track.track,
+            album_id: track.album_id.hashed().to_string(),
+            album: track.album.clone(),
+            album_artist_id: track.album_artist_id.hashed().to_string(),
+            album_artist: track.album_artist.clone(),
+            track_artist_id: track.track_artist_id.hashed().to_string(),
+            track_artist: track.track_artist.clone(),
+            genre: track.genre.clone(),
+            date: track.date_number,
+            duration_seconds: track.duration.as_secs(),
+            content_type: contenttype::audio_content_type_from_ext(&track.ext).to_string(),
+            url: track_url,
+            audio_url: format!(
+                "/artists/{}/albums/{}/disc_tracks/{}/tracks/{}/audio/song.{}",
+                track.album_artist_id.hashed(),
+                track.album_id.hashed(),
+                track.disc,
+                track.track,
+                track.ext,
+            ),
+            cover_url: cover_url(&track.album_artist_id, &track.album_id),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ClientQueue {
+    pub current: Option<Track>,
+    pub queue: Vec<Track>,
+    pub history: Vec<Track>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResults {
+    pub query: String,
+    pub artists: Page<Artist>,
+    pub albums: Page<Album>,
+    pub tracks: Page<Track>,
+}
+
+fn artist_url(artist_id: &ID) -> String {
+    format!("{}/artists/{}", API_PREFIX, artist_id.hashed())
+}
+
+fn album_url(artist_id: &ID, album_id: &ID) -> String {
+    format!("{}/albums/{}", artist_url(artist_id), album_id.hashed())
+}
+
+fn cover_url(artist_id: &ID, album_id: &ID) -> String {
+    format!(
+        "/artists/{}/albums/{}/cover.jpg",
+        artist_id.hashed(),
+        album_id.hashed()
+    )
+}
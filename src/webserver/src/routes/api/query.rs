@@ -0,0 +1,139 @@
+use std::cmp::Ordering;
+
+use super::super::params::RequestError;
+use super::model::Page;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+/// The decoded `key=value` pairs of a request's query string
+pub struct Query {
+    pairs: Vec<(String, String)>,
+}
+
+impl Query {
+    pub fn parse(raw_query: Option<&str>) -> Self {
+        Query {
+            pairs: match raw_query {
+                Some(raw_query) => form_urlencoded::parse(raw_query.as_bytes())
+                    .into_owned()
+                    .collect(),
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Add the parameters from a form encoded request body, which some clients send
+    /// instead of (or as well as) a query string
+    pub fn with_form_body(mut self, body: &[u8]) -> Self {
+        self.pairs.extend(form_urlencoded::parse(body).into_owned());
+        self
+    }
+
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.pairs
+            .iter()
+            .filter(|(found_key, _)| found_key == key)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(found_key, _)| found_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The `offset` and `limit` parameters
+    pub fn paging(&self) -> Result<Paging, RequestError> {
+        let offset = match self.get("offset") {
+            Some(offset) => offset
+                .parse::<usize>()
+                .map_err(|_| RequestError::BadRequest(format!("invalid offset: {}", offset)))?,
+            None => 0,
+        };
+        let limit = match self.get("limit") {
+            Some(limit) => match limit.parse::<usize>() {
+                Ok(limit) if limit > 0 && limit <= MAX_LIMIT => limit,
+                _ => {
+                    return Err(RequestError::BadRequest(format!(
+                        "limit must be between 1 and {}: {}",
+                        MAX_LIMIT, limit
+                    )))
+                }
+            },
+            None => DEFAULT_LIMIT,
+        };
+        Ok(Paging { offset, limit })
+    }
+
+    /// The `sort` and `order` parameters, where `sort` must be one of `sort_keys`
+    /// and defaults to the first of them
+    pub fn sorting(&self, sort_keys: &[&'static str]) -> Result<Sorting, RequestError> {
+        let key = match self.get("sort") {
+            Some(sort) => match sort_keys.iter().find(|key| **key == sort) {
+                Some(key) => *key,
+                None => {
+                    return Err(RequestError::BadRequest(format!(
+                        "sort must be one of {}: {}",
+                        sort_keys.join(", "),
+                        sort
+                    )))
+                }
+            },
+            None => sort_keys[0],
+        };
+        let descending = match self.get("order") {
+            Some("asc") | None => false,
+            Some("desc") => true,
+            Some(order) => {
+                return Err(RequestError::BadRequest(format!(
+                    "order must be asc or desc: {}",
+                    order
+                )))
+            }
+        };
+        Ok(Sorting { key, descending })
+    }
+}
+
+pub struct Paging {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Paging {
+    pub fn page<T, J, F: Fn(&T) -> J>(&self, items: &[T], to_json: F) -> Page<J> {
+        Page {
+            offset: self.offset,
+            limit: self.limit,
+            total: items.len(),
+            items: items
+                .iter()
+                .skip(self.offset)
+                .take(self.limit)
+                .map(to_json)
+                .collect(),
+        }
+    }
+}
+
+pub struct Sorting {
+    pub key: &'static str,
+    pub descending: bool,
+}
+
+impl Sorting {
+    /// Stable sort the items by the chosen key, with `compare` mapping a key to an ordering
+    pub fn apply<T, F: Fn(&str, &T, &T) -> Ordering>(&self, items: &mut [T], compare: F) {
+        match self.descending {
+            true => items.sort_by(|a, b| compare(self.key, b, a)),
+            false => items.sort_by(|a, b| compare(self.key, a, b)),
+        }
+    }
+}
+
+pub fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
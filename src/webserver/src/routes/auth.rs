@@ -0,0 +1,78 @@
+use base64::Engine;
+use hyper::{header, Body, HeaderMap, Response, StatusCode};
+
+use crate::config::WebserverConfig;
+
+/// Who a response was served to, kept in the response's extensions for the access log
+#[derive(Clone)]
+pub struct AuthenticatedUser(pub String);
+
+/// The request did not authenticate as any account
+pub struct Unauthorized;
+
+impl Unauthorized {
+    pub fn to_response(&self) -> Response<Body> {
+        let mut resp = Response::new(Body::from("authentication required"));
+        *resp.status_mut() = StatusCode::UNAUTHORIZED;
+        resp.headers_mut().insert(
+            header::WWW_AUTHENTICATE,
+            header::HeaderValue::from_static("Basic realm=\"musiq\", charset=\"UTF-8\""),
+        );
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("text/plain"),
+        );
+        resp
+    }
+}
+
+/// Check the request's `Authorization` header, either HTTP basic auth with an
+/// account's password or a bearer token of the account; with no accounts set up,
+/// every request is let through without a user
+pub fn authenticate(
+    request_headers: &HeaderMap,
+    config: &WebserverConfig,
+) -> Result<Option<AuthenticatedUser>, Unauthorized> {
+    if config.users.is_empty() {
+        return Ok(None);
+    }
+
+    let authorization = request_headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    let found = match authorization.split_once(' ') {
+        // the scheme name is case-insensitive (RFC 7235)
+        Some((scheme, encoded)) if scheme.eq_ignore_ascii_case("Basic") => {
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|decoded| String::from_utf8(decoded).ok())
+                .and_then(|decoded| {
+                    let (username, password) = decoded.split_once(':')?;
+                    config
+                        .get_user(username)
+                        .filter(|user| constant_time_eq(&user.password, password))
+                })
+        }
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("Bearer") => {
+            config.get_user_by_token(token.trim())
+        }
+        _ => None,
+    };
+
+    match found {
+        Some(user) => Ok(Some(AuthenticatedUser(user.username.clone()))),
+        None => Err(Unauthorized),
+    }
+}
+
+/// Compare secrets without stopping at the first difference
+pub fn constant_time_eq(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
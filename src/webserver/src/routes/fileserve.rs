@@ -0,0 +1,464 @@
+use std::fs;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hyper::body::{Bytes, Sender};
+use hyper::{header, Body, HeaderMap, Response, StatusCode};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+const MULTIPART_BOUNDARY: &str = "MUSIQ_BYTERANGES_BOUNDARY";
+
+/// Range headers asking for more ranges than this are ignored and the whole file is served
+const MAX_RANGES: usize = 16;
+
+/// An inclusive range of bytes to serve from a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ByteRange {
+    start: u64,
+    end: u64,
+}
+
+impl ByteRange {
+    fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    fn content_range(&self, file_len: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, file_len)
+    }
+}
+
+/// What a `Range` header asks for
+#[derive(Debug, PartialEq, Eq)]
+enum RangeRequest {
+    /// Not a byte range header we understand (or too many ranges), so it is ignored
+    Invalid,
+    /// Well formed, but none of the ranges overlap the file
+    Unsatisfiable,
+    /// The ranges to serve, sorted and with overlapping ones merged
+    Satisfiable(Vec<ByteRange>),
+}
+
+/// A part of the response body, either literal bytes or a range of the file
+enum BodyPart {
+    Literal(Bytes),
+    File(ByteRange),
+}
+
+impl BodyPart {
+    fn len(&self) -> u64 {
+        match self {
+            BodyPart::Literal(bytes) => bytes.len() as u64,
+            BodyPart::File(range) => range.len(),
+        }
+    }
+}
+
+/// Serve a file from disk, streaming its contents and honoring
+/// conditional (ETag/Last-Modified) and byte range requests
+pub fn serve_file(
+    path: &Path,
+    content_type: &'static str,
+    last_modified: SystemTime,
+    request_headers: &HeaderMap,
+) -> Response<Body> {
+    let file_len = match fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) => {
+            println!("could not read file {:?}: {}", path, e);
+            return empty_response(StatusCode::NOT_FOUND);
+        }
+    };
+
+    let etag = compute_etag(file_len, last_modified);
+    let last_modified_str = httpdate::fmt_http_date(last_modified);
+
+    if is_not_modified(request_headers, &etag, last_modified) {
+        let mut resp = empty_response(StatusCode::NOT_MODIFIED);
+        insert_caching_headers(resp.headers_mut(), &etag, &last_modified_str);
+        return resp;
+    }
+
+    let maybe_ranges = match request_headers.get(header::RANGE) {
+        Some(range_header) if if_range_matches(request_headers, &etag, &last_modified_str) => {
+            let range_request = match range_header.to_str() {
+                Ok(range) => parse_ranges(range, file_len),
+                Err(_) => RangeRequest::Invalid,
+            };
+            match range_request {
+                RangeRequest::Invalid => None,
+                RangeRequest::Unsatisfiable => {
+                    let mut resp = empty_response(StatusCode::RANGE_NOT_SATISFIABLE);
+                    resp.headers_mut().insert(
+                        header::CONTENT_RANGE,
+                        header_value(format!("bytes */{}", file_len)),
+                    );
+                    return resp;
+                }
+                RangeRequest::Satisfiable(ranges) => Some(ranges),
+            }
+        }
+        _ => None,
+    };
+
+    let (status, body_content_type, maybe_content_range, parts) = match maybe_ranges {
+        None => (
+            StatusCode::OK,
+            content_type.to_string(),
+            None,
+            match file_len {
+                0 => Vec::new(),
+                _ => vec![BodyPart::File(ByteRange {
+                    start: 0,
+                    end: file_len - 1,
+                })],
+            },
+        ),
+        Some(ranges) if ranges.len() == 1 => (
+            StatusCode::PARTIAL_CONTENT,
+            content_type.to_string(),
+            Some(ranges[0].content_range(file_len)),
+            vec![BodyPart::File(ranges[0])],
+        ),
+        Some(ranges) => (
+            StatusCode::PARTIAL_CONTENT,
+            format!("multipart/byteranges; boundary={}", MULTIPART_BOUNDARY),
+            None,
+            multipart_parts(&ranges, content_type, file_len),
+        ),
+    };
+
+    let content_length: u64 = parts.iter().map(|part| part.len()).sum();
+
+    let (sender, body) = Body::channel();
+    tokio::spawn(stream_parts(path.to_path_buf(), parts, sender));
+
+    let mut resp = Response::new(body);
+    *resp.status_mut() = status;
+    let headers = resp.headers_mut();
+    headers.insert(header::CONTENT_TYPE, header_value(body_content_type));
+    headers.insert(
+        header::CONTENT_LENGTH,
+        header_value(content_length.to_string()),
+    );
+    headers.insert(
+        header::ACCEPT_RANGES,
+        header::HeaderValue::from_static("bytes"),
+    );
+    if let Some(content_range) = maybe_content_range {
+        headers.insert(header::CONTENT_RANGE, header_value(content_range));
+    }
+    insert_caching_headers(headers, &etag, &last_modified_str);
+    resp
+}
+
+async fn stream_parts(path: PathBuf, parts: Vec<BodyPart>, mut sender: Sender) {
+    let mut file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) => {
+            println!("could not open file {:?}: {}", path, e);
+            sender.abort();
+            return;
+        }
+    };
+
+    for part in parts.into_iter() {
+        match part {
+            BodyPart::Literal(bytes) => {
+                if sender.send_data(bytes).await.is_err() {
+                    return;
+                }
+            }
+            BodyPart::File(range) => {
+                if let Err(e) = file.seek(SeekFrom::Start(range.start)).await {
+                    println!("could not seek in file {:?}: {}", path, e);
+                    sender.abort();
+                    return;
+                }
+                let mut remaining = range.len();
+                while remaining > 0 {
+                    let mut buffer = vec![0; CHUNK_SIZE.min(remaining as usize)];
+                    let read = match file.read(&mut buffer).await {
+                        Ok(0) => {
+                            println!("file {:?} was shorter than expected", path);
+                            sender.abort();
+                            return;
+                        }
+                        Ok(read) => read,
+                        Err(e) => {
+                            println!("could not read file {:?}: {}", path, e);
+                            sender.abort();
+                            return;
+                        }
+                    };
+                    buffer.truncate(read);
+                    remaining -= read as u64;
+                    // the client hung up (e.g. the browser seeked elsewhere)
+                    if sender.send_data(Bytes::from(buffer)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn multipart_parts(ranges: &[ByteRange], content_type: &str, file_len: u64) -> Vec<BodyPart> {
+    let mut parts = Vec::new();
+    for range in ranges.iter() {
+        parts.push(BodyPart::Literal(Bytes::from(format!(
+            "\r\n--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+            MULTIPART_BOUNDARY,
+            content_type,
+            range.content_range(file_len),
+        ))));
+        parts.push(BodyPart::File(*range));
+    }
+    parts.push(BodyPart::Literal(Bytes::from(format!(
+        "\r\n--{}--\r\n",
+        MULTIPART_BOUNDARY
+    ))));
+    parts
+}
+
+/// Parse a `Range: bytes=...` header into the ranges to serve
+fn parse_ranges(range_header: &str, file_len: u64) -> RangeRequest {
+    let specs = match range_header.trim().strip_prefix("bytes=") {
+        Some(specs) => specs.split(',').collect::<Vec<&str>>(),
+        None => return RangeRequest::Invalid,
+    };
+    if specs.len() > MAX_RANGES {
+        return RangeRequest::Invalid;
+    }
+
+    let mut ranges = Vec::new();
+    for spec in specs.into_iter() {
+        match parse_range_spec(spec, file_len) {
+            Ok(Some(range)) => ranges.push(range),
+            Ok(None) => (),
+            Err(()) => return RangeRequest::Invalid,
+        }
+    }
+
+    // overlapping or touching ranges are served as one, so the same bytes aren't sent twice
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<ByteRange> = Vec::new();
+    for range in ranges.into_iter() {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end.saturating_add(1) => {
+                last.end = last.end.max(range.end)
+            }
+            _ => merged.push(range),
+        }
+    }
+
+    match merged.is_empty() {
+        true => RangeRequest::Unsatisfiable,
+        false => RangeRequest::Satisfiable(merged),
+    }
+}
+
+/// One range of a `Range` header, which is `None` when it doesn't overlap the file
+fn parse_range_spec(spec: &str, file_len: u64) -> Result<Option<ByteRange>, ()> {
+    let (start, end) = spec.trim().split_once('-').ok_or(())?;
+    match (start.trim(), end.trim()) {
+        ("", "") => Err(()),
+        // suffix range, the last N bytes
+        ("", suffix_len) => {
+            let suffix_len = suffix_len.parse::<u64>().map_err(|_| ())?;
+            if suffix_len == 0 || file_len == 0 {
+                return Ok(None);
+            }
+            Ok(Some(ByteRange {
+                start: file_len.saturating_sub(suffix_len),
+                end: file_len - 1,
+            }))
+        }
+        (start, end) => {
+            let start = start.parse::<u64>().map_err(|_| ())?;
+            let end = match end {
+                "" => None,
+                end => Some(end.parse::<u64>().map_err(|_| ())?),
+            };
+            if end.map(|end| end < start).unwrap_or(false) {
+                return Err(());
+            }
+            if start >= file_len {
+                return Ok(None);
+            }
+            let last_byte = file_len - 1;
+            Ok(Some(ByteRange {
+                start,
+                end: end.map(|end| end.min(last_byte)).unwrap_or(last_byte),
+            }))
+        }
+    }
+}
+
+fn compute_etag(file_len: u64, last_modified: SystemTime) -> String {
+    let modified_secs = last_modified
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    format!("\"{:x}-{:x}\"", file_len, modified_secs)
+}
+
+fn is_not_modified(request_headers: &HeaderMap, etag: &str, last_modified: SystemTime) -> bool {
+    match request_headers.get(header::IF_NONE_MATCH) {
+        Some(if_none_match) => match if_none_match.to_str() {
+            Ok(if_none_match) => if_none_match
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag),
+            Err(_) => false,
+        },
+        None => match request_headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|since| since.to_str().ok())
+            .and_then(|since| httpdate::parse_http_date(since).ok())
+        {
+            // http dates only have second precision
+            Some(since) => match last_modified.duration_since(since) {
+                Ok(newer_by) => newer_by.as_secs() == 0,
+                Err(_) => true,
+            },
+            None => false,
+        },
+    }
+}
+
+/// A range request only applies if its `If-Range` (if any) still matches the file
+fn if_range_matches(request_headers: &HeaderMap, etag: &str, last_modified_str: &str) -> bool {
+    match request_headers.get(header::IF_RANGE) {
+        Some(if_range) => match if_range.to_str() {
+            Ok(if_range) => if_range == etag || if_range == last_modified_str,
+            Err(_) => false,
+        },
+        None => true,
+    }
+}
+
+fn insert_caching_headers(headers: &mut HeaderMap, etag: &str, last_modified_str: &str) {
+    headers.insert(header::ETAG, header_value(etag.to_string()));
+    headers.insert(
+        header::LAST_MODIFIED,
+        header_value(last_modified_str.to_string()),
+    );
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut resp = Response::new(Body::empty());
+    *resp.status_mut() = status;
+    resp
+}
+
+fn header_value(value: String) -> header::HeaderValue {
+    header::HeaderValue::from_str(&value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn satisfiable(ranges: &[(u64, u64)]) -> RangeRequest {
+        RangeRequest::Satisfiable(
+            ranges
+                .iter()
+                .map(|(start, end)| ByteRange {
+                    start: *start,
+                    end: *end,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parses_single_ranges() {
+        assert_eq!(parse_ranges("bytes=0-99", 1000), satisfiable(&[(0, 99)]));
+        assert_eq!(parse_ranges("bytes=500-", 1000), satisfiable(&[(500, 999)]));
+        assert_eq!(parse_ranges("bytes=-100", 1000), satisfiable(&[(900, 999)]));
+        assert_eq!(
+            parse_ranges(" bytes= 10 - 20 ", 1000),
+            satisfiable(&[(10, 20)])
+        );
+    }
+
+    #[test]
+    fn clamps_ranges_to_the_file() {
+        assert_eq!(
+            parse_ranges("bytes=900-5000", 1000),
+            satisfiable(&[(900, 999)])
+        );
+        assert_eq!(parse_ranges("bytes=-5000", 1000), satisfiable(&[(0, 999)]));
+    }
+
+    #[test]
+    fn ignores_invalid_headers() {
+        assert_eq!(parse_ranges("items=0-99", 1000), RangeRequest::Invalid);
+        assert_eq!(parse_ranges("bytes=", 1000), RangeRequest::Invalid);
+        assert_eq!(parse_ranges("bytes=-", 1000), RangeRequest::Invalid);
+        assert_eq!(parse_ranges("bytes=5", 1000), RangeRequest::Invalid);
+        assert_eq!(parse_ranges("bytes=a-b", 1000), RangeRequest::Invalid);
+        assert_eq!(parse_ranges("bytes=20-10", 1000), RangeRequest::Invalid);
+        assert_eq!(parse_ranges("bytes=0-99,x", 1000), RangeRequest::Invalid);
+    }
+
+    #[test]
+    fn reports_unsatisfiable_ranges() {
+        assert_eq!(
+            parse_ranges("bytes=1000-", 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_ranges("bytes=2000-3000,1000-1999", 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(parse_ranges("bytes=-0", 1000), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_ranges("bytes=0-", 0), RangeRequest::Unsatisfiable);
+    }
+
+    #[test]
+    fn skips_unsatisfiable_ranges_among_others() {
+        assert_eq!(
+            parse_ranges("bytes=5000-6000,0-9", 1000),
+            satisfiable(&[(0, 9)])
+        );
+    }
+
+    #[test]
+    fn merges_overlapping_and_duplicate_ranges() {
+        assert_eq!(
+            parse_ranges("bytes=0-,0-,0-", 1000),
+            satisfiable(&[(0, 999)])
+        );
+        assert_eq!(
+            parse_ranges("bytes=50-99,0-59,200-299", 1000),
+            satisfiable(&[(0, 99), (200, 299)])
+        );
+        assert_eq!(
+            parse_ranges("bytes=0-9,10-19", 1000),
+            satisfiable(&[(0, 19)])
+        );
+        assert_eq!(
+            parse_ranges("bytes=-100,850-949", 1000),
+            satisfiable(&[(850, 999)])
+        );
+    }
+
+    #[test]
+    fn ignores_too_many_ranges() {
+        let many = vec!["0-0"; MAX_RANGES + 1].join(",");
+        assert_eq!(
+            parse_ranges(&format!("bytes={}", many), 1000),
+            RangeRequest::Invalid
+        );
+        let most = vec!["0-0"; MAX_RANGES].join(",");
+        assert_eq!(
+            parse_ranges(&format!("bytes={}", most), 1000),
+            satisfiable(&[(0, 0)])
+        );
+    }
+}
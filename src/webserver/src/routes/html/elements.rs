@@ -1,7 +1,7 @@
 use musiqlibrary::model::ID;
 
 use crate::contenttype;
-use crate::model::{FullTrackMetadata, PlayPriority};
+use crate::model::{FullTrackMetadata, MovieMetadata, PlayPriority, ShowMetadata};
 
 pub const NOATTRS: Vec<(String, String)> = Vec::new();
 const NOKWS: Vec<String> = Vec::new();
@@ -153,6 +153,24 @@ pub fn audio<S: ToString, K: ToString, V: ToString>(src: Option<S>, attrs: Vec<(
     render_element("audio", vec!["controls"], attrs, Some(Vec::<String>::new()))
 }
 
+#[allow(dead_code)]
+pub fn video<S: ToString, T: ToString>(src: S, type_: T) -> String {
+    render_element(
+        "video",
+        vec!["controls"],
+        vec![
+            ("id", "main-video".to_string()),
+            ("width", "100%".to_string()),
+        ],
+        Some(vec![source(
+            Some(src),
+            Some(type_),
+            NOATTRS,
+            Vec::<String>::new(),
+        )]),
+    )
+}
+
 #[allow(dead_code)]
 pub fn source<S: ToString, T: ToString, K: ToString, V: ToString, C: ToString>(
     src: Option<S>,
@@ -239,14 +257,14 @@ pub fn artist_album_link(album_artist: &String, album: &String) -> String {
 }
 
 pub fn artist_album_image(album_artist: &String, album: &String) -> String {
-    img(
-        format!(
-            "/artists/{}/albums/{}/cover.jpg",
-            ID::new(&album_artist).hashed(),
-            ID::new(&album).hashed(),
-        ),
-        200,
-        200,
+    img(artist_album_cover_url(album_artist, album), 200, 200)
+}
+
+pub fn artist_album_cover_url(album_artist: &String, album: &String) -> String {
+    format!(
+        "/artists/{}/albums/{}/cover.jpg",
+        ID::new(&album_artist).hashed(),
+        ID::new(&album).hashed(),
     )
 }
 
@@ -278,6 +296,66 @@ pub fn artist_album_disc_track_play_url(track: &FullTrackMetadata) -> String {
     )
 }
 
+pub fn movie_link(movie: &MovieMetadata) -> String {
+    a(
+        format!("/movies/{}", ID::new(&movie.relative_path).hashed()),
+        &movie.title,
+    )
+}
+
+pub fn movie_video_url(movie: &MovieMetadata) -> String {
+    format!("/movies/{}/video", ID::new(&movie.relative_path).hashed())
+}
+
+pub fn show_link(show_name: &String) -> String {
+    a(
+        format!("/shows/{}/seasons", ID::new(show_name).hashed()),
+        show_name,
+    )
+}
+
+pub fn show_season_link<S: ToString>(show_name: &String, season_no: u32, contents: S) -> String {
+    a(
+        format!(
+            "/shows/{}/seasons/{}/episodes",
+            ID::new(show_name).hashed(),
+            season_no
+        ),
+        contents,
+    )
+}
+
+pub fn show_episode_link(episode: &ShowMetadata) -> String {
+    a(
+        format!(
+            "/shows/{}/seasons/{}/episodes/{}",
+            ID::new(&episode.show).hashed(),
+            episode.season_number,
+            episode.episode_sort,
+        ),
+        episode.local_display_name(),
+    )
+}
+
+pub fn show_episode_video_url(episode: &ShowMetadata) -> String {
+    format!(
+        "/shows/{}/seasons/{}/episodes/{}/video",
+        ID::new(&episode.show).hashed(),
+        episode.season_number,
+        episode.episode_sort,
+    )
+}
+
+pub fn duration_text(duration: &std::time::Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
 pub fn artist_album_disc_track_play_link(
     track: &FullTrackMetadata,
     play_priority: PlayPriority,
@@ -320,6 +398,11 @@ pub fn artist_album_play_link(
     tracks: &Vec<FullTrackMetadata>,
     play_priority: PlayPriority,
 ) -> String {
+    tracks_play_link(tracks, play_priority)
+}
+
+/// Play all of the tracks, in order, e.g. all of an artist's albums
+pub fn tracks_play_link(tracks: &Vec<FullTrackMetadata>, play_priority: PlayPriority) -> String {
     match play_priority {
         PlayPriority::Now => a(
             format!(
@@ -367,9 +450,21 @@ fn track_list_playback_json(tracks: &Vec<FullTrackMetadata>) -> String {
 
 fn track_playback_json(track: &FullTrackMetadata) -> String {
     format!(
-        "{{src: '{}', title: '{}', contenttype: '{}'}}",
+        "{{artist_id: '{}', album_id: '{}', disc: {}, track: {}, \
+        src: '{}', title: '{}', artist: '{}', album: '{}', cover: '{}', contenttype: '{}'}}",
+        track.album_artist_id.hashed(),
+        track.album_id.hashed(),
+        track.disc,
+        track.track,
         artist_album_disc_track_play_url(&track),
-        track.title.replace("'", "&quot").replace("\"", "&quot"),
+        js_string_escape(&track.title),
+        js_string_escape(&track.track_artist),
+        js_string_escape(&track.album),
+        artist_album_cover_url(&track.album_artist, &track.album),
         contenttype::audio_content_type_from_ext(&track.ext),
     )
 }
+
+fn js_string_escape(text: &str) -> String {
+    text.replace("'", "&quot").replace("\"", "&quot")
+}
@@ -1,3 +1,4 @@
+use crate::contenttype;
 use crate::ds::Datastore;
 use crate::model::{ArtistInfo, PlayPriority};
 
@@ -6,13 +7,25 @@ use super::elements::*;
 use crate::model;
 
 pub fn not_found() -> String {
+    error_page("Page Not Found".to_string())
+}
+
+pub fn bad_request(message: &str) -> String {
+    error_page(format!("Bad Request: {}", message))
+}
+
+pub fn internal_error(message: &str) -> String {
+    error_page(format!("Internal Error: {}", message))
+}
+
+fn error_page(message: String) -> String {
     html(
         "en-US",
         vec![
             head(vec![meta("utf-8")]),
             body(vec![
                 div(NOATTRS, vec![a("/", "Back Home")]),
-                div(NOATTRS, vec!["Page Not Found".to_string()]),
+                div(NOATTRS, vec![message]),
             ]),
         ],
     )
@@ -26,6 +39,8 @@ pub fn index() -> (Vec<(String, String)>, String) {
             h1(a("/albums/", "Albums")),
             h1(a("/tracks/", "Tracks")),
             h1(a("/tree/", "Tree")),
+            h1(a("/movies/", "Movies")),
+            h1(a("/shows/", "Shows")),
         ]]),
     )
 }
@@ -63,24 +78,46 @@ pub fn artist_albums(artist_id: model::ID, ds: Datastore) -> (Vec<(String, Strin
                 album_artist.artist_name.clone(),
             ),
         ],
-        table(
-            ds.list_artist_albums(artist_id)
+        {
+            let albums: Vec<(model::AlbumInfo, Vec<model::FullTrackMetadata>)> = ds
+                .list_artist_albums(artist_id)
                 .into_iter()
                 .map(|album_info| {
                     let album_tracks = ds.list_artist_album_tracks(
                         album_artist.artist_id.clone(),
                         album_info.album_id.clone(),
                     );
-                    vec![
-                        artist_album_image(&album_artist.artist_name, &album_info.album_name),
-                        artist_album_play_link(&album_tracks, PlayPriority::Now),
-                        artist_album_play_link(&album_tracks, PlayPriority::Next),
-                        artist_album_play_link(&album_tracks, PlayPriority::Append),
-                        artist_album_link(&album_artist.artist_name, &album_info.album_name),
-                    ]
+                    (album_info, album_tracks)
                 })
-                .collect(),
-        ),
+                .collect();
+            let artist_tracks: Vec<model::FullTrackMetadata> = albums
+                .iter()
+                .flat_map(|(_album_info, album_tracks)| album_tracks.iter().cloned())
+                .collect();
+
+            let mut trs = vec![vec![
+                "".to_string(),
+                tracks_play_link(&artist_tracks, PlayPriority::Now),
+                tracks_play_link(&artist_tracks, PlayPriority::Next),
+                tracks_play_link(&artist_tracks, PlayPriority::Append),
+                "All Albums".to_string(),
+            ]];
+            trs.append(
+                &mut albums
+                    .into_iter()
+                    .map(|(album_info, album_tracks)| {
+                        vec![
+                            artist_album_image(&album_artist.artist_name, &album_info.album_name),
+                            artist_album_play_link(&album_tracks, PlayPriority::Now),
+                            artist_album_play_link(&album_tracks, PlayPriority::Next),
+                            artist_album_play_link(&album_tracks, PlayPriority::Append),
+                            artist_album_link(&album_artist.artist_name, &album_info.album_name),
+                        ]
+                    })
+                    .collect(),
+            );
+            table(trs)
+        },
     )
 }
 
@@ -269,6 +306,226 @@ pub fn tracks(ds: Datastore) -> (Vec<(String, String)>, String) {
     )
 }
 
+pub fn movies(ds: Datastore) -> (Vec<(String, String)>, String) {
+    (
+        vec![("/movies".to_string(), "Movies".to_string())],
+        table({
+            let mut trs = vec![vec![
+                "Title".to_string(),
+                "Released".to_string(),
+                "Length".to_string(),
+            ]];
+            trs.append(
+                &mut ds
+                    .list_movies()
+                    .iter()
+                    .map(|movie| {
+                        vec![
+                            movie_link(movie),
+                            match movie.extra.as_ref().and_then(|extra| extra.release) {
+                                Some(release) => release.to_string(),
+                                None => "".to_string(),
+                            },
+                            duration_text(&movie.duration),
+                        ]
+                    })
+                    .collect(),
+            );
+            trs
+        }),
+    )
+}
+
+pub fn movie(movie_id: model::ID, ds: Datastore) -> (Vec<(String, String)>, String) {
+    let movie = ds.find_movie(movie_id).unwrap();
+    (
+        vec![
+            ("/movies".to_string(), "Movies".to_string()),
+            (
+                format!("/movies/{}", movie_id.hashed()),
+                movie.title.clone(),
+            ),
+        ],
+        table(vec![
+            vec![video(
+                movie_video_url(movie),
+                contenttype::video_content_type_from_ext(file_ext(&movie.path)),
+            )],
+            vec![table({
+                let mut trs = vec![vec!["Length".to_string(), duration_text(&movie.duration)]];
+                if let Some(ref extra) = movie.extra {
+                    if let Some(release) = extra.release {
+                        trs.push(vec!["Released".to_string(), release.to_string()]);
+                    }
+                    trs.append(&mut vec![
+                        vec!["Genres".to_string(), extra.genres.join(", ")],
+                        vec!["Directors".to_string(), extra.directors.join(", ")],
+                        vec!["Cast".to_string(), extra.cast.join(", ")],
+                    ]);
+                }
+                trs
+            })],
+        ]),
+    )
+}
+
+pub fn shows(ds: Datastore) -> (Vec<(String, String)>, String) {
+    (
+        vec![("/shows".to_string(), "Shows".to_string())],
+        table(vec![
+            vec![h2("Continue Watching")],
+            vec![table({
+                let mut trs = vec![vec![
+                    "Show".to_string(),
+                    "Last Watched".to_string(),
+                    "Up Next".to_string(),
+                ]];
+                trs.append(
+                    &mut ds
+                        .list_continue_watching()
+                        .into_iter()
+                        .map(|(last_episode, next_episode)| {
+                            vec![
+                                show_link(&last_episode.show),
+                                show_episode_link(&last_episode),
+                                show_episode_link(&next_episode),
+                            ]
+                        })
+                        .collect(),
+                );
+                trs
+            })],
+            vec![h2("All Shows")],
+            vec![table(
+                ds.list_shows()
+                    .into_iter()
+                    .map(|show| vec![show_link(&show.get_name())])
+                    .collect(),
+            )],
+        ]),
+    )
+}
+
+pub fn show_seasons(show_id: model::ID, ds: Datastore) -> (Vec<(String, String)>, String) {
+    let show = ds.find_show(show_id).unwrap();
+    (
+        vec![
+            ("/shows".to_string(), "Shows".to_string()),
+            (
+                format!("/shows/{}/seasons", show_id.hashed()),
+                show.get_name(),
+            ),
+        ],
+        table(
+            show.get_seasons()
+                .values()
+                .map(|season| {
+                    vec![
+                        show_season_link(
+                            &show.get_name(),
+                            season.get_season_number(),
+                            season.pretty_display(),
+                        ),
+                        format!("{} episodes", season.get_episodes().len()),
+                    ]
+                })
+                .collect(),
+        ),
+    )
+}
+
+pub fn show_season_episodes(
+    show_id: model::ID,
+    season_no: u32,
+    ds: Datastore,
+) -> (Vec<(String, String)>, String) {
+    let show = ds.find_show(show_id).unwrap();
+    let season = show.get_season(&season_no).unwrap();
+    (
+        vec![
+            ("/shows".to_string(), "Shows".to_string()),
+            (
+                format!("/shows/{}/seasons", show_id.hashed()),
+                show.get_name(),
+            ),
+            (
+                format!("/shows/{}/seasons/{}/episodes", show_id.hashed(), season_no),
+                season.pretty_display(),
+            ),
+        ],
+        table({
+            let mut trs = vec![vec![
+                "#".to_string(),
+                "Episode".to_string(),
+                "Length".to_string(),
+            ]];
+            trs.append(
+                &mut season
+                    .get_episodes()
+                    .values()
+                    .map(|episode| {
+                        vec![
+                            format!("{}", episode.episode_sort),
+                            show_episode_link(episode),
+                            duration_text(&episode.duration),
+                        ]
+                    })
+                    .collect(),
+            );
+            trs
+        }),
+    )
+}
+
+pub fn show_season_episode(
+    show_id: model::ID,
+    season_no: u32,
+    episode_no: u32,
+    ds: Datastore,
+) -> (Vec<(String, String)>, String) {
+    let show = ds.find_show(show_id).unwrap();
+    let season = show.get_season(&season_no).unwrap();
+    let episode = season.get_episode(&episode_no).unwrap();
+    (
+        vec![
+            ("/shows".to_string(), "Shows".to_string()),
+            (
+                format!("/shows/{}/seasons", show_id.hashed()),
+                show.get_name(),
+            ),
+            (
+                format!("/shows/{}/seasons/{}/episodes", show_id.hashed(), season_no),
+                season.pretty_display(),
+            ),
+            (
+                format!(
+                    "/shows/{}/seasons/{}/episodes/{}",
+                    show_id.hashed(),
+                    season_no,
+                    episode_no
+                ),
+                episode.local_display_name(),
+            ),
+        ],
+        table(vec![
+            vec![video(
+                show_episode_video_url(episode),
+                contenttype::video_content_type_from_ext(file_ext(&episode.full_path)),
+            )],
+            vec![table(vec![
+                vec!["Title".to_string(), episode.title.clone()],
+                vec!["Length".to_string(), duration_text(&episode.duration)],
+            ])],
+        ]),
+    )
+}
+
+fn file_ext(path: &std::path::Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
 pub fn tree(ds: Datastore) -> (Vec<(String, String)>, String) {
     (vec![("/tree".to_string(), "Tree".to_string())], {
         table(
@@ -63,6 +63,14 @@ fn playback_controls() -> String {
     )
 }
 
+/// The play queue, which `main.js` fills in once it has restored the queue from the server
 fn playlist() -> String {
-    div(NOATTRS, vec!["The Playlist"])
+    div(
+        NOATTRS,
+        vec![
+            h3("Play Queue"),
+            a("javascript:{ clear_play_queue(); }", "Clear"),
+            div(vec![("id", "playlist-list")], Vec::<String>::new()),
+        ],
+    )
 }
@@ -1,59 +1,177 @@
+mod api;
+mod auth;
+mod fileserve;
 mod html;
 mod nonhtml;
+mod params;
+mod subsonic;
 
 use std::convert::Infallible;
+use std::net::SocketAddr;
 
-use hyper::{header, Body, Method, Request, Response, StatusCode};
+use chrono::Local;
+use hyper::body::HttpBody;
+use hyper::{header, Body, HeaderMap, Method, Request, Response};
 
 use crate::ds::Datastore;
-use crate::model;
 
 use html::main_html_wrapper;
+use params::RequestError;
+
+/// Request bodies are small forms and json (a Subsonic form is a few KB)
+const MAX_BODY_BYTES: usize = 64 * 1024;
 
 pub async fn service_handler(
     req: Request<Body>,
+    remote_addr: SocketAddr,
     ds: Datastore,
 ) -> Result<Response<Body>, Infallible> {
-    let path_frags = req
-        .uri()
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+
+    // the Subsonic api checks its own credentials, which come in the query
+    let resp = match path.starts_with("/rest/") {
+        true => route(req, ds).await?,
+        false => match auth::authenticate(req.headers(), ds.get_config()) {
+            Ok(maybe_user) => {
+                let mut resp = route(req, ds).await?;
+                if let Some(user) = maybe_user {
+                    resp.extensions_mut().insert(user);
+                }
+                resp
+            }
+            Err(unauthorized) => unauthorized.to_response(),
+        },
+    };
+
+    println!(
+        "{} {} [{}] \"{} {}\" {}",
+        remote_addr.ip(),
+        resp.extensions()
+            .get::<auth::AuthenticatedUser>()
+            .map(|user| user.0.as_str())
+            .unwrap_or("-"),
+        Local::now().format("%d/%b/%Y:%H:%M:%S %z"),
+        method,
+        path,
+        resp.status().as_u16()
+    );
+    Ok(resp)
+}
+
+async fn route(req: Request<Body>, ds: Datastore) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = req.into_parts();
+    let path_frags = parts
+        .uri
         .path()
         .split('/')
         .filter(|x| !x.is_empty())
         .collect::<Vec<&str>>();
 
-    println!(
-        "responding to: {} ({}) ({:?})",
-        req.uri().path(),
-        req.method(),
-        path_frags
-    );
-    match (req.method(), path_frags.as_slice()) {
+    match (&parts.method, path_frags.as_slice()) {
         // Serve hard-coded images
         (&Method::GET, ["favicon.ico"]) => nonhtml::image("favicon.ico"),
         (&Method::GET, ["static", asset_name]) => nonhtml::static_assets(asset_name),
         (&Method::GET, ["images", image_name]) => nonhtml::image(image_name),
         (&Method::GET, ["artists", artist_id, "albums", album_id, "cover.jpg"]) => {
-            nonhtml::album_art(
-                model::ID::from_u64(artist_id.parse::<u64>().unwrap()),
-                model::ID::from_u64(album_id.parse::<u64>().unwrap()),
-                ds,
-            )
+            match params::artist_album(&ds, artist_id, album_id) {
+                Ok((artist_id, album_id)) => {
+                    nonhtml::album_art(artist_id, album_id, &parts.headers, ds)
+                }
+                Err(e) => nonhtml::error(e),
+            }
         }
         (
             &Method::GET,
-            ["artists", artist_id, "albums", album_id, "disc_tracks", disc_no, "tracks", track_no, "audio", filename_with_ext],
-        ) => nonhtml::audio(
-            model::ID::from_u64(artist_id.parse::<u64>().unwrap()),
-            model::ID::from_u64(album_id.parse::<u64>().unwrap()),
-            disc_no.parse::<u64>().unwrap(),
-            track_no.parse::<u64>().unwrap(),
-            filename_with_ext.to_string(),
-            ds,
-        ),
+            ["artists", artist_id, "albums", album_id, "disc_tracks", disc_no, "tracks", track_no, "audio", _filename_with_ext],
+        ) => {
+            let query = api::query::Query::parse(parts.uri.query());
+            match params::artist_album_track(&ds, artist_id, album_id, disc_no, track_no).and_then(
+                |found| {
+                    params::transcode_profile(query.get("format"), query.get("bitrate"))
+                        .map(|profile| (found, profile))
+                },
+            ) {
+                Ok(((artist_id, album_id, disc_no, track_no), profile)) => {
+                    nonhtml::audio(
+                        artist_id,
+                        album_id,
+                        disc_no,
+                        track_no,
+                        profile,
+                        &parts.headers,
+                        ds,
+                    )
+                    .await
+                }
+                Err(e) => nonhtml::error(e),
+            }
+        }
+        (&Method::GET, ["movies", movie_id, "video"]) => match params::movie(&ds, movie_id) {
+            Ok(movie_id) => nonhtml::movie_video(movie_id, &parts.headers, ds),
+            Err(e) => nonhtml::error(e),
+        },
+        (
+            &Method::GET,
+            ["shows", show_id, "seasons", season_no, "episodes", episode_no, "video"],
+        ) => match params::show_season_episode(&ds, show_id, season_no, episode_no) {
+            Ok((show_id, season_no, episode_no)) => {
+                nonhtml::show_episode_video(show_id, season_no, episode_no, &parts.headers, ds)
+            }
+            Err(e) => nonhtml::error(e),
+        },
+        (method, ["api", "v1", api_frags @ ..]) => match read_body(&parts.headers, body).await {
+            Ok(body) => Ok(api::handle_api(
+                method,
+                api_frags,
+                parts.uri.query(),
+                &body,
+                ds,
+            )),
+            Err(e) => Ok(api::error_response(e)),
+        },
+        (_, ["rest", method_name]) => match read_body(&parts.headers, body).await {
+            Ok(body) => Ok(subsonic::handle_subsonic(
+                method_name,
+                &api::query::Query::parse(parts.uri.query()).with_form_body(&body),
+                &parts.headers,
+                ds,
+            )
+            .await),
+            Err(e) => Ok(api::error_response(e)),
+        },
         (method, frags) => handle_pages(method, frags, ds),
     }
 }
 
+/// Read a request body, refusing one over `MAX_BODY_BYTES` before (or while) it is read, since
+/// the Subsonic api reads it before checking credentials
+async fn read_body(headers: &HeaderMap, mut body: Body) -> Result<Vec<u8>, RequestError> {
+    let too_large =
+        || RequestError::PayloadTooLarge(format!("request body is over {} bytes", MAX_BODY_BYTES));
+
+    let content_length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if let Some(content_length) = content_length {
+        if content_length > MAX_BODY_BYTES as u64 {
+            return Err(too_large());
+        }
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk
+            .map_err(|e| RequestError::BadRequest(format!("could not read request body: {}", e)))?;
+        if bytes.len() + chunk.len() > MAX_BODY_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
 fn handle_pages(
     method: &Method,
     frags: &[&str],
@@ -65,30 +183,43 @@ fn handle_pages(
         (&Method::GET, ["tree"]) => Ok(html::tree(ds)),
         (&Method::GET, ["oldtree"]) => Ok(html::old_tree(ds)),
         (&Method::GET, ["artists"]) => Ok(html::artists(ds)),
-        (&Method::GET, ["artists", artist_id, "albums"]) => Ok(html::artist_albums(
-            model::ID::from_u64(artist_id.parse::<u64>().unwrap()),
-            ds,
-        )),
+        (&Method::GET, ["artists", artist_id, "albums"]) => {
+            params::artist(&ds, artist_id).map(|artist_id| html::artist_albums(artist_id, ds))
+        }
         (&Method::GET, ["artists", artist_id, "albums", album_id, "disc_tracks"]) => {
-            Ok(html::artist_album_tracks(
-                model::ID::from_u64(artist_id.parse::<u64>().unwrap()),
-                model::ID::from_u64(album_id.parse::<u64>().unwrap()),
-                ds,
-            ))
+            params::artist_album(&ds, artist_id, album_id)
+                .map(|(artist_id, album_id)| html::artist_album_tracks(artist_id, album_id, ds))
         }
         (
             &Method::GET,
             ["artists", artist_id, "albums", album_id, "disc_tracks", disc_no, "tracks", track_no, "info"],
-        ) => Ok(html::artist_album_track_info(
-            model::ID::from_u64(artist_id.parse::<u64>().unwrap()),
-            model::ID::from_u64(album_id.parse::<u64>().unwrap()),
-            disc_no.parse::<u64>().unwrap(),
-            track_no.parse::<u64>().unwrap(),
-            ds,
-        )),
+        ) => params::artist_album_track(&ds, artist_id, album_id, disc_no, track_no).map(
+            |(artist_id, album_id, disc_no, track_no)| {
+                html::artist_album_track_info(artist_id, album_id, disc_no, track_no, ds)
+            },
+        ),
         (&Method::GET, ["albums"]) => Ok(html::albums(ds)),
         (&Method::GET, ["tracks"]) => Ok(html::tracks(ds)),
-        _ => Err(html::not_found()),
+        (&Method::GET, ["movies"]) => Ok(html::movies(ds)),
+        (&Method::GET, ["movies", movie_id]) => {
+            params::movie(&ds, movie_id).map(|movie_id| html::movie(movie_id, ds))
+        }
+        (&Method::GET, ["shows"]) => Ok(html::shows(ds)),
+        (&Method::GET, ["shows", show_id, "seasons"]) => {
+            params::show(&ds, show_id).map(|show_id| html::show_seasons(show_id, ds))
+        }
+        (&Method::GET, ["shows", show_id, "seasons", season_no, "episodes"]) => {
+            params::show_season(&ds, show_id, season_no)
+                .map(|(show_id, season_no)| html::show_season_episodes(show_id, season_no, ds))
+        }
+        (&Method::GET, ["shows", show_id, "seasons", season_no, "episodes", episode_no]) => {
+            params::show_season_episode(&ds, show_id, season_no, episode_no).map(
+                |(show_id, season_no, episode_no)| {
+                    html::show_season_episode(show_id, season_no, episode_no, ds)
+                },
+            )
+        }
+        _ => Err(RequestError::NotFound(frags.join("/"))),
     };
     match found {
         Ok((breadcrumbs, content)) => Ok(Response::new(Body::from(main_html_wrapper(
@@ -102,14 +233,61 @@ fn handle_pages(
             );
             resp
         }),
-        Err(not_found_resp) => {
-            let mut resp = Response::new(Body::from(not_found_resp));
+        Err(e) => {
+            let status = e.status();
+            let mut resp = Response::new(Body::from(match e {
+                RequestError::BadRequest(message) | RequestError::PayloadTooLarge(message) => {
+                    html::bad_request(&message)
+                }
+                RequestError::NotFound(_) => html::not_found(),
+                RequestError::Internal(message) => html::internal_error(&message),
+            }));
             resp.headers_mut().insert(
                 header::CONTENT_TYPE,
                 header::HeaderValue::from_static("text/html"),
             );
-            *resp.status_mut() = StatusCode::NOT_FOUND;
+            *resp.status_mut() = status;
             Ok(resp)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with_length(length: usize) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_LENGTH, length.into());
+        headers
+    }
+
+    #[tokio::test]
+    async fn small_body_is_read() {
+        let body = read_body(&headers_with_length(5), Body::from("u=bob"))
+            .await
+            .unwrap();
+        assert_eq!(body, b"u=bob");
+    }
+
+    #[tokio::test]
+    async fn declared_large_body_is_refused_unread() {
+        let result = read_body(&headers_with_length(MAX_BODY_BYTES + 1), Body::empty()).await;
+        assert!(matches!(result, Err(RequestError::PayloadTooLarge(_))));
+    }
+
+    #[tokio::test]
+    async fn undeclared_large_body_is_refused() {
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            let chunk = vec![b'a'; 1024];
+            for _ in 0..(MAX_BODY_BYTES / 1024 + 1) {
+                if sender.send_data(chunk.clone().into()).await.is_err() {
+                    return;
+                }
+            }
+        });
+        let result = read_body(&HeaderMap::new(), body).await;
+        assert!(matches!(result, Err(RequestError::PayloadTooLarge(_))));
+    }
+}
@@ -1,12 +1,17 @@
 use std::convert::Infallible;
 use std::fs;
 use std::path;
+use std::time::UNIX_EPOCH;
 
-use hyper::{header, Body, Response};
+use hyper::{header, Body, HeaderMap, Response};
 
 use crate::contenttype;
 use crate::ds::Datastore;
 use crate::model;
+use crate::transcode;
+
+use super::fileserve;
+use super::params::RequestError;
 
 pub fn static_assets(asset_name: &str) -> Result<Response<Body>, Infallible> {
     Ok(Response::new(Body::from(match asset_name {
@@ -55,43 +60,104 @@ pub fn image(image_name: &str) -> Result<Response<Body>, Infallible> {
 pub fn album_art(
     artist_id: model::ID,
     album_id: model::ID,
+    request_headers: &HeaderMap,
     ds: Datastore,
 ) -> Result<Response<Body>, Infallible> {
-    Ok(Response::new(Body::from(
-        ds.get_artist_album_cover(artist_id, album_id),
-    )))
-    .map(|mut resp| {
-        resp.headers_mut().insert(
-            header::CONTENT_TYPE,
-            header::HeaderValue::from_static("image/jpg"),
-        );
-        resp
-    })
+    let cover_path = ds.get_artist_album_cover_path(artist_id, album_id);
+    let last_modified = fs::metadata(&cover_path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(UNIX_EPOCH);
+
+    Ok(fileserve::serve_file(
+        &cover_path,
+        "image/jpg",
+        last_modified,
+        request_headers,
+    ))
 }
 
-pub fn audio(
+pub async fn audio(
     artist_id: model::ID,
     album_id: model::ID,
     disc_no: u64,
     track_no: u64,
-    filename_with_ext: String,
+    profile: Option<transcode::TranscodeProfile>,
+    request_headers: &HeaderMap,
     ds: Datastore,
 ) -> Result<Response<Body>, Infallible> {
-    Ok(Response::new(Body::from(
-        ds.get_artist_album_track_audio_from_id(artist_id, album_id, disc_no, track_no),
-    )))
-    .map(|mut resp| {
-        resp.headers_mut().insert(
-            header::CONTENT_TYPE,
-            header::HeaderValue::from_static(contenttype::audio_content_type_from_ext(
-                path::Path::new(&filename_with_ext)
-                    .extension()
-                    .unwrap()
-                    .to_string_lossy()
-                    .to_string()
-                    .as_str(),
-            )),
-        );
-        resp
-    })
+    let track = ds.get_artist_album_track_name_from_id(artist_id, album_id, disc_no, track_no);
+
+    let (audio_path, ext, last_modified) = match profile {
+        Some(profile) => match ds.get_transcoded_audio(&track, &profile).await {
+            Ok((audio_path, last_modified)) => {
+                (audio_path, profile.format.ext().to_string(), last_modified)
+            }
+            Err(e) => {
+                println!("could not transcode: {}", e);
+                return error(RequestError::Internal(format!(
+                    "could not transcode track {} on disc {}",
+                    track_no, disc_no
+                )));
+            }
+        },
+        None => (track.path.clone(), track.ext.clone(), track.last_modified),
+    };
+
+    Ok(fileserve::serve_file(
+        &audio_path,
+        contenttype::audio_content_type_from_ext(ext),
+        last_modified,
+        request_headers,
+    ))
+}
+
+pub fn movie_video(
+    movie_id: model::ID,
+    request_headers: &HeaderMap,
+    ds: Datastore,
+) -> Result<Response<Body>, Infallible> {
+    let movie = ds.find_movie(movie_id).unwrap();
+    Ok(fileserve::serve_file(
+        &movie.path,
+        video_content_type(&movie.path),
+        movie.last_modified,
+        request_headers,
+    ))
+}
+
+pub fn show_episode_video(
+    show_id: model::ID,
+    season_no: u32,
+    episode_no: u32,
+    request_headers: &HeaderMap,
+    ds: Datastore,
+) -> Result<Response<Body>, Infallible> {
+    let episode = ds
+        .find_show_episode(show_id, season_no, episode_no)
+        .unwrap();
+    Ok(fileserve::serve_file(
+        &episode.full_path,
+        video_content_type(&episode.full_path),
+        episode.last_modified,
+        request_headers,
+    ))
+}
+
+fn video_content_type(video_path: &path::Path) -> &'static str {
+    contenttype::video_content_type_from_ext(
+        video_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    )
+}
+
+pub fn error(e: RequestError) -> Result<Response<Body>, Infallible> {
+    let mut resp = Response::new(Body::from(e.message().to_string()));
+    *resp.status_mut() = e.status();
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("text/plain"),
+    );
+    Ok(resp)
 }
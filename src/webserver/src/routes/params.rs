@@ -0,0 +1,221 @@
+use hyper::StatusCode;
+
+use crate::clients;
+use crate::ds::Datastore;
+use crate::model;
+use crate::transcode;
+
+/// Why a request could not be served, independent of how the error is rendered
+#[derive(Debug)]
+pub enum RequestError {
+    BadRequest(String),
+    NotFound(String),
+    PayloadTooLarge(String),
+    Internal(String),
+}
+
+impl RequestError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            RequestError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            RequestError::NotFound(_) => StatusCode::NOT_FOUND,
+            RequestError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            RequestError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            RequestError::BadRequest(message) => message,
+            RequestError::NotFound(message) => message,
+            RequestError::PayloadTooLarge(message) => message,
+            RequestError::Internal(message) => message,
+        }
+    }
+}
+
+pub fn parse_id(raw: &str, what: &str) -> Result<model::ID, RequestError> {
+    raw.parse::<u64>()
+        .map(model::ID::from_u64)
+        .map_err(|_| RequestError::BadRequest(format!("invalid {} id: {}", what, raw)))
+}
+
+pub fn parse_no(raw: &str, what: &str) -> Result<u64, RequestError> {
+    raw.parse::<u64>()
+        .map_err(|_| RequestError::BadRequest(format!("invalid {} number: {}", what, raw)))
+}
+
+/// Parse an artist id from the path, making sure the artist exists
+pub fn artist(ds: &Datastore, raw_artist_id: &str) -> Result<model::ID, RequestError> {
+    let artist_id = parse_id(raw_artist_id, "artist")?;
+    match ds.find_artist(artist_id) {
+        Some(_) => Ok(artist_id),
+        None => Err(RequestError::NotFound(format!(
+            "no artist with id: {}",
+            raw_artist_id
+        ))),
+    }
+}
+
+/// Parse artist and album ids from the path, making sure the album exists
+pub fn artist_album(
+    ds: &Datastore,
+    raw_artist_id: &str,
+    raw_album_id: &str,
+) -> Result<(model::ID, model::ID), RequestError> {
+    let artist_id = artist(ds, raw_artist_id)?;
+    let album_id = parse_id(raw_album_id, "album")?;
+    match ds.find_artist_album(artist_id, album_id) {
+        Some(_) => Ok((artist_id, album_id)),
+        None => Err(RequestError::NotFound(format!(
+            "no album with id: {}",
+            raw_album_id
+        ))),
+    }
+}
+
+/// Parse artist, album, disc and track from the path, making sure the track exists
+pub fn artist_album_track(
+    ds: &Datastore,
+    raw_artist_id: &str,
+    raw_album_id: &str,
+    raw_disc_no: &str,
+    raw_track_no: &str,
+) -> Result<(model::ID, model::ID, u64, u64), RequestError> {
+    let (artist_id, album_id) = artist_album(ds, raw_artist_id, raw_album_id)?;
+    let disc_no = parse_no(raw_disc_no, "disc")?;
+    let track_no = parse_no(raw_track_no, "track")?;
+    match ds.find_artist_album_track(artist_id, album_id, disc_no, track_no) {
+        Some(_) => Ok((artist_id, album_id, disc_no, track_no)),
+        None => Err(RequestError::NotFound(format!(
+            "no track {} on disc {}",
+            raw_track_no, raw_disc_no
+        ))),
+    }
+}
+
+/// Parse a movie id from the path, making sure the movie exists
+pub fn movie(ds: &Datastore, raw_movie_id: &str) -> Result<model::ID, RequestError> {
+    let movie_id = parse_id(raw_movie_id, "movie")?;
+    match ds.find_movie(movie_id) {
+        Some(_) => Ok(movie_id),
+        None => Err(RequestError::NotFound(format!(
+            "no movie with id: {}",
+            raw_movie_id
+        ))),
+    }
+}
+
+/// Parse a show id from the path, making sure the show exists
+pub fn show(ds: &Datastore, raw_show_id: &str) -> Result<model::ID, RequestError> {
+    let show_id = parse_id(raw_show_id, "show")?;
+    match ds.find_show(show_id) {
+        Some(_) => Ok(show_id),
+        None => Err(RequestError::NotFound(format!(
+            "no show with id: {}",
+            raw_show_id
+        ))),
+    }
+}
+
+/// Parse show and season from the path, making sure the season exists
+pub fn show_season(
+    ds: &Datastore,
+    raw_show_id: &str,
+    raw_season_no: &str,
+) -> Result<(model::ID, u32), RequestError> {
+    let show_id = show(ds, raw_show_id)?;
+    let season_no = parse_short_no(raw_season_no, "season")?;
+    match ds
+        .find_show(show_id)
+        .and_then(|show| show.get_season(&season_no))
+    {
+        Some(_) => Ok((show_id, season_no)),
+        None => Err(RequestError::NotFound(format!(
+            "no season {} of show {}",
+            raw_season_no, raw_show_id
+        ))),
+    }
+}
+
+/// Parse show, season and episode from the path, making sure the episode exists
+pub fn show_season_episode(
+    ds: &Datastore,
+    raw_show_id: &str,
+    raw_season_no: &str,
+    raw_episode_no: &str,
+) -> Result<(model::ID, u32, u32), RequestError> {
+    let (show_id, season_no) = show_season(ds, raw_show_id, raw_season_no)?;
+    let episode_no = parse_short_no(raw_episode_no, "episode")?;
+    match ds.find_show_episode(show_id, season_no, episode_no) {
+        Some(_) => Ok((show_id, season_no, episode_no)),
+        None => Err(RequestError::NotFound(format!(
+            "no episode {} in season {}",
+            raw_episode_no, raw_season_no
+        ))),
+    }
+}
+
+fn parse_short_no(raw: &str, what: &str) -> Result<u32, RequestError> {
+    raw.parse::<u32>()
+        .map_err(|_| RequestError::BadRequest(format!("invalid {} number: {}", what, raw)))
+}
+
+pub fn client(raw_client_id: &str) -> Result<clients::ClientID, RequestError> {
+    clients::ClientID::parse(raw_client_id).ok_or(RequestError::BadRequest(format!(
+        "invalid client id: {}",
+        raw_client_id
+    )))
+}
+
+/// Look up the track a request body points at
+pub fn track_key(
+    ds: &Datastore,
+    key: &clients::TrackKey,
+) -> Result<model::FullTrackMetadata, RequestError> {
+    ds.find_artist_album_track(
+        parse_id(&key.artist_id, "artist")?,
+        parse_id(&key.album_id, "album")?,
+        key.disc,
+        key.track,
+    )
+    .ok_or(RequestError::NotFound(format!(
+        "no track {} on disc {} of album {} by artist {}",
+        key.track, key.disc, key.album_id, key.artist_id
+    )))
+}
+
+/// Parse the optional `format` and `bitrate` query parameters of the audio route
+pub fn transcode_profile(
+    raw_format: Option<&str>,
+    raw_bitrate: Option<&str>,
+) -> Result<Option<transcode::TranscodeProfile>, RequestError> {
+    let format = match (raw_format, raw_bitrate) {
+        (None, None) => return Ok(None),
+        (None, Some(_)) => {
+            return Err(RequestError::BadRequest(
+                "bitrate needs a format to transcode to".to_string(),
+            ))
+        }
+        (Some(raw_format), _) => transcode::AudioFormat::parse(raw_format).ok_or(
+            RequestError::BadRequest(format!("unsupported format: {}", raw_format)),
+        )?,
+    };
+    let bitrate = match raw_bitrate {
+        Some(raw_bitrate) => match raw_bitrate.parse::<u32>() {
+            Ok(bitrate) if (transcode::MIN_BITRATE..=transcode::MAX_BITRATE).contains(&bitrate) => {
+                bitrate
+            }
+            _ => {
+                return Err(RequestError::BadRequest(format!(
+                    "bitrate must be between {} and {}: {}",
+                    transcode::MIN_BITRATE,
+                    transcode::MAX_BITRATE,
+                    raw_bitrate
+                )))
+            }
+        },
+        None => transcode::DEFAULT_BITRATE,
+    };
+    Ok(Some(transcode::TranscodeProfile::new(format, bitrate)))
+}
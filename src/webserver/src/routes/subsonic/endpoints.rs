@@ -0,0 +1,499 @@
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use hyper::{Body, HeaderMap, Response};
+
+use crate::clients;
+use crate::contenttype;
+use crate::ds::Datastore;
+use crate::model::{AlbumInfo, ArtistInfo, FullTrackMetadata};
+use crate::transcode;
+
+use super::super::api::query::Query;
+use super::super::fileserve;
+use super::ids::SubsonicID;
+use super::response::Node;
+use super::SubsonicError;
+
+const MUSIC_FOLDER_ID: u32 = 0;
+
+const DEFAULT_SEARCH_COUNT: usize = 20;
+
+pub fn license() -> Node {
+    Node::new("license").attr("valid", true)
+}
+
+/// None of the OpenSubsonic extensions are supported yet
+pub fn open_subsonic_extensions() -> Vec<Node> {
+    Vec::new()
+}
+
+pub fn music_folders() -> Node {
+    Node::new("musicFolders").list(
+        "musicFolder",
+        vec![Node::new("musicFolder")
+            .attr("id", MUSIC_FOLDER_ID)
+            .attr("name", "Music")],
+    )
+}
+
+/// Artists grouped by their first letter, as both `getIndexes` and `getArtists` list them
+pub fn artist_indexes(name: &str, ds: &Datastore) -> Node {
+    let mut indexes: Vec<(String, Vec<Node>)> = Vec::new();
+    for artist in ds.list_artists().into_iter() {
+        let first_letter = artist
+            .artist_name
+            .chars()
+            .next()
+            .map(|c| c.to_uppercase().to_string())
+            .unwrap_or("#".to_string());
+        let artist = artist_node(&artist, ds);
+        match indexes.last_mut() {
+            Some((letter, artists)) if *letter == first_letter => artists.push(artist),
+            _ => indexes.push((first_letter, vec![artist])),
+        }
+    }
+
+    let node = Node::new(name).attr("ignoredArticles", "");
+    let node = match name {
+        "indexes" => node.attr("lastModified", 0),
+        _ => node,
+    };
+    node.list(
+        "index",
+        indexes
+            .into_iter()
+            .map(|(letter, artists)| {
+                Node::new("index")
+                    .attr("name", letter)
+                    .list("artist", artists)
+            })
+            .collect(),
+    )
+}
+
+pub fn artist(query: &Query, ds: &Datastore) -> Result<Node, SubsonicError> {
+    let artist_id = match required_id(query)? {
+        SubsonicID::Artist(artist_id) => artist_id,
+        _ => return Err(not_found(query)),
+    };
+    let artist = ds.find_artist(artist_id).ok_or_else(|| not_found(query))?;
+
+    let albums = ds
+        .list_artist_albums(artist_id)
+        .iter()
+        .map(|album| album_node(&artist, album, None, ds))
+        .collect();
+    Ok(artist_node(&artist, ds).list("album", albums))
+}
+
+pub fn album(query: &Query, ds: &Datastore) -> Result<Node, SubsonicError> {
+    let (artist_id, album_id) = match required_id(query)? {
+        SubsonicID::Album(artist_id, album_id) => (artist_id, album_id),
+        _ => return Err(not_found(query)),
+    };
+    let artist = ds.find_artist(artist_id).ok_or_else(|| not_found(query))?;
+    let album = ds
+        .find_artist_album(artist_id, album_id)
+        .ok_or_else(|| not_found(query))?;
+
+    let tracks = ds.list_artist_album_tracks(artist_id, album_id);
+    let songs = tracks
+        .iter()
+        .map(|track| song_node("song", track))
+        .collect();
+    Ok(album_node(&artist, &album, Some(&tracks), ds).list("song", songs))
+}
+
+pub fn song(query: &Query, ds: &Datastore) -> Result<Node, SubsonicError> {
+    let track = required_track(query, ds)?;
+    Ok(song_node("song", &track))
+}
+
+pub fn search(query: &Query, ds: &Datastore) -> Result<Node, SubsonicError> {
+    // clients ask for `""` (or nothing) to list everything, e.g. when syncing
+    let needle = query
+        .get("query")
+        .unwrap_or("")
+        .trim()
+        .trim_matches('"')
+        .to_lowercase();
+    let matches = |name: &str| needle.is_empty() || name.to_lowercase().contains(&needle);
+
+    let artists: Vec<Node> = ds
+        .list_artists()
+        .into_iter()
+        .filter(|artist| matches(&artist.artist_name))
+        .skip(count_param(query, "artistOffset", 0)?)
+        .take(count_param(query, "artistCount", DEFAULT_SEARCH_COUNT)?)
+        .map(|artist| artist_node(&artist, ds))
+        .collect();
+    let albums: Vec<Node> = ds
+        .list_albums()
+        .into_iter()
+        .filter(|(_artist, album)| matches(&album.album_name))
+        .skip(count_param(query, "albumOffset", 0)?)
+        .take(count_param(query, "albumCount", DEFAULT_SEARCH_COUNT)?)
+        .map(|(artist, album)| album_node(&artist, &album, None, ds))
+        .collect();
+    let songs: Vec<Node> = ds
+        .list_tracks()
+        .into_iter()
+        .filter(|track| matches(&track.title))
+        .skip(count_param(query, "songOffset", 0)?)
+        .take(count_param(query, "songCount", DEFAULT_SEARCH_COUNT)?)
+        .map(|track| song_node("song", &track))
+        .collect();
+
+    Ok(Node::new("searchResult3")
+        .list("artist", artists)
+        .list("album", albums)
+        .list("song", songs))
+}
+
+pub fn playlists(username: &str, ds: &Datastore) -> Node {
+    let listing = ds.list_playlists();
+    Node::new("playlists").list(
+        "playlist",
+        listing
+            .playlists
+            .iter()
+            .map(|playlist| {
+                let tracks = playlist_tracks(&playlist.tracks, ds);
+                playlist_node(playlist, &tracks, listing.last_modified, username)
+            })
+            .collect(),
+    )
+}
+
+pub fn playlist(query: &Query, username: &str, ds: &Datastore) -> Result<Node, SubsonicError> {
+    let playlist_id = match required_id(query)? {
+        SubsonicID::Playlist(playlist_id) => playlist_id,
+        _ => return Err(not_found(query)),
+    };
+    let listing = ds.list_playlists();
+    let playlist = listing
+        .playlists
+        .iter()
+        .find(|playlist| playlist.id == playlist_id)
+        .ok_or_else(|| not_found(query))?;
+
+    let tracks = playlist_tracks(&playlist.tracks, ds);
+    let entries = tracks
+        .iter()
+        .map(|track| song_node("entry", track))
+        .collect();
+    Ok(playlist_node(playlist, &tracks, listing.last_modified, username).list("entry", entries))
+}
+
+/// Record plays into the user's own tracker file; "now playing" updates are not recorded
+pub fn scrobble(query: &Query, username: &str, ds: &Datastore) -> Result<(), SubsonicError> {
+    if query.get("submission") == Some("false") {
+        return Ok(());
+    }
+
+    let client_id = clients::ClientID::subsonic(username).ok_or(SubsonicError::Generic(
+        format!("cannot record plays for user: {}", username),
+    ))?;
+
+    let raw_ids = query.get_all("id");
+    if raw_ids.is_empty() {
+        return Err(SubsonicError::MissingParam("id"));
+    }
+    let times = query.get_all("time");
+
+    for (index, raw_id) in raw_ids.into_iter().enumerate() {
+        let track = match SubsonicID::parse(raw_id) {
+            Some(SubsonicID::Track(artist_id, album_id, disc_no, track_no)) => ds
+                .find_artist_album_track(artist_id, album_id, disc_no, track_no)
+                .ok_or(SubsonicError::NotFound(format!(
+                    "no song with id: {}",
+                    raw_id
+                )))?,
+            _ => {
+                return Err(SubsonicError::NotFound(format!(
+                    "no song with id: {}",
+                    raw_id
+                )))
+            }
+        };
+        let listened_at = match times.get(index).map(|time| time.parse::<i64>()) {
+            Some(Ok(millis)) => Local
+                .timestamp_millis_opt(millis)
+                .single()
+                .unwrap_or(Local::now()),
+            _ => Local::now(),
+        };
+        ds.record_client_listen(&client_id, &track, listened_at)
+            .map_err(SubsonicError::Generic)?;
+    }
+    Ok(())
+}
+
+pub fn cover_art(
+    query: &Query,
+    request_headers: &HeaderMap,
+    ds: &Datastore,
+) -> Result<Response<Body>, SubsonicError> {
+    let (artist_id, album_id) = match required_id(query)? {
+        SubsonicID::Album(artist_id, album_id) => (artist_id, album_id),
+        SubsonicID::Track(artist_id, album_id, _, _) => (artist_id, album_id),
+        SubsonicID::Artist(artist_id) => (
+            artist_id,
+            ds.list_artist_albums(artist_id)
+                .first()
+                .map(|album| album.album_id)
+                .ok_or_else(|| not_found(query))?,
+        ),
+        SubsonicID::Playlist(_) => return Err(not_found(query)),
+    };
+    if ds.find_artist_album(artist_id, album_id).is_none() {
+        return Err(not_found(query));
+    }
+
+    let cover_path = ds.get_artist_album_cover_path(artist_id, album_id);
+    let last_modified = fs::metadata(&cover_path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(UNIX_EPOCH);
+    Ok(fileserve::serve_file(
+        &cover_path,
+        "image/jpeg",
+        last_modified,
+        request_headers,
+    ))
+}
+
+/// Transcodes when the client asks for a `format` or a `maxBitRate`, clients
+/// that ask for a format that is not supported get the original file
+pub async fn stream(
+    query: &Query,
+    request_headers: &HeaderMap,
+    ds: &Datastore,
+) -> Result<Response<Body>, SubsonicError> {
+    let track = required_track(query, ds)?;
+    let max_bitrate = match query.get("maxBitRate").map(|raw| raw.parse::<u32>()) {
+        Some(Ok(max_bitrate)) if max_bitrate > 0 => Some(max_bitrate),
+        Some(Err(_)) => {
+            return Err(SubsonicError::Generic(format!(
+                "invalid maxBitRate: {}",
+                query.get("maxBitRate").unwrap_or("")
+            )))
+        }
+        _ => None,
+    };
+    let format = match (query.get("format"), max_bitrate) {
+        (Some("raw"), _) => None,
+        (Some(raw_format), _) => transcode::AudioFormat::parse(raw_format),
+        (None, Some(_)) => Some(transcode::AudioFormat::Mp3),
+        (None, None) => None,
+    };
+    let profile = format.map(|format| {
+        transcode::TranscodeProfile::new(
+            format,
+            max_bitrate
+                .unwrap_or(transcode::DEFAULT_BITRATE)
+                .clamp(transcode::MIN_BITRATE, transcode::MAX_BITRATE),
+        )
+    });
+    serve_track(&track, profile, request_headers, ds).await
+}
+
+pub async fn download(
+    query: &Query,
+    request_headers: &HeaderMap,
+    ds: &Datastore,
+) -> Result<Response<Body>, SubsonicError> {
+    let track = required_track(query, ds)?;
+    serve_track(&track, None, request_headers, ds).await
+}
+
+async fn serve_track(
+    track: &FullTrackMetadata,
+    profile: Option<transcode::TranscodeProfile>,
+    request_headers: &HeaderMap,
+    ds: &Datastore,
+) -> Result<Response<Body>, SubsonicError> {
+    let (audio_path, ext, last_modified) = match profile {
+        Some(profile) => {
+            let (audio_path, last_modified) = ds
+                .get_transcoded_audio(track, &profile)
+                .await
+                .map_err(SubsonicError::Generic)?;
+            (audio_path, profile.format.ext().to_string(), last_modified)
+        }
+        None => (track.path.clone(), track.ext.clone(), track.last_modified),
+    };
+    Ok(fileserve::serve_file(
+        &audio_path,
+        contenttype::audio_content_type_from_ext(ext),
+        last_modified,
+        request_headers,
+    ))
+}
+
+fn artist_node(artist: &ArtistInfo, ds: &Datastore) -> Node {
+    let albums = ds.list_artist_albums(artist.artist_id);
+    Node::new("artist")
+        .attr("id", SubsonicID::Artist(artist.artist_id).to_string())
+        .attr("name", artist.artist_name.clone())
+        .attr("albumCount", albums.len())
+        .maybe_attr(
+            "coverArt",
+            albums
+                .first()
+                .map(|album| SubsonicID::Album(artist.artist_id, album.album_id).to_string()),
+        )
+}
+
+/// `tracks` can be passed when the caller already has them, otherwise they are looked up
+fn album_node(
+    artist: &ArtistInfo,
+    album: &AlbumInfo,
+    tracks: Option<&Vec<FullTrackMetadata>>,
+    ds: &Datastore,
+) -> Node {
+    let song_count = match tracks {
+        Some(tracks) => tracks.len(),
+        None => ds
+            .list_artist_album_tracks(artist.artist_id, album.album_id)
+            .len(),
+    };
+    let album_id = SubsonicID::Album(artist.artist_id, album.album_id).to_string();
+    Node::new("album")
+        .attr("id", album_id.clone())
+        .attr("name", album.album_name.clone())
+        .attr("artist", artist.artist_name.clone())
+        .attr("artistId", SubsonicID::Artist(artist.artist_id).to_string())
+        .attr("coverArt", album_id)
+        .attr("songCount", song_count)
+        .attr("duration", album.total_duration.as_secs())
+        .maybe_attr("year", non_zero(album.start_date))
+        .maybe_attr("genre", album.genres.iter().next().cloned())
+        .attr("created", timestamp(album.last_modified))
+}
+
+fn song_node(name: &str, track: &FullTrackMetadata) -> Node {
+    let album_id = SubsonicID::Album(track.album_artist_id, track.album_id).to_string();
+    Node::new(name)
+        .attr("id", SubsonicID::from_track(track).to_string())
+        .attr("parent", album_id.clone())
+        .attr("isDir", false)
+        .attr("title", track.title.clone())
+        .attr("album", track.album.clone())
+        .attr("artist", track.track_artist.clone())
+        .attr("track", track.track)
+        .attr("discNumber", track.disc)
+        .maybe_attr("year", non_zero(track.date_number))
+        .maybe_attr(
+            "genre",
+            match track.genre.is_empty() {
+                true => None,
+                false => Some(track.genre.clone()),
+            },
+        )
+        .attr("coverArt", album_id.clone())
+        .attr(
+            "size",
+            fs::metadata(&track.path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0),
+        )
+        .attr(
+            "contentType",
+            contenttype::audio_content_type_from_ext(&track.ext),
+        )
+        .attr("suffix", track.ext.clone())
+        .attr("duration", track.duration.as_secs())
+        .attr("path", track.relative_path.to_string_lossy().to_string())
+        .attr("albumId", album_id)
+        .attr(
+            "artistId",
+            SubsonicID::Artist(track.album_artist_id).to_string(),
+        )
+        .attr("type", "music")
+        .attr("isVideo", false)
+        .attr("created", timestamp(track.last_modified))
+}
+
+fn playlist_node(
+    playlist: &crate::playlists::Playlist,
+    tracks: &[FullTrackMetadata],
+    last_modified: SystemTime,
+    username: &str,
+) -> Node {
+    Node::new("playlist")
+        .attr("id", SubsonicID::Playlist(playlist.id).to_string())
+        .attr("name", playlist.name.clone())
+        .attr("owner", username)
+        .attr("public", false)
+        .attr("songCount", tracks.len())
+        .attr(
+            "duration",
+            tracks
+                .iter()
+                .map(|track| track.duration.as_secs())
+                .sum::<u64>(),
+        )
+        .attr("created", timestamp(last_modified))
+        .attr("changed", timestamp(last_modified))
+}
+
+/// The playlist's tracks that are still in the library
+fn playlist_tracks(
+    track_ids: &[crate::model::TrackUniqueIdentifier],
+    ds: &Datastore,
+) -> Vec<FullTrackMetadata> {
+    track_ids
+        .iter()
+        .filter_map(|track_id| {
+            ds.find_artist_album_track(
+                track_id.artist_id,
+                track_id.album_id,
+                track_id.disc_no,
+                track_id.track_no,
+            )
+        })
+        .collect()
+}
+
+fn required_id(query: &Query) -> Result<SubsonicID, SubsonicError> {
+    let raw_id = query.get("id").ok_or(SubsonicError::MissingParam("id"))?;
+    SubsonicID::parse(raw_id).ok_or_else(|| not_found(query))
+}
+
+fn required_track(query: &Query, ds: &Datastore) -> Result<FullTrackMetadata, SubsonicError> {
+    match required_id(query)? {
+        SubsonicID::Track(artist_id, album_id, disc_no, track_no) => ds
+            .find_artist_album_track(artist_id, album_id, disc_no, track_no)
+            .ok_or_else(|| not_found(query)),
+        _ => Err(not_found(query)),
+    }
+}
+
+fn count_param(query: &Query, name: &'static str, default: usize) -> Result<usize, SubsonicError> {
+    match query.get(name) {
+        Some(count) => count
+            .parse::<usize>()
+            .map_err(|_| SubsonicError::Generic(format!("invalid {}: {}", name, count))),
+        None => Ok(default),
+    }
+}
+
+fn not_found(query: &Query) -> SubsonicError {
+    SubsonicError::NotFound(format!(
+        "no item with id: {}",
+        query.get("id").unwrap_or("")
+    ))
+}
+
+fn non_zero(value: u32) -> Option<u32> {
+    match value {
+        0 => None,
+        value => Some(value),
+    }
+}
+
+fn timestamp(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
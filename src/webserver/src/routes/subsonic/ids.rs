@@ -0,0 +1,65 @@
+use crate::model::{FullTrackMetadata, ID};
+
+/// Subsonic ids are opaque strings, so each kind of id is prefixed
+/// and carries the same musiqlibrary ids as the webserver paths
+#[derive(Debug, Clone, Copy)]
+pub enum SubsonicID {
+    Artist(ID),
+    Album(ID, ID),
+    Track(ID, ID, u64, u64),
+    Playlist(u32),
+}
+
+impl SubsonicID {
+    pub fn from_track(track: &FullTrackMetadata) -> Self {
+        SubsonicID::Track(
+            track.album_artist_id,
+            track.album_id,
+            track.disc,
+            track.track,
+        )
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (kind, rest) = raw.split_once('-')?;
+        let parts: Vec<&str> = rest.split('-').collect();
+        match (kind, parts.as_slice()) {
+            ("ar", [artist_id]) => Some(SubsonicID::Artist(parse_id(artist_id)?)),
+            ("al", [artist_id, album_id]) => {
+                Some(SubsonicID::Album(parse_id(artist_id)?, parse_id(album_id)?))
+            }
+            ("tr", [artist_id, album_id, disc_no, track_no]) => Some(SubsonicID::Track(
+                parse_id(artist_id)?,
+                parse_id(album_id)?,
+                disc_no.parse().ok()?,
+                track_no.parse().ok()?,
+            )),
+            ("pl", [playlist_id]) => Some(SubsonicID::Playlist(playlist_id.parse().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SubsonicID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubsonicID::Artist(artist_id) => write!(f, "ar-{}", artist_id.hashed()),
+            SubsonicID::Album(artist_id, album_id) => {
+                write!(f, "al-{}-{}", artist_id.hashed(), album_id.hashed())
+            }
+            SubsonicID::Track(artist_id, album_id, disc_no, track_no) => write!(
+                f,
+                "tr-{}-{}-{}-{}",
+                artist_id.hashed(),
+                album_id.hashed(),
+                disc_no,
+                track_no
+            ),
+            SubsonicID::Playlist(playlist_id) => write!(f, "pl-{}", playlist_id),
+        }
+    }
+}
+
+fn parse_id(raw: &str) -> Option<ID> {
+    raw.parse::<u64>().ok().map(ID::from_u64)
+}
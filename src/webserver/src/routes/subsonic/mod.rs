@@ -0,0 +1,182 @@
+mod endpoints;
+mod ids;
+mod response;
+
+use hyper::{header, Body, HeaderMap, Response};
+
+use crate::ds::Datastore;
+
+use super::api::query::Query;
+use super::auth::{self, AuthenticatedUser};
+
+use response::{Format, Node};
+
+/// Errors as Subsonic reports them, by code in the response body
+pub enum SubsonicError {
+    Generic(String),
+    MissingParam(&'static str),
+    WrongCredentials,
+    NotFound(String),
+}
+
+impl SubsonicError {
+    fn code(&self) -> u32 {
+        match self {
+            SubsonicError::Generic(_) => 0,
+            SubsonicError::MissingParam(_) => 10,
+            SubsonicError::WrongCredentials => 40,
+            SubsonicError::NotFound(_) => 70,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            SubsonicError::Generic(message) => message.clone(),
+            SubsonicError::MissingParam(name) => format!("required parameter is missing: {}", name),
+            SubsonicError::WrongCredentials => "wrong username or password".to_string(),
+            SubsonicError::NotFound(message) => message.clone(),
+        }
+    }
+}
+
+/// Serve the `/rest/<method>` Subsonic endpoints, which clients may call with a `.view` suffix
+pub async fn handle_subsonic(
+    method_name: &str,
+    query: &Query,
+    request_headers: &HeaderMap,
+    ds: Datastore,
+) -> Response<Body> {
+    let method_name = method_name.strip_suffix(".view").unwrap_or(method_name);
+    let format = Format::from_param(query.get("f"));
+
+    // clients ask for this before logging in, to see what the server supports
+    if method_name == "getOpenSubsonicExtensions" {
+        return render_response(
+            response::ok(None).list(
+                "openSubsonicExtensions",
+                endpoints::open_subsonic_extensions(),
+            ),
+            format,
+        );
+    }
+
+    let username = match authenticate(query, &ds) {
+        Ok(username) => username,
+        Err(e) => return subsonic_response(Err(e), format),
+    };
+
+    let mut resp = handle_method(method_name, &username, query, request_headers, format, ds).await;
+    resp.extensions_mut().insert(AuthenticatedUser(username));
+    resp
+}
+
+async fn handle_method(
+    method_name: &str,
+    username: &str,
+    query: &Query,
+    request_headers: &HeaderMap,
+    format: Format,
+    ds: Datastore,
+) -> Response<Body> {
+    let result = match method_name {
+        "ping" => Ok(None),
+        "getLicense" => Ok(Some(endpoints::license())),
+        "getMusicFolders" => Ok(Some(endpoints::music_folders())),
+        "getIndexes" => Ok(Some(endpoints::artist_indexes("indexes", &ds))),
+        "getArtists" => Ok(Some(endpoints::artist_indexes("artists", &ds))),
+        "getArtist" => endpoints::artist(query, &ds).map(Some),
+        "getAlbum" => endpoints::album(query, &ds).map(Some),
+        "getSong" => endpoints::song(query, &ds).map(Some),
+        "search3" => endpoints::search(query, &ds).map(Some),
+        "getPlaylists" => Ok(Some(endpoints::playlists(username, &ds))),
+        "getPlaylist" => endpoints::playlist(query, username, &ds).map(Some),
+        "scrobble" => endpoints::scrobble(query, username, &ds).map(|_| None),
+        "getCoverArt" => {
+            return match endpoints::cover_art(query, request_headers, &ds) {
+                Ok(resp) => resp,
+                Err(e) => subsonic_response(Err(e), format),
+            }
+        }
+        "stream" => {
+            return match endpoints::stream(query, request_headers, &ds).await {
+                Ok(resp) => resp,
+                Err(e) => subsonic_response(Err(e), format),
+            }
+        }
+        "download" => {
+            return match endpoints::download(query, request_headers, &ds).await {
+                Ok(resp) => resp,
+                Err(e) => subsonic_response(Err(e), format),
+            }
+        }
+        _ => Err(SubsonicError::NotFound(format!(
+            "unsupported method: {}",
+            method_name
+        ))),
+    };
+    subsonic_response(result, format)
+}
+
+/// Check the `u` user against the config, either by token (`t`, the md5 of the
+/// password and the salt `s`) or by plain password (`p`, maybe hex encoded)
+fn authenticate(query: &Query, ds: &Datastore) -> Result<String, SubsonicError> {
+    let username = query.get("u").ok_or(SubsonicError::MissingParam("u"))?;
+    let user = ds
+        .get_config()
+        .get_user(username)
+        .ok_or(SubsonicError::WrongCredentials)?;
+
+    let valid = match (query.get("t"), query.get("s"), query.get("p")) {
+        (Some(token), Some(salt), _) => {
+            let expected = format!("{:x}", md5::compute(format!("{}{}", user.password, salt)));
+            auth::constant_time_eq(&expected, &token.to_lowercase())
+        }
+        (_, _, Some(password)) => match password.strip_prefix("enc:") {
+            Some(hex) => match decode_hex(hex) {
+                Some(decoded) => auth::constant_time_eq(&user.password, &decoded),
+                None => false,
+            },
+            None => auth::constant_time_eq(&user.password, password),
+        },
+        (Some(_), None, None) => return Err(SubsonicError::MissingParam("s")),
+        _ => return Err(SubsonicError::MissingParam("t")),
+    };
+    match valid {
+        true => Ok(user.username.clone()),
+        false => Err(SubsonicError::WrongCredentials),
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<String> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Subsonic errors are still sent with a 200, the failure is in the body
+fn subsonic_response(
+    result: Result<Option<Node>, SubsonicError>,
+    format: Format,
+) -> Response<Body> {
+    render_response(
+        match result {
+            Ok(payload) => response::ok(payload),
+            Err(e) => response::failed(e.code(), &e.message()),
+        },
+        format,
+    )
+}
+
+fn render_response(root: Node, format: Format) -> Response<Body> {
+    let mut resp = Response::new(Body::from(response::render(&root, format)));
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static(format.content_type()),
+    );
+    resp
+}
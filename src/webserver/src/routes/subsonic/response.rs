@@ -0,0 +1,175 @@
+use serde_json::{Map, Value};
+
+pub const API_VERSION: &str = "1.16.1";
+
+const XML_NAMESPACE: &str = "http://subsonic.org/restapi";
+
+/// A Subsonic response element, which is rendered either as xml (attributes and
+/// child elements) or as json (fields, with repeated children as arrays)
+pub struct Node {
+    name: String,
+    attrs: Vec<(String, Value)>,
+    children: Vec<Child>,
+}
+
+enum Child {
+    Single(Node),
+    /// Always a json array, even with only one item, as Subsonic clients expect
+    List(String, Vec<Node>),
+}
+
+impl Node {
+    pub fn new<S: ToString>(name: S) -> Self {
+        Node {
+            name: name.to_string(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn attr<S: ToString, V: Into<Value>>(mut self, key: S, value: V) -> Self {
+        self.attrs.push((key.to_string(), value.into()));
+        self
+    }
+
+    pub fn maybe_attr<S: ToString, V: Into<Value>>(self, key: S, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.attr(key, value),
+            None => self,
+        }
+    }
+
+    pub fn child(mut self, child: Node) -> Self {
+        self.children.push(Child::Single(child));
+        self
+    }
+
+    pub fn list<S: ToString>(mut self, name: S, items: Vec<Node>) -> Self {
+        self.children.push(Child::List(name.to_string(), items));
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut fields = Map::new();
+        // the namespace only means something in xml
+        for (key, value) in self.attrs.iter().filter(|(key, _)| key != "xmlns") {
+            fields.insert(key.clone(), value.clone());
+        }
+        for child in self.children.iter() {
+            match child {
+                Child::Single(node) => {
+                    fields.insert(node.name.clone(), node.to_json());
+                }
+                Child::List(name, nodes) => {
+                    fields.insert(
+                        name.clone(),
+                        Value::Array(nodes.iter().map(|node| node.to_json()).collect()),
+                    );
+                }
+            }
+        }
+        Value::Object(fields)
+    }
+
+    fn write_xml(&self, out: &mut String) {
+        out.push('<');
+        out.push_str(&self.name);
+        for (key, value) in self.attrs.iter() {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            out.push_str(&format!(" {}=\"{}\"", key, xml_escape(&value)));
+        }
+
+        let children: Vec<&Node> = self
+            .children
+            .iter()
+            .flat_map(|child| match child {
+                Child::Single(node) => vec![node],
+                Child::List(_, nodes) => nodes.iter().collect(),
+            })
+            .collect();
+        if children.is_empty() {
+            out.push_str("/>");
+            return;
+        }
+        out.push('>');
+        for child in children.into_iter() {
+            child.write_xml(out);
+        }
+        out.push_str(&format!("</{}>", self.name));
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Xml,
+    Json,
+}
+
+impl Format {
+    pub fn from_param(param: Option<&str>) -> Self {
+        match param {
+            Some("json") => Format::Json,
+            _ => Format::Xml,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Xml => "text/xml; charset=utf-8",
+            Format::Json => "application/json",
+        }
+    }
+}
+
+pub fn ok(payload: Option<Node>) -> Node {
+    let root = root("ok");
+    match payload {
+        Some(payload) => root.child(payload),
+        None => root,
+    }
+}
+
+pub fn failed(code: u32, message: &str) -> Node {
+    root("failed").child(
+        Node::new("error")
+            .attr("code", code)
+            .attr("message", message),
+    )
+}
+
+pub fn render(root: &Node, format: Format) -> String {
+    match format {
+        Format::Json => {
+            let mut wrapper = Map::new();
+            wrapper.insert("subsonic-response".to_string(), root.to_json());
+            Value::Object(wrapper).to_string()
+        }
+        Format::Xml => {
+            let mut out = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string();
+            root.write_xml(&mut out);
+            out
+        }
+    }
+}
+
+fn root(status: &str) -> Node {
+    Node::new("subsonic-response")
+        .attr("xmlns", XML_NAMESPACE)
+        .attr("status", status)
+        .attr("version", API_VERSION)
+        .attr("type", "musiq")
+        .attr("serverVersion", env!("CARGO_PKG_VERSION"))
+        .attr("openSubsonic", true)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,52 @@
+use std::fs;
+use std::io;
+use std::sync::Arc;
+
+use tokio_rustls::rustls;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::TlsConfig;
+
+/// Set up https from the configured PEM files, which have to be readable at startup
+pub fn load_acceptor(tls_config: &TlsConfig) -> TlsAcceptor {
+    let cert_file = fs::File::open(&tls_config.cert_path).unwrap_or_else(|e| {
+        panic!(
+            "could not open certificate {:?}: {}",
+            tls_config.cert_path, e
+        )
+    });
+    let certs = rustls_pemfile::certs(&mut io::BufReader::new(cert_file))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|e| {
+            panic!(
+                "could not read certificate {:?}: {}",
+                tls_config.cert_path, e
+            )
+        });
+
+    let key_file = fs::File::open(&tls_config.key_path).unwrap_or_else(|e| {
+        panic!(
+            "could not open private key {:?}: {}",
+            tls_config.key_path, e
+        )
+    });
+    let key = rustls_pemfile::private_key(&mut io::BufReader::new(key_file))
+        .unwrap_or_else(|e| {
+            panic!(
+                "could not read private key {:?}: {}",
+                tls_config.key_path, e
+            )
+        })
+        .unwrap_or_else(|| panic!("no private key in {:?}", tls_config.key_path));
+
+    let server_config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .unwrap_or_else(|e| panic!("invalid certificate or key: {}", e));
+
+    TlsAcceptor::from(Arc::new(server_config))
+}
@@ -0,0 +1,482 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::{OnceCell, Semaphore};
+use tokio::task;
+
+use crate::config::TranscodingConfig;
+use crate::model::FullTrackMetadata;
+
+pub const DEFAULT_BITRATE: u32 = 128;
+pub const MIN_BITRATE: u32 = 32;
+pub const MAX_BITRATE: u32 = 320;
+
+/// The bitrates (in kbit/s) tracks are encoded at, so that each track only ever has a few
+/// cache entries; a requested bitrate snaps down to the closest one
+pub const BITRATE_LADDER: [u32; 6] = [64, 96, 128, 192, 256, 320];
+
+/// How many encodes run at once, the rest wait their turn
+const MAX_CONCURRENT_ENCODES: usize = 2;
+
+/// How big the cache gets before the oldest encodes are removed, when none is configured
+const DEFAULT_CACHE_MAX_MB: u64 = 2048;
+
+/// What the encoder is run with when none is configured; the codec follows from
+/// the extension of `{output}`
+const DEFAULT_ENCODER_COMMAND: &[&str] = &[
+    "ffmpeg",
+    "-nostdin",
+    "-loglevel",
+    "error",
+    "-y",
+    "-i",
+    "{input}",
+    "-vn",
+    "-b:a",
+    "{bitrate}k",
+    "{output}",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioFormat {
+    Opus,
+    Mp3,
+    Aac,
+}
+
+impl AudioFormat {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "opus" => Some(AudioFormat::Opus),
+            "mp3" => Some(AudioFormat::Mp3),
+            "aac" | "m4a" => Some(AudioFormat::Aac),
+            _ => None,
+        }
+    }
+
+    pub fn ext(&self) -> &'static str {
+        match self {
+            AudioFormat::Opus => "opus",
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Aac => "m4a",
+        }
+    }
+}
+
+/// How a track should be re-encoded before it is sent
+#[derive(Debug, Clone, Copy)]
+pub struct TranscodeProfile {
+    pub format: AudioFormat,
+    /// In kbit/s, one of `BITRATE_LADDER`
+    pub bitrate: u32,
+}
+
+impl TranscodeProfile {
+    /// The profile for a requested bitrate, snapped to `BITRATE_LADDER`
+    pub fn new(format: AudioFormat, requested_bitrate: u32) -> Self {
+        let bitrate = BITRATE_LADDER
+            .iter()
+            .rev()
+            .find(|bitrate| **bitrate <= requested_bitrate)
+            .copied()
+            .unwrap_or(BITRATE_LADDER[0]);
+        TranscodeProfile { format, bitrate }
+    }
+
+    fn cache_key(&self, track: &FullTrackMetadata) -> String {
+        let mtime = track
+            .last_modified
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        format!(
+            "{:x}",
+            md5::compute(format!(
+                "{}|{}.{}|{}|{}",
+                track.path.to_string_lossy(),
+                mtime.as_secs(),
+                mtime.subsec_nanos(),
+                self.format.ext(),
+                self.bitrate,
+            ))
+        )
+    }
+}
+
+/// The same track in the compressed mirror of the library, if it is there in the wanted format
+pub fn find_compressed(
+    compressed_library_path: &Path,
+    track: &FullTrackMetadata,
+    profile: &TranscodeProfile,
+) -> Option<(PathBuf, SystemTime)> {
+    let compressed_path = compressed_library_path
+        .join(&track.relative_path)
+        .with_extension(profile.format.ext());
+    let last_modified = fs::metadata(&compressed_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()?;
+    Some((compressed_path, last_modified))
+}
+
+type EncodeResult = Result<(PathBuf, SystemTime), String>;
+
+/// Runs the encodes, a few at a time, with requests for the same cache entry sharing one encode
+#[derive(Clone)]
+pub struct Transcoder {
+    config: Arc<TranscodingConfig>,
+    cache_path: PathBuf,
+    permits: Arc<Semaphore>,
+    in_flight: Arc<Mutex<HashMap<String, Arc<OnceCell<EncodeResult>>>>>,
+}
+
+impl Transcoder {
+    pub fn new(config: TranscodingConfig, cache_path: PathBuf) -> Self {
+        Transcoder {
+            config: Arc::new(config),
+            cache_path,
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_ENCODES)),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the transcoded file for the track from the cache, running the encoder first
+    /// if it is not there yet; a changed source file gets a new cache entry
+    pub async fn transcode(
+        &self,
+        track: &FullTrackMetadata,
+        profile: &TranscodeProfile,
+    ) -> EncodeResult {
+        let key = profile.cache_key(track);
+        let cached_path = self
+            .cache_path
+            .join(format!("{}.{}", key, profile.format.ext()));
+
+        if let Some(found) = cached(&cached_path) {
+            return Ok(found);
+        }
+
+        let encode = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+
+        let result = encode
+            .get_or_init(|| async {
+                let _permit = self
+                    .permits
+                    .acquire()
+                    .await
+                    .map_err(|e| format!("could not wait for an encoder: {}", e))?;
+
+                // an encode that finished while this one was starting is already in the cache
+                if let Some(found) = cached(&cached_path) {
+                    return Ok(found);
+                }
+
+                let config = self.config.clone();
+                let cache_path = self.cache_path.clone();
+                let track = track.clone();
+                let profile = *profile;
+                let key = key.clone();
+                task::spawn_blocking(move || {
+                    let result = encode_into_cache(&config, &cache_path, &track, &profile, &key);
+                    let max_bytes =
+                        config.cache_max_mb.unwrap_or(DEFAULT_CACHE_MAX_MB) * 1024 * 1024;
+                    evict_oldest(&cache_path, max_bytes);
+                    result
+                })
+                .await
+                .map_err(|e| format!("the encoder task failed: {}", e))?
+            })
+            .await
+            .clone();
+
+        // done, so the next request goes to the cache (unless a newer encode took the key)
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(current) = in_flight.get(&key) {
+            if Arc::ptr_eq(current, &encode) {
+                in_flight.remove(&key);
+            }
+        }
+
+        result
+    }
+}
+
+fn cached(cached_path: &Path) -> Option<(PathBuf, SystemTime)> {
+    let last_modified = fs::metadata(cached_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()?;
+    Some((cached_path.to_path_buf(), last_modified))
+}
+
+fn encode_into_cache(
+    config: &TranscodingConfig,
+    cache_path: &Path,
+    track: &FullTrackMetadata,
+    profile: &TranscodeProfile,
+    key: &str,
+) -> EncodeResult {
+    let cached_path = cache_path.join(format!("{}.{}", key, profile.format.ext()));
+
+    fs::create_dir_all(cache_path)
+        .map_err(|e| format!("could not create cache dir {:?}: {}", cache_path, e))?;
+
+    // encode next to the cache entry and move it in place once done, so that a
+    // half-written file is never served
+    let partial_path = cache_path.join(format!(
+        "{}.{}.partial.{}",
+        key,
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+        profile.format.ext()
+    ));
+    let result = run_encoder(config, &track.path, &partial_path, profile)
+        .and_then(|_| {
+            fs::rename(&partial_path, &cached_path)
+                .map_err(|e| format!("could not move {:?} into cache: {}", partial_path, e))
+        })
+        .and_then(|_| {
+            fs::metadata(&cached_path)
+                .and_then(|metadata| metadata.modified())
+                .map_err(|e| format!("could not read {:?}: {}", cached_path, e))
+        });
+    match result {
+        Ok(last_modified) => Ok((cached_path, last_modified)),
+        Err(e) => {
+            let _ = fs::remove_file(&partial_path);
+            Err(e)
+        }
+    }
+}
+
+/// Remove the oldest encodes until the cache fits in `max_bytes`
+fn evict_oldest(cache_path: &Path, max_bytes: u64) {
+    let entries = match fs::read_dir(cache_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            println!("could not list cache dir {:?}: {}", cache_path, e);
+            return;
+        }
+    };
+    let mut cached_files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().contains(".partial."))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            match metadata.is_file() {
+                true => Some((metadata.modified().ok()?, metadata.len(), entry.path())),
+                false => None,
+            }
+        })
+        .collect();
+
+    let mut total_bytes: u64 = cached_files.iter().map(|(_, len, _)| len).sum();
+    cached_files.sort();
+    for (_, len, path) in cached_files.into_iter() {
+        if total_bytes <= max_bytes {
+            break;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => total_bytes -= len,
+            Err(e) => println!("could not remove {:?} from the cache: {}", path, e),
+        }
+    }
+}
+
+fn run_encoder(
+    config: &TranscodingConfig,
+    input: &Path,
+    output: &Path,
+    profile: &TranscodeProfile,
+) -> Result<(), String> {
+    let template: Vec<String> = match config.encoder_command {
+        Some(ref command) => command.clone(),
+        None => DEFAULT_ENCODER_COMMAND
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect(),
+    };
+    let placeholders = [
+        ("{input}", input.to_string_lossy().to_string()),
+        ("{output}", output.to_string_lossy().to_string()),
+        ("{bitrate}", profile.bitrate.to_string()),
+        ("{format}", profile.format.ext().to_string()),
+    ];
+    let args: Vec<String> = template
+        .iter()
+        .map(|arg| fill_placeholders(arg, &placeholders))
+        .collect();
+
+    let (program, program_args) = args
+        .split_first()
+        .ok_or("the encoder command is empty".to_string())?;
+
+    println!("transcoding {:?} to {:?}", input, output);
+    let result = process::Command::new(program)
+        .args(program_args)
+        .stdin(process::Stdio::null())
+        .output()
+        .map_err(|e| format!("could not run encoder {}: {}", program, e))?;
+
+    match result.status.success() {
+        true => Ok(()),
+        false => Err(format!(
+            "encoder failed on {:?} ({}): {}",
+            input,
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        )),
+    }
+}
+
+/// Fill in the placeholders in one pass, so that what is filled in (like a file name with
+/// `{output}` in it) is never filled in again
+fn fill_placeholders(arg: &str, placeholders: &[(&str, String)]) -> String {
+    let mut filled = String::new();
+    let mut rest = arg;
+    while !rest.is_empty() {
+        match placeholders
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                filled.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                let next = rest.chars().next().unwrap();
+                filled.push(next);
+                rest = &rest[next.len_utf8()..];
+            }
+        }
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_bitrates_down_to_the_ladder() {
+        let bitrates: Vec<u32> = [32, 64, 100, 128, 200, 319, 320]
+            .iter()
+            .map(|bitrate| TranscodeProfile::new(AudioFormat::Mp3, *bitrate).bitrate)
+            .collect();
+        assert_eq!(bitrates, vec![64, 64, 96, 128, 192, 256, 320]);
+    }
+
+    #[test]
+    fn fills_each_placeholder_once() {
+        let placeholders = [
+            ("{input}", "/music/{output} {format}.flac".to_string()),
+            ("{output}", "/cache/out.mp3".to_string()),
+            ("{format}", "mp3".to_string()),
+        ];
+        assert_eq!(
+            fill_placeholders("{input}", &placeholders),
+            "/music/{output} {format}.flac"
+        );
+        assert_eq!(
+            fill_placeholders("-f={format} {output}", &placeholders),
+            "-f=mp3 /cache/out.mp3"
+        );
+        assert_eq!(fill_placeholders("{unknown}", &placeholders), "{unknown}");
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "musiqwebserver-transcode-test-{}-{}",
+            process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn track_at(path: PathBuf) -> FullTrackMetadata {
+        FullTrackMetadata {
+            title: "Track".to_string(),
+            track: 1,
+            raw_track: Some(1),
+            disc: 1,
+            raw_disc: Some(1),
+            disc_total: None,
+            album: "Album".to_string(),
+            raw_album: Some("Album".to_string()),
+            album_id: musiqlibrary::ID::new(&"Album"),
+            album_artist: "Artist".to_string(),
+            album_artist_id: musiqlibrary::ID::new(&"Artist"),
+            track_artist: "Artist".to_string(),
+            track_artist_id: musiqlibrary::ID::new(&"Artist"),
+            genre: "Genre".to_string(),
+            date_number: 2000,
+            raw_date: "2000".to_string(),
+            duration: std::time::Duration::from_secs(60),
+            relative_path: PathBuf::from("track.flac"),
+            last_modified: fs::metadata(&path).unwrap().modified().unwrap(),
+            path,
+            ext: "flac".to_string(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn requests_for_the_same_entry_share_one_encode() {
+        let dir = scratch_dir("shared");
+        let input = dir.join("track.flac");
+        fs::write(&input, b"audio").unwrap();
+        let runs = dir.join("runs");
+        let config = TranscodingConfig {
+            encoder_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!(
+                    "echo run >> '{}'; sleep 0.3; cp \"$0\" \"$1\"",
+                    runs.to_string_lossy()
+                ),
+                "{input}".to_string(),
+                "{output}".to_string(),
+            ]),
+            ..TranscodingConfig::default()
+        };
+        let transcoder = Transcoder::new(config, dir.join("cache"));
+        let track = track_at(input);
+        let profile = TranscodeProfile::new(AudioFormat::Mp3, 128);
+
+        let (first, second) = tokio::join!(
+            transcoder.transcode(&track, &profile),
+            transcoder.transcode(&track, &profile)
+        );
+
+        assert_eq!(first.unwrap().0, second.unwrap().0);
+        assert_eq!(fs::read_to_string(&runs).unwrap().lines().count(), 1);
+        assert!(transcoder.in_flight.lock().unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn evicts_the_oldest_entries_first() {
+        let dir = scratch_dir("evict");
+        for name in ["old.mp3", "middle.mp3", "new.mp3"] {
+            fs::write(dir.join(name), [0u8; 100]).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        evict_oldest(&dir, 200);
+
+        assert!(!dir.join("old.mp3").exists());
+        assert!(dir.join("middle.mp3").exists());
+        assert!(dir.join("new.mp3").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
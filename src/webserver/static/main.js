@@ -1,68 +1,201 @@
 var play_history;
 var play_queue;
+var current_song;
+var client_id;
 var audio;
 var audio_info;
 var playlist_list;
 
+const API_PREFIX = '/api/v1';
+const MAX_PLAY_HISTORY = 200;
+
 function play_songs_next_in_play_queue(songs) {
     for (const song of songs.reverse()) {
         play_queue.unshift(song)
     }
+    save_play_queue();
 }
 
 function append_songs_to_play_queue(songs) {
     for (const song of songs) {
         play_queue.push(song)
     }
+    save_play_queue();
 }
 
-function play_next_song_in_queue() {
-    const finished_src = audio.getAttribute('src');
-    const finished_type = audio.getAttribute('type');
-    const finished_title = audio_info.textContent;
-    if (finished_src !== null) {
-        play_history.push({src: finished_src, title: finished_title, contenttype: finished_type });
+function clear_play_queue() {
+    play_queue = [];
+    save_play_queue();
+}
+
+function play_song_from_queue(index) {
+    const skipped = play_queue.splice(0, index);
+    if (current_song !== undefined) {
+        play_history.push(current_song);
     }
-    const next = play_queue.shift();
-    if (next !== undefined) {
-        audio.setAttribute('src', next.src);
-        audio.setAttribute('type', next.contenttype);
-        audio.setAttribute('preload', 'auto');
-        audio_info.textContent = next.title;
-        audio.load();
-        audio.play();
+    for (const song of skipped) {
+        play_history.push(song);
     }
+    load_song(play_queue.shift(), true);
+}
+
+function remove_song_from_queue(index) {
+    play_queue.splice(index, 1);
+    save_play_queue();
+}
+
+function play_next_song_in_queue() {
+    if (current_song !== undefined) {
+        play_history.push(current_song);
+    }
+    load_song(play_queue.shift(), true);
 };
 
 function play_prev_song_in_history() {
-    const old_src = audio.getAttribute('src');
-    const old_type = audio.getAttribute('type');
-    const old_title = audio_info.textContent;
-    if (old_src !== null) {
-        play_queue.unshift({src: old_src, title: old_title, contenttype: old_type });
+    if (current_song !== undefined) {
+        play_queue.unshift(current_song);
     }
-    const next = play_history.pop();
-    if (next !== undefined) {
-        audio.setAttribute('src', next.src);
-        audio.setAttribute('type', next.contenttype);
+    load_song(play_history.pop(), true);
+};
+
+function load_song(song, autoplay) {
+    current_song = song;
+    if (song !== undefined) {
+        audio.setAttribute('src', song.src);
+        audio.setAttribute('type', song.contenttype);
         audio.setAttribute('preload', 'auto');
-        audio_info.textContent = next.title;
+        audio_info.textContent = song.title;
         audio.load();
-        audio.play();
+        if (autoplay) {
+            audio.play();
+        }
+        set_media_session_metadata(song);
     }
-};
+    save_play_queue();
+}
+
+function set_media_session_metadata(song) {
+    if (!('mediaSession' in navigator)) {
+        return;
+    }
+    navigator.mediaSession.metadata = new MediaMetadata({
+        title: song.title,
+        artist: song.artist,
+        album: song.album,
+        artwork: [{ src: song.cover, type: 'image/jpeg' }],
+    });
+}
+
+function song_key(song) {
+    return { artist_id: song.artist_id, album_id: song.album_id, disc: song.disc, track: song.track };
+}
+
+function song_from_api_track(track) {
+    return {
+        artist_id: track.album_artist_id,
+        album_id: track.album_id,
+        disc: track.disc,
+        track: track.track,
+        src: track.audio_url,
+        title: track.title,
+        artist: track.track_artist,
+        album: track.album,
+        cover: track.cover_url,
+        contenttype: track.content_type,
+    };
+}
+
+function record_listen(song) {
+    fetch(`${API_PREFIX}/clients/${client_id}/listens`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(song_key(song)),
+    }).catch((error) => console.log('could not record listen', error));
+}
+
+function save_play_queue() {
+    render_play_queue();
+    play_history = play_history.slice(-MAX_PLAY_HISTORY);
+    fetch(`${API_PREFIX}/clients/${client_id}/queue`, {
+        method: 'PUT',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({
+            current: current_song === undefined ? null : song_key(current_song),
+            queue: play_queue.map(song_key),
+            history: play_history.map(song_key),
+        }),
+    }).catch((error) => console.log('could not save play queue', error));
+}
+
+function restore_play_queue() {
+    fetch(`${API_PREFIX}/clients/${client_id}/queue`)
+        .then((response) => response.json())
+        .then((saved) => {
+            play_queue = saved.queue.map(song_from_api_track);
+            play_history = saved.history.map(song_from_api_track);
+            if (saved.current !== null) {
+                load_song(song_from_api_track(saved.current), false);
+            } else {
+                render_play_queue();
+            }
+        })
+        .catch((error) => console.log('could not restore play queue', error));
+}
+
+function render_play_queue() {
+    playlist_list.replaceChildren();
+    play_queue.forEach((song, index) => {
+        const entry = document.createElement('div');
+
+        const play_link = document.createElement('a');
+        play_link.setAttribute('href', `javascript:{ play_song_from_queue(${index}); }`);
+        play_link.textContent = song.title;
+
+        const remove_link = document.createElement('a');
+        remove_link.setAttribute('href', `javascript:{ remove_song_from_queue(${index}); }`);
+        remove_link.textContent = ' x';
+
+        entry.appendChild(play_link);
+        entry.appendChild(remove_link);
+        playlist_list.appendChild(entry);
+    });
+}
+
+function get_client_id() {
+    let id = window.localStorage.getItem('client_id');
+    if (id === null) {
+        id = Array.from(crypto.getRandomValues(new Uint8Array(16)))
+            .map((byte) => byte.toString(16).padStart(2, '0'))
+            .join('');
+        window.localStorage.setItem('client_id', id);
+    }
+    return id;
+}
 
 window.onload = () => {
     play_history = [];
     play_queue = [];
+    client_id = get_client_id();
     audio = document.getElementById('main-playback');
     audio_info = document.getElementById('main-playback-info');
     audio.addEventListener('ended', (event) => {
         console.log("just got this event", event)
+        if (current_song !== undefined) {
+            record_listen(current_song);
+        }
         play_next_song_in_queue();
     }, false);
 
     playlist_list = document.getElementById('playlist-list');
 
+    if ('mediaSession' in navigator) {
+        navigator.mediaSession.setActionHandler('play', () => audio.play());
+        navigator.mediaSession.setActionHandler('pause', () => audio.pause());
+        navigator.mediaSession.setActionHandler('nexttrack', () => play_next_song_in_queue());
+        navigator.mediaSession.setActionHandler('previoustrack', () => play_prev_song_in_history());
+    }
+
+    restore_play_queue();
+
     console.log('loaded');
 };